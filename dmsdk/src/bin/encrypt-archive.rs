@@ -0,0 +1,234 @@
+//! Encrypts a bundled Defold resource archive for use with `dmresource::set_cipher()`.
+//!
+//! ```text
+//! encrypt-archive --key <key> <game.arci>
+//! ```
+//!
+//! The index (`.arci`) and its data file (`.arcd`, next to it) are rewritten in place. Every entry is encrypted
+//! with [`Cipher`] and flagged as encrypted, including the ones the build tools already encrypted
+//! with the engine's default key; those are decrypted first, since the installed cipher replaces the engine's.
+//! Live update entries live outside the archive and are left alone. The index checksum is updated to match the
+//! new flags, and the unused padding of the index header is marked so that archives that were already encrypted
+//! are refused instead of being encrypted twice.
+//!
+//! The key is either 16 characters or 32 hexadecimal digits.
+
+use dmsdk::dmresource::cipher::{self, Cipher, DEFAULT_XTEA_KEY};
+use std::{env, fs, path::PathBuf, process::ExitCode};
+
+const USAGE: &str = "usage: encrypt-archive --key <key> <game.arci>";
+
+/// Offset of the header's padding, which the engine ignores.
+const MARKER_OFFSET: usize = 4;
+/// Written to the header's padding once the archive is encrypted.
+const MARKER: &[u8; 4] = b"dmsE";
+/// Size of the index header, up to and including the index checksum.
+const HEADER_LEN: usize = 48;
+/// Offset of the index checksum, the MD5 of everything after the header.
+const CHECKSUM_OFFSET: usize = 32;
+const ENTRY_LEN: usize = 16;
+const UNCOMPRESSED: u32 = u32::MAX;
+
+const FLAG_ENCRYPTED: u32 = 1 << 0;
+const FLAG_LIVE_UPDATE: u32 = 1 << 2;
+
+struct Args {
+    cipher: Cipher,
+    index: PathBuf,
+}
+
+fn parse_key(key: &str) -> Result<[u8; 16], String> {
+    if let Ok(key) = <[u8; 16]>::try_from(key.as_bytes()) {
+        return Ok(key);
+    }
+
+    if key.len() == 32 && key.is_ascii() {
+        let mut bytes = [0; 16];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&key[i * 2..i * 2 + 2], 16)
+                .map_err(|_| format!("invalid hexadecimal key {key:?}"))?;
+        }
+        return Ok(bytes);
+    }
+
+    Err("key must be 16 characters or 32 hexadecimal digits".to_owned())
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut key = None;
+    let mut index = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--key" => key = Some(args.next().ok_or(USAGE)?),
+            "-h" | "--help" => return Err(USAGE.to_owned()),
+            _ if index.is_none() && !arg.starts_with('-') => index = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument {arg:?}\n{USAGE}")),
+        }
+    }
+
+    let key = parse_key(&key.ok_or(USAGE)?)?;
+
+    Ok(Args {
+        cipher: Cipher::new(&key),
+        index: index.ok_or(USAGE)?,
+    })
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> Result<u32, String> {
+    buf.get(offset..offset + 4)
+        .map(|bytes| u32::from_be_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| "archive index is truncated".to_owned())
+}
+
+fn encrypt(args: &Args) -> Result<usize, String> {
+    let data_path = args.index.with_extension("arcd");
+    let mut index =
+        fs::read(&args.index).map_err(|err| format!("{}: {err}", args.index.display()))?;
+    let mut data = fs::read(&data_path).map_err(|err| format!("{}: {err}", data_path.display()))?;
+
+    if index.len() < HEADER_LEN {
+        return Err("archive index is truncated".to_owned());
+    }
+    if &index[MARKER_OFFSET..MARKER_OFFSET + 4] == MARKER {
+        return Err(format!("{} is already encrypted", args.index.display()));
+    }
+    let entry_count = read_u32(&index, 16)? as usize;
+    let entry_offset = read_u32(&index, 20)? as usize;
+
+    let mut encrypted = 0;
+    for i in 0..entry_count {
+        let entry = entry_offset + i * ENTRY_LEN;
+        let size = read_u32(&index, entry)?;
+        let compressed_size = read_u32(&index, entry + 4)?;
+        let offset = read_u32(&index, entry + 8)? as usize;
+        let flags = read_u32(&index, entry + 12)?;
+
+        if flags & FLAG_LIVE_UPDATE != 0 {
+            continue;
+        }
+
+        let len = if compressed_size == UNCOMPRESSED {
+            size
+        } else {
+            compressed_size
+        } as usize;
+        let stored = data
+            .get_mut(offset..offset + len)
+            .ok_or_else(|| format!("entry {i} lies outside the archive data"))?;
+
+        if flags & FLAG_ENCRYPTED != 0 {
+            cipher::xtea(&DEFAULT_XTEA_KEY, stored);
+        }
+        args.cipher.encrypt(stored);
+
+        index[entry + 12..entry + 16].copy_from_slice(&(flags | FLAG_ENCRYPTED).to_be_bytes());
+        encrypted += 1;
+    }
+    index[MARKER_OFFSET..MARKER_OFFSET + 4].copy_from_slice(MARKER);
+    let checksum = md5::digest(&index[HEADER_LEN..]);
+    index[CHECKSUM_OFFSET..HEADER_LEN].copy_from_slice(&checksum);
+
+    fs::write(&data_path, data).map_err(|err| format!("{}: {err}", data_path.display()))?;
+    fs::write(&args.index, index).map_err(|err| format!("{}: {err}", args.index.display()))?;
+    Ok(encrypted)
+}
+
+mod md5 {
+    #[rustfmt::skip]
+    const SHIFTS: [u32; 64] = [
+        7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22,
+        5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20,
+        4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23,
+        6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+    ];
+
+    #[rustfmt::skip]
+    const CONSTANTS: [u32; 64] = [
+        0xd76a_a478, 0xe8c7_b756, 0x2420_70db, 0xc1bd_ceee,
+        0xf57c_0faf, 0x4787_c62a, 0xa830_4613, 0xfd46_9501,
+        0x6980_98d8, 0x8b44_f7af, 0xffff_5bb1, 0x895c_d7be,
+        0x6b90_1122, 0xfd98_7193, 0xa679_438e, 0x49b4_0821,
+        0xf61e_2562, 0xc040_b340, 0x265e_5a51, 0xe9b6_c7aa,
+        0xd62f_105d, 0x0244_1453, 0xd8a1_e681, 0xe7d3_fbc8,
+        0x21e1_cde6, 0xc337_07d6, 0xf4d5_0d87, 0x455a_14ed,
+        0xa9e3_e905, 0xfcef_a3f8, 0x676f_02d9, 0x8d2a_4c8a,
+        0xfffa_3942, 0x8771_f681, 0x6d9d_6122, 0xfde5_380c,
+        0xa4be_ea44, 0x4bde_cfa9, 0xf6bb_4b60, 0xbebf_bc70,
+        0x289b_7ec6, 0xeaa1_27fa, 0xd4ef_3085, 0x0488_1d05,
+        0xd9d4_d039, 0xe6db_99e5, 0x1fa2_7cf8, 0xc4ac_5665,
+        0xf429_2244, 0x432a_ff97, 0xab94_23a7, 0xfc93_a039,
+        0x655b_59c3, 0x8f0c_cc92, 0xffef_f47d, 0x8584_5dd1,
+        0x6fa8_7e4f, 0xfe2c_e6e0, 0xa301_4314, 0x4e08_11a1,
+        0xf753_7e82, 0xbd3a_f235, 0x2ad7_d2bb, 0xeb86_d391,
+    ];
+
+    fn compress(state: &mut [u32; 4], chunk: &[u8]) {
+        let mut words = [0; 16];
+        for (word, bytes) in words.iter_mut().zip(chunk.chunks_exact(4)) {
+            *word = u32::from_le_bytes(bytes.try_into().unwrap());
+        }
+
+        let [mut a, mut b, mut c, mut d] = *state;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let f = f
+                .wrapping_add(a)
+                .wrapping_add(CONSTANTS[i])
+                .wrapping_add(words[g]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(f.rotate_left(SHIFTS[i]));
+        }
+
+        for (word, value) in state.iter_mut().zip([a, b, c, d]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+
+    pub fn digest(data: &[u8]) -> [u8; 16] {
+        let mut state = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+
+        let mut tail = data.chunks_exact(64);
+        for chunk in &mut tail {
+            compress(&mut state, chunk);
+        }
+
+        let mut last = tail.remainder().to_vec();
+        last.push(0x80);
+        while last.len() % 64 != 56 {
+            last.push(0);
+        }
+        last.extend_from_slice(&(data.len() as u64 * 8).to_le_bytes());
+        for chunk in last.chunks_exact(64) {
+            compress(&mut state, chunk);
+        }
+
+        let mut digest = [0; 16];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(state) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        digest
+    }
+}
+
+fn main() -> ExitCode {
+    let result = parse_args().and_then(|args| encrypt(&args));
+    match result {
+        Ok(count) => {
+            println!("encrypted {count} entries");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,460 @@
+//! Remote debug endpoints served by the engine's web server.
+//!
+//! The debug server is disabled unless `game.project` (or an override, see
+//! [`ProjectOverride`](crate::dmconfigfile::ProjectOverride)) sets [`ENABLED_KEY`]:
+//!
+//! ```ini
+//! [debug_server]
+//! enabled = 1
+//! ```
+//!
+//! Every endpoint lives under [`PREFIX`] and responds with JSON:
+//!
+//! | Endpoint                        | Description                                                           |
+//! |---------------------------------|-----------------------------------------------------------------------|
+//! | `GET /dmsdk/extensions`         | Lists declared Rust extensions and their [`debug_summary`](crate::dmextension::Extension::debug_summary). |
+//! | `GET /dmsdk/config?key=a.b`     | Reads a config value.                                                 |
+//! | `POST /dmsdk/config?key=a.b`    | Writes a config value, taken from the `value` parameter or the body. Fails with 501 unless [`ConfigOverrides`] is declared. |
+//! | `GET /dmsdk/log?lines=N`        | Returns the last `N` (default 100) log lines.                         |
+//! | `POST /dmsdk/lua`               | Runs the Lua snippet in the body on the main thread and returns its results. |
+//!
+//! The routes are backed by a [`Backend`], so they can be exercised without the engine through [`route()`].
+
+use crate::{
+    dmconfigfile::{self, ConfigFile, ConfigPlugin},
+    dmextension::{self, Params},
+    dmlog, dmwebserver, lua,
+};
+use dmsdk_ffi::dmScript;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ffi::{c_char, CStr},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+};
+
+/// Config key that enables the debug server when set to a non-zero integer.
+pub const ENABLED_KEY: &str = "debug_server.enabled";
+
+/// Path prefix of every debug server endpoint.
+pub const PREFIX: &str = "/dmsdk";
+
+/// Number of log lines kept for `GET /dmsdk/log`.
+pub const LOG_CAPACITY: usize = 512;
+
+const DEFAULT_LOG_LINES: usize = 100;
+
+/// Provides the data behind the debug server endpoints.
+///
+/// [`DebugServer`] uses one backed by the running engine, implement this to serve the endpoints from
+/// something else, e.g. a stand-in for the engine in tests.
+pub trait Backend {
+    /// Returns the value of a config key, or [`None`] if it doesn't exist.
+    fn get_config(&mut self, key: &str) -> Option<String>;
+
+    /// Sets the value of a config key, or returns why config values can't be written.
+    fn set_config(&mut self, key: &str, value: &str) -> Result<(), String>;
+
+    /// Returns the declared extensions.
+    fn extensions(&mut self) -> Vec<dmextension::ExtensionInfo> {
+        Vec::new()
+    }
+
+    /// Returns up to `count` of the most recent log lines, oldest first.
+    fn log_lines(&mut self, _count: usize) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Runs a Lua snippet and returns its results converted to strings.
+    fn eval_lua(&mut self, _code: &str) -> Result<Vec<String>, String> {
+        Err("Lua isn't available".to_owned())
+    }
+}
+
+/// Routes a request to the matching endpoint. Returns the status code and JSON body of the response.
+pub fn route(backend: &mut dyn Backend, method: &str, path: &str, body: &[u8]) -> (u16, String) {
+    let path = path.strip_prefix(PREFIX).unwrap_or(path);
+    let (path, query) = path.split_once('?').unwrap_or((path, ""));
+    let param = |name: &str| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| percent_decode(key) == name)
+            .map(|(_, value)| percent_decode(value))
+    };
+
+    match (method, path.trim_end_matches('/')) {
+        ("GET", "/extensions") => {
+            let extensions = backend.extensions().into_iter().map(|info| {
+                format!(
+                    r#"{{"name":{},"created":{},"summary":{}}}"#,
+                    json_string(info.name),
+                    info.created,
+                    json_option(info.summary.as_deref())
+                )
+            });
+            (200, json_array(extensions))
+        }
+        ("GET", "/config") => match param("key") {
+            Some(key) => {
+                let value = backend.get_config(&key);
+                (200, config_json(&key, value.as_deref()))
+            }
+            None => error(400, "missing `key` parameter"),
+        },
+        ("POST" | "PUT", "/config") => {
+            let Some(key) = param("key") else {
+                return error(400, "missing `key` parameter");
+            };
+            let value =
+                param("value").unwrap_or_else(|| String::from_utf8_lossy(body).into_owned());
+            match backend.set_config(&key, &value) {
+                Ok(()) => (200, config_json(&key, Some(&value))),
+                Err(err) => error(501, &err),
+            }
+        }
+        ("GET", "/log") => {
+            let count = param("lines")
+                .and_then(|lines| lines.parse().ok())
+                .unwrap_or(DEFAULT_LOG_LINES);
+            let lines = backend.log_lines(count);
+            (
+                200,
+                format!(
+                    r#"{{"lines":{}}}"#,
+                    json_array(lines.iter().map(|line| json_string(line)))
+                ),
+            )
+        }
+        ("POST", "/lua") => {
+            let Ok(code) = std::str::from_utf8(body) else {
+                return error(400, "Lua snippet isn't valid UTF-8");
+            };
+            match backend.eval_lua(code) {
+                Ok(results) => (
+                    200,
+                    format!(
+                        r#"{{"results":{}}}"#,
+                        json_array(results.iter().map(|result| json_string(result)))
+                    ),
+                ),
+                Err(err) => error(400, &err),
+            }
+        }
+        (_, "/extensions" | "/config" | "/log" | "/lua") => error(405, "method not allowed"),
+        _ => error(404, "no such endpoint"),
+    }
+}
+
+fn error(status: u16, message: &str) -> (u16, String) {
+    (status, format!(r#"{{"error":{}}}"#, json_string(message)))
+}
+
+fn config_json(key: &str, value: Option<&str>) -> String {
+    format!(
+        r#"{{"key":{},"value":{}}}"#,
+        json_string(key),
+        json_option(value)
+    )
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn json_option(s: Option<&str>) -> String {
+    s.map_or_else(|| "null".to_owned(), json_string)
+}
+
+fn json_array(items: impl Iterator<Item = String>) -> String {
+    format!("[{}]", items.collect::<Vec<_>>().join(","))
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                match std::str::from_utf8(&bytes[i + 1..i + 3])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+static LOG_LINES: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+fn log_lines() -> MutexGuard<'static, VecDeque<String>> {
+    LOG_LINES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+unsafe extern "C" fn log_listener(
+    _severity: dmsdk_ffi::LogSeverity,
+    _domain: *const c_char,
+    formatted_string: *const c_char,
+) {
+    if formatted_string.is_null() {
+        return;
+    }
+
+    let line = CStr::from_ptr(formatted_string).to_string_lossy();
+    let mut lines = log_lines();
+    if lines.len() == LOG_CAPACITY {
+        lines.pop_front();
+    }
+    lines.push_back(line.trim_end().to_owned());
+}
+
+static CONFIG_OVERRIDES: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+fn config_overrides() -> MutexGuard<'static, BTreeMap<String, String>> {
+    CONFIG_OVERRIDES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Whether a [`ConfigOverrides`] plugin is currently created, so written values reach the engine.
+static OVERRIDES_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Config plugin that serves the values written through `POST /dmsdk/config`.
+///
+/// Without it, `POST /dmsdk/config` fails with status 501, since the engine would never see the value.
+///
+/// # Examples
+/// ```ignore
+/// use dmsdk::*;
+///
+/// declare_configfile_extension!(DEBUG_SERVER_CONFIG, debug_server::ConfigOverrides);
+/// ```
+#[derive(Debug, Default)]
+pub struct ConfigOverrides;
+
+impl ConfigPlugin for ConfigOverrides {
+    fn create(_config: ConfigFile) -> Self {
+        OVERRIDES_ACTIVE.store(true, Ordering::Relaxed);
+        Self
+    }
+
+    fn destroy(&mut self, _config: ConfigFile) {
+        OVERRIDES_ACTIVE.store(false, Ordering::Relaxed);
+    }
+
+    fn get_string(
+        &mut self,
+        _config: ConfigFile,
+        key: &str,
+        _default_value: &str,
+    ) -> Option<String> {
+        config_overrides().get(key).cloned()
+    }
+
+    fn get_int(&mut self, _config: ConfigFile, key: &str, _default_value: i32) -> Option<i32> {
+        config_overrides().get(key)?.trim().parse().ok()
+    }
+
+    fn get_float(&mut self, _config: ConfigFile, key: &str, _default_value: f32) -> Option<f32> {
+        config_overrides().get(key)?.trim().parse().ok()
+    }
+}
+
+/// [`Backend`] backed by the running engine.
+#[derive(Clone, Copy)]
+pub struct EngineBackend {
+    config: ConfigFile,
+    l: lua::State,
+}
+
+impl EngineBackend {
+    /// Creates a new [`EngineBackend`] using the config file and Lua state from `params`.
+    pub fn new(params: Params) -> Self {
+        Self {
+            config: params.config,
+            l: params.l,
+        }
+    }
+}
+
+impl Backend for EngineBackend {
+    fn get_config(&mut self, key: &str) -> Option<String> {
+        dmconfigfile::find_string(self.config, key)
+    }
+
+    fn set_config(&mut self, key: &str, value: &str) -> Result<(), String> {
+        if !OVERRIDES_ACTIVE.load(Ordering::Relaxed) {
+            return Err(
+                "config values can't be written without the `ConfigOverrides` plugin".to_owned(),
+            );
+        }
+        config_overrides().insert(key.to_owned(), value.to_owned());
+        Ok(())
+    }
+
+    fn extensions(&mut self) -> Vec<dmextension::ExtensionInfo> {
+        dmextension::extensions()
+    }
+
+    fn log_lines(&mut self, count: usize) -> Vec<String> {
+        let lines = log_lines();
+        lines
+            .iter()
+            .skip(lines.len().saturating_sub(count))
+            .cloned()
+            .collect()
+    }
+
+    fn eval_lua(&mut self, code: &str) -> Result<Vec<String>, String> {
+        let l = self.l.ptr();
+        let top = lua::get_top(self.l);
+
+        let result = unsafe {
+            let name = c"=debug_server";
+            if dmsdk_ffi::luaL_loadbuffer(
+                l,
+                code.as_ptr() as *const c_char,
+                code.len(),
+                name.as_ptr(),
+            ) != 0
+                || dmScript::PCall(l, 0, dmsdk_ffi::LUA_MULTRET) != 0
+            {
+                let message = if lua::get_top(self.l) > top {
+                    lua_value_to_string(self.l, -1)
+                } else {
+                    "error running snippet".to_owned()
+                };
+                Err(message)
+            } else {
+                Ok(((top + 1)..=lua::get_top(self.l))
+                    .map(|i| lua_value_to_string(self.l, i))
+                    .collect())
+            }
+        };
+
+        unsafe { dmsdk_ffi::lua_settop(l, top) };
+        result
+    }
+}
+
+unsafe fn lua_value_to_string(l: lua::State, i: i32) -> String {
+    let kind = dmsdk_ffi::lua_type(l.ptr(), i);
+    match kind as u32 {
+        dmsdk_ffi::LUA_TNIL => "nil".to_owned(),
+        dmsdk_ffi::LUA_TBOOLEAN => lua::to_bool(l, i).to_string(),
+        dmsdk_ffi::LUA_TNUMBER | dmsdk_ffi::LUA_TSTRING => {
+            // Copy the value first so numbers aren't converted to strings in place
+            dmsdk_ffi::lua_pushvalue(l.ptr(), i);
+            let mut len = 0;
+            let ptr = dmsdk_ffi::lua_tolstring(l.ptr(), -1, &mut len);
+            let bytes = std::slice::from_raw_parts(ptr as *const u8, len);
+            let s = String::from_utf8_lossy(bytes).into_owned();
+            lua::pop(l, 1);
+            s
+        }
+        _ => {
+            let name = CStr::from_ptr(dmsdk_ffi::lua_typename(l.ptr(), kind));
+            format!("<{}>", name.to_string_lossy())
+        }
+    }
+}
+
+/// Running debug server. The endpoints are removed when this is dropped.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+/// use dmextension::{Extension, Params};
+///
+/// #[derive(Default)]
+/// struct MyExtension {
+///     debug_server: Option<debug_server::DebugServer>,
+/// }
+///
+/// impl Extension for MyExtension {
+///     fn ext_init(&mut self, params: Params) -> dmextension::Result {
+///         self.debug_server = debug_server::DebugServer::start(params);
+///
+///         dmextension::Result::Ok
+///     }
+///
+///     fn ext_final(&mut self, _params: Params) -> dmextension::Result {
+///         self.debug_server = None;
+///
+///         dmextension::Result::Ok
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DebugServer {
+    _handler: dmwebserver::Handler,
+}
+
+impl DebugServer {
+    /// Starts the debug server if [`ENABLED_KEY`] is set, otherwise returns [`None`].
+    pub fn start(params: Params) -> Option<Self> {
+        if dmconfigfile::get_int(params.config, ENABLED_KEY, 0) == 0 {
+            return None;
+        }
+
+        let Some(server) = params.web_server() else {
+            dmlog::error!("Debug server couldn't find the engine's web server");
+            return None;
+        };
+
+        let mut backend = EngineBackend::new(params);
+        let handler = server.add_handler(PREFIX, move |request| {
+            let body = request.read_body().unwrap_or_default();
+            let (status, json) = route(&mut backend, request.method(), request.path(), &body);
+            dmwebserver::Response::new(status)
+                .header("Content-Type", "application/json")
+                .body(json)
+        });
+
+        match handler {
+            Ok(handler) => {
+                unsafe { dmsdk_ffi::dmLogRegisterListener(Some(log_listener)) };
+                dmlog::info!("Debug server endpoints available under {PREFIX}");
+                Some(Self { _handler: handler })
+            }
+            Err(err) => {
+                dmlog::error!("Failed to start debug server: {err}");
+                None
+            }
+        }
+    }
+}
+
+impl Drop for DebugServer {
+    fn drop(&mut self) {
+        unsafe { dmsdk_ffi::dmLogUnregisterListener(Some(log_listener)) };
+        log_lines().clear();
+    }
+}
@@ -0,0 +1,364 @@
+//! Growable arrays that share their memory layout with the engine's `dmArray`.
+//!
+//! [`Array`] is both an owned array that can be handed over to C++ code, and a view of arrays owned by the engine,
+//! such as the ones inside component or resource structs. Either way, it dereferences to a slice for reading,
+//! writing and iterating.
+//!
+//! Like `dmArray`, it never runs destructors for its elements, so only [`Copy`] types can be added to it.
+//! Zero-sized types can't be stored at all, since `dmArray` tells elements apart by their address:
+//!
+//! ```compile_fail
+//! let units = dmsdk::dmarray::Array::<()>::new();
+//! ```
+//!
+//! # Examples
+//! ```
+//! use dmsdk::dmarray::{Array, RawArray};
+//!
+//! fn scale_weights(raw: &mut RawArray<f32>, factor: f32) {
+//!     let weights = unsafe { Array::from_raw_mut(raw) };
+//!     for weight in weights.iter_mut() {
+//!         *weight *= factor;
+//!     }
+//!     weights.push(factor);
+//! }
+//! ```
+
+use dmsdk_ffi::{__BindgenBitfieldUnit, dmArray, dmArrayUtil};
+use libc::c_void;
+use std::{
+    fmt,
+    marker::PhantomData,
+    mem,
+    ops::{Deref, DerefMut},
+    ptr, slice,
+};
+
+/// The engine's `dmArray<T>`, as found in the structs it shares with extensions.
+pub type RawArray<T> = dmArray<T>;
+
+extern "C" {
+    // `operator delete[]`, which `dmArray` frees its storage with
+    #[link_name = "\u{1}_ZdaPv"]
+    fn operator_delete_array(ptr: *mut c_void);
+}
+
+/// Frees the storage of `raw` the same way the `dmArray` destructor does.
+///
+/// # Safety
+/// `raw` must be a valid array that isn't used afterwards.
+pub(crate) unsafe fn free_raw<T>(raw: &mut RawArray<T>) {
+    if raw.m_UserAllocated() == 0 && !raw.m_Front.is_null() {
+        operator_delete_array(raw.m_Front.cast());
+    }
+}
+
+/// Array with the same layout as the engine's `dmArray<T>`.
+///
+/// An owned `Array` frees its memory when dropped, unless it was given away with [`Array::into_raw()`].
+/// References to arrays owned by the engine can be made with [`Array::from_raw()`] and [`Array::from_raw_mut()`].
+///
+/// Arrays made by C++ code over memory they don't own (*user-allocated* arrays) have a fixed capacity,
+/// and adding elements past it panics.
+#[repr(transparent)]
+pub struct Array<T>(RawArray<T>);
+
+unsafe impl<T: Send> Send for Array<T> {}
+unsafe impl<T: Sync> Sync for Array<T> {}
+
+impl<T> Array<T> {
+    const NOT_ZERO_SIZED: () = assert!(
+        mem::size_of::<T>() != 0,
+        "zero-sized elements are not supported"
+    );
+
+    /// Creates an empty array, without allocating.
+    pub const fn new() -> Self {
+        let () = Self::NOT_ZERO_SIZED;
+        Self(dmArray {
+            m_Front: ptr::null_mut(),
+            m_End: ptr::null_mut(),
+            m_Back: ptr::null_mut(),
+            _bitfield_align_1: [],
+            _bitfield_1: __BindgenBitfieldUnit::new([0]),
+            _phantom_0: PhantomData,
+        })
+    }
+
+    /// Creates an empty array with room for `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut array = Self::new();
+        array.set_capacity(capacity);
+        array
+    }
+
+    /// Returns a reference to an existing `dmArray`.
+    ///
+    /// # Safety
+    /// `raw` must be a valid array, e.g. one constructed by C++ code or zeroed.
+    pub unsafe fn from_raw(raw: &RawArray<T>) -> &Self {
+        let () = Self::NOT_ZERO_SIZED;
+        &*(raw as *const RawArray<T>).cast::<Self>()
+    }
+
+    /// Returns a mutable reference to an existing `dmArray`.
+    ///
+    /// Growing the array reallocates its memory the same way `dmArray::SetCapacity()` does,
+    /// so the engine can keep using it afterwards.
+    ///
+    /// # Safety
+    /// `raw` must be a valid array, e.g. one constructed by C++ code or zeroed.
+    pub unsafe fn from_raw_mut(raw: &mut RawArray<T>) -> &mut Self {
+        let () = Self::NOT_ZERO_SIZED;
+        &mut *(raw as *mut RawArray<T>).cast::<Self>()
+    }
+
+    /// Gives the array away, e.g. to store it in a struct that C++ code will destroy.
+    pub fn into_raw(self) -> RawArray<T> {
+        let raw = unsafe { ptr::read(&self.0) };
+        mem::forget(self);
+        raw
+    }
+
+    /// Returns a reference to the underlying `dmArray`, e.g. to pass it to an engine function.
+    pub fn as_raw(&self) -> &RawArray<T> {
+        &self.0
+    }
+
+    /// Returns a mutable reference to the underlying `dmArray`, e.g. to pass it to an engine function.
+    pub fn as_raw_mut(&mut self) -> &mut RawArray<T> {
+        &mut self.0
+    }
+
+    fn distance(from: *mut T, to: *mut T) -> usize {
+        (to as usize - from as usize) / mem::size_of::<T>()
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        Self::distance(self.0.m_Front, self.0.m_End)
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.0.m_End == self.0.m_Front
+    }
+
+    /// Returns the number of elements the array can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        Self::distance(self.0.m_Front, self.0.m_Back)
+    }
+
+    /// Returns `true` if the array's memory is owned by someone else, which prevents it from growing.
+    pub fn is_user_allocated(&self) -> bool {
+        self.0.m_UserAllocated() != 0
+    }
+
+    /// Returns the elements as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        if self.0.m_Front.is_null() {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(self.0.m_Front, self.len()) }
+        }
+    }
+
+    /// Returns the elements as a mutable slice.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        if self.0.m_Front.is_null() {
+            &mut []
+        } else {
+            unsafe { slice::from_raw_parts_mut(self.0.m_Front, self.len()) }
+        }
+    }
+
+    /// Changes the capacity to exactly `capacity` elements, removing the elements that don't fit anymore.
+    ///
+    /// # Panics
+    /// Panics if the array is user-allocated, or if `capacity` doesn't fit in a `u32`.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        assert!(
+            !self.is_user_allocated(),
+            "can't change the capacity of a user-allocated array"
+        );
+        if capacity == self.capacity() {
+            return;
+        }
+
+        let capacity = u32::try_from(capacity).expect("array capacity overflow");
+        unsafe {
+            dmArrayUtil::SetCapacity(
+                capacity,
+                mem::size_of::<T>() as u32,
+                ptr::addr_of_mut!(self.0.m_Front).cast(),
+                ptr::addr_of_mut!(self.0.m_Back).cast(),
+                ptr::addr_of_mut!(self.0.m_End).cast(),
+            )
+        }
+    }
+
+    /// Makes sure there's room for at least `additional` more elements, growing the capacity geometrically.
+    ///
+    /// # Panics
+    /// Panics if the array needs to grow but is user-allocated.
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self.len() + additional;
+        if required > self.capacity() {
+            self.set_capacity(required.max(self.capacity() * 2).max(4));
+        }
+    }
+
+    /// Removes the last element and returns it, or `None` if the array is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        unsafe {
+            self.0.m_End = self.0.m_End.sub(1);
+            Some(ptr::read(self.0.m_End))
+        }
+    }
+
+    /// Removes the element at `index` and returns it, replacing it with the last element.
+    ///
+    /// This is `dmArray::EraseSwap()`, which doesn't preserve the order of elements but is *O*(1).
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.len();
+        assert!(index < len, "index {index} out of bounds for length {len}");
+        self.as_mut_slice().swap(index, len - 1);
+        self.pop().unwrap()
+    }
+
+    /// Shortens the array to `len` elements, keeping the capacity. Does nothing if it's already shorter.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.0.m_End = unsafe { self.0.m_Front.add(len) };
+        }
+    }
+
+    /// Removes every element, keeping the capacity.
+    pub fn clear(&mut self) {
+        self.0.m_End = self.0.m_Front;
+    }
+}
+
+impl<T: Copy> Array<T> {
+    /// Adds an element at the end, growing the array if it's full.
+    ///
+    /// # Panics
+    /// Panics if the array is full and user-allocated.
+    pub fn push(&mut self, value: T) {
+        self.reserve(1);
+        unsafe {
+            ptr::write(self.0.m_End, value);
+            self.0.m_End = self.0.m_End.add(1);
+        }
+    }
+
+    /// Adds all the elements of `values` at the end, growing the array if needed.
+    ///
+    /// # Panics
+    /// Panics if the array needs to grow but is user-allocated.
+    pub fn extend_from_slice(&mut self, values: &[T]) {
+        self.reserve(values.len());
+        unsafe {
+            ptr::copy_nonoverlapping(values.as_ptr(), self.0.m_End, values.len());
+            self.0.m_End = self.0.m_End.add(values.len());
+        }
+    }
+}
+
+impl<T> Default for Array<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for Array<T> {
+    fn drop(&mut self) {
+        unsafe { free_raw(&mut self.0) }
+    }
+}
+
+impl<T> Deref for Array<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> DerefMut for Array<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: Copy> Clone for Array<T> {
+    fn clone(&self) -> Self {
+        let mut array = Self::with_capacity(self.len());
+        array.extend_from_slice(self);
+        array
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Array<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for Array<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq> Eq for Array<T> {}
+
+impl<T: Copy> From<&[T]> for Array<T> {
+    fn from(values: &[T]) -> Self {
+        let mut array = Self::with_capacity(values.len());
+        array.extend_from_slice(values);
+        array
+    }
+}
+
+impl<T: Copy> Extend<T> for Array<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: Copy> FromIterator<T> for Array<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut array = Self::new();
+        array.extend(iter);
+        array
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Array<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Array<T> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
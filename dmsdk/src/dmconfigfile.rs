@@ -1,8 +1,16 @@
 //! Configuration file access functions. The configuration file is the compiled version of game.project.
 
+use crate::dmlog;
 use dmsdk_ffi::dmConfigFile;
 use libc::c_void;
-use std::ffi::{c_char, CStr, CString};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    ffi::{c_char, CStr, CString},
+    path::PathBuf,
+    str::FromStr,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
 
 #[doc(hidden)]
 pub type RawConfigFile = dmConfigFile::HConfig;
@@ -51,6 +59,15 @@ pub fn get_string(config: ConfigFile, key: &str, default_value: &str) -> String
     String::from_utf8_lossy(cstr.to_bytes()).into_owned()
 }
 
+/// Gets the corresponding config value as a String, or [`None`] if the key isn't found.
+pub(crate) fn find_string(config: ConfigFile, key: &str) -> Option<String> {
+    // Returned by the engine when the key doesn't exist
+    const MISSING: &str = "\u{1}dmsdk_missing_value";
+
+    let value = get_string(config, key, MISSING);
+    (value != MISSING).then_some(value)
+}
+
 /// Gets the corresponding config value as an i32.
 ///
 /// `default_value` will be returned if the key isn't found or if the value found isn't a valid integer.
@@ -93,178 +110,648 @@ pub fn get_float(config: ConfigFile, key: &str, default_value: f32) -> f32 {
     unsafe { dmConfigFile::GetFloat(config.into(), key.as_ptr(), default_value) }
 }
 
-/// Callback function called during the config plugin lifecycle.
-pub type PluginLifecycle = fn(ConfigFile);
-/// Function used to provide config values.
-pub type PluginGetter<T> = fn(ConfigFile, &str, T) -> Option<T>;
-#[doc(hidden)]
-pub type StringGetter = fn(ConfigFile, &str, &str) -> Option<String>;
-#[doc(hidden)]
-pub type RawPluginLifecycle = unsafe extern "C" fn(dmConfigFile::HConfig);
-#[doc(hidden)]
-pub type RawPluginGetter<T> =
-    unsafe extern "C" fn(dmConfigFile::HConfig, *const c_char, T, *mut T) -> bool;
-#[doc(hidden)]
-pub type Desc = [u8; DESC_BUFFER_SIZE as usize];
+/// A config file extension, registered with [`declare_configfile_extension!`].
+///
+/// The plugin is constructed by [`ConfigPlugin::create`] when the engine loads the config file and dropped
+/// right after [`ConfigPlugin::destroy`], so any state it needs can live in `self`.
+///
+/// Each `get` function is called whenever a config value is requested from Lua or C++.
+/// Return [`Some`] to override a value with your own, or [`None`] to let another plugin handle it.
+/// Looking up config values from inside a getter is allowed, the lookup simply skips this plugin.
+pub trait ConfigPlugin: Send + 'static {
+    /// Creates the plugin when the config file is loaded.
+    fn create(config: ConfigFile) -> Self
+    where
+        Self: Sized;
 
-#[doc(hidden)]
-pub const DESC_BUFFER_SIZE: u32 = 64;
+    /// Called when the config file is about to be unloaded.
+    fn destroy(&mut self, _config: ConfigFile) {}
 
-#[doc(hidden)]
-#[macro_export]
-macro_rules! declare_plugin_lifecycle {
-    ($symbol:ident, $option:expr) => {
-        #[no_mangle]
-        unsafe extern "C" fn $symbol(config: dmconfigfile::RawConfigFile) {
-            let func: Option<dmconfigfile::PluginLifecycle> = $option;
-            if let Some(func) = func {
-                func(config.into());
-            }
+    /// Provides a string value for `key`.
+    ///
+    /// The engine gets a pointer that stays valid until the plugin is destroyed, even if the value changes later.
+    /// Every distinct value returned is kept until then.
+    fn get_string(
+        &mut self,
+        _config: ConfigFile,
+        _key: &str,
+        _default_value: &str,
+    ) -> Option<String> {
+        None
+    }
+
+    /// Provides an integer value for `key`.
+    fn get_int(&mut self, _config: ConfigFile, _key: &str, _default_value: i32) -> Option<i32> {
+        None
+    }
+
+    /// Provides a float value for `key`.
+    fn get_float(&mut self, _config: ConfigFile, _key: &str, _default_value: f32) -> Option<f32> {
+        None
+    }
+}
+
+/// Owned storage for the strings handed out to the engine.
+///
+/// Callers may keep the pointer returned by a string getter, so every distinct value is kept alive
+/// until the plugin is destroyed, even after the key it was returned for changes.
+/// Returning a value that was returned before gives the same pointer, which bounds the memory used
+/// by the number of distinct values.
+#[derive(Debug, Default)]
+struct StringStore {
+    strings: HashSet<CString>,
+}
+
+impl StringStore {
+    /// Stores `value` and returns a pointer to it, or [`None`] if it contains a null byte.
+    fn insert(&mut self, value: String) -> Option<*const c_char> {
+        let value = CString::new(value).ok()?;
+        // The characters live on the heap, so the pointer stays valid when the set grows
+        if let Some(stored) = self.strings.get(&value) {
+            return Some(stored.as_ptr());
         }
-    };
+        let ptr = value.as_ptr();
+        self.strings.insert(value);
+        Some(ptr)
+    }
 }
 
-#[doc(hidden)]
-#[macro_export]
-macro_rules! declare_plugin_getter {
-    ($symbol:ident, $option:expr, $type:ident) => {
-        #[no_mangle]
-        unsafe extern "C" fn $symbol(
-            config: dmconfigfile::RawConfigFile,
-            key: *const core::ffi::c_char,
-            default_value: $type,
-            out: *mut $type,
-        ) -> bool {
-            let func: Option<dmconfigfile::PluginGetter<$type>> = $option;
-            if let Some(func) = func {
-                let key = core::ffi::CStr::from_ptr(key)
-                    .to_str()
-                    .expect("Invalid UTF-8 sequence in key!");
-                if let Some(value) = func(config.into(), key, default_value) {
-                    out.write(value);
-                    true
-                } else {
-                    false
-                }
+struct PluginState<T> {
+    plugin: T,
+    strings: StringStore,
+}
+
+thread_local! {
+    static ACTIVE_PLUGINS: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Marks a plugin as running on the current thread until dropped.
+struct ReentryGuard {
+    id: usize,
+}
+
+impl ReentryGuard {
+    /// Returns [`None`] if the plugin is already running further up the stack.
+    fn enter(id: usize) -> Option<Self> {
+        ACTIVE_PLUGINS.with(|active| {
+            let mut active = active.borrow_mut();
+            if active.contains(&id) {
+                None
             } else {
-                false
+                active.push(id);
+                Some(Self { id })
             }
-        }
-    };
+        })
+    }
+}
+
+impl Drop for ReentryGuard {
+    fn drop(&mut self) {
+        ACTIVE_PLUGINS.with(|active| active.borrow_mut().retain(|id| *id != self.id));
+    }
 }
 
+/// Storage for a [`ConfigPlugin`] declared with [`declare_configfile_extension!`].
 #[doc(hidden)]
-#[macro_export]
-macro_rules! declare_plugin_string_getter {
-    ($symbol:ident, $option:expr) => {
-        #[no_mangle]
-        unsafe extern "C" fn $symbol(
-            config: dmconfigfile::RawConfigFile,
-            key: *const core::ffi::c_char,
-            default_value: *const core::ffi::c_char,
-            out: *mut *const core::ffi::c_char,
-        ) -> bool {
-            let func: Option<dmconfigfile::StringGetter> = $option;
-            if let Some(func) = func {
-                let key = core::ffi::CStr::from_ptr(key).to_str();
-                if key.is_err() {
-                    dmlog::error!("Invalid UTF-8 sequence in key!");
+pub struct PluginCell<T> {
+    state: Mutex<Option<PluginState<T>>>,
+}
+
+impl<T: ConfigPlugin> PluginCell<T> {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self {
+            state: Mutex::new(None),
+        }
+    }
+
+    fn id(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<PluginState<T>>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn with<R>(&self, f: impl FnOnce(&mut PluginState<T>) -> Option<R>) -> Option<R> {
+        let _guard = ReentryGuard::enter(self.id())?;
+        self.lock().as_mut().and_then(f)
+    }
+
+    pub fn create(&self, config: RawConfigFile) {
+        let Some(_guard) = ReentryGuard::enter(self.id()) else {
+            return;
+        };
+
+        // The plugin isn't stored until it's fully created, so lookups made by `create` skip it
+        let plugin = T::create(config.into());
+        *self.lock() = Some(PluginState {
+            plugin,
+            strings: StringStore::default(),
+        });
+    }
+
+    pub fn destroy(&self, config: RawConfigFile) {
+        let Some(_guard) = ReentryGuard::enter(self.id()) else {
+            return;
+        };
+
+        let state = self.lock().take();
+        if let Some(mut state) = state {
+            state.plugin.destroy(config.into());
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `key` must be a valid C string, `default_value` must be null or a valid C string and `out` must be writable.
+    pub unsafe fn get_string(
+        &self,
+        config: RawConfigFile,
+        key: *const c_char,
+        default_value: *const c_char,
+        out: *mut *const c_char,
+    ) -> bool {
+        let Ok(key) = CStr::from_ptr(key).to_str() else {
+            dmlog::error!("Invalid UTF-8 sequence in key!");
+            return false;
+        };
+
+        let default_value = if default_value.is_null() {
+            ""
+        } else {
+            match CStr::from_ptr(default_value).to_str() {
+                Ok(str) => str,
+                Err(_) => {
+                    dmlog::error!("Invalid UTF-8 sequence in default value!");
                     return false;
                 }
+            }
+        };
 
-                let default_value = if default_value.is_null() {
-                    ""
-                } else {
-                    match core::ffi::CStr::from_ptr(default_value).to_str() {
-                        Ok(str) => str,
-                        Err(_) => {
-                            dmlog::error!("Invalid UTF-8 sequence in default value!");
-                            return false;
-                        }
-                    }
-                };
-
-                if let Some(value) = func(config.into(), key.unwrap(), default_value) {
-                    let cstr =
-                        std::ffi::CString::new(value).expect("Unexpected null in return value!");
-
-                    let boxed_str = Box::new(cstr);
-                    out.write(Box::leak(boxed_str).as_ptr());
-                    true
-                } else {
-                    false
-                }
-            } else {
-                false
+        let value = self.with(|state| {
+            let value = state.plugin.get_string(config.into(), key, default_value)?;
+            let ptr = state.strings.insert(value);
+            if ptr.is_none() {
+                dmlog::error!("Unexpected null in value returned for {key}!");
             }
+            ptr
+        });
+
+        match value {
+            Some(ptr) => {
+                out.write(ptr);
+                true
+            }
+            None => false,
         }
-    };
+    }
+
+    /// # Safety
+    ///
+    /// `key` must be a valid C string and `out` must be writable.
+    pub unsafe fn get_int(
+        &self,
+        config: RawConfigFile,
+        key: *const c_char,
+        default_value: i32,
+        out: *mut i32,
+    ) -> bool {
+        self.get_value(config, key, default_value, out, T::get_int)
+    }
+
+    /// # Safety
+    ///
+    /// `key` must be a valid C string and `out` must be writable.
+    pub unsafe fn get_float(
+        &self,
+        config: RawConfigFile,
+        key: *const c_char,
+        default_value: f32,
+        out: *mut f32,
+    ) -> bool {
+        self.get_value(config, key, default_value, out, T::get_float)
+    }
+
+    unsafe fn get_value<V>(
+        &self,
+        config: RawConfigFile,
+        key: *const c_char,
+        default_value: V,
+        out: *mut V,
+        getter: fn(&mut T, ConfigFile, &str, V) -> Option<V>,
+    ) -> bool {
+        let Ok(key) = CStr::from_ptr(key).to_str() else {
+            dmlog::error!("Invalid UTF-8 sequence in key!");
+            return false;
+        };
+
+        match self.with(|state| getter(&mut state.plugin, config.into(), key, default_value)) {
+            Some(value) => {
+                out.write(value);
+                true
+            }
+            None => false,
+        }
+    }
 }
 
-/// Equivalent to `DM_DECLARE_CONFIGFILE_EXTENSION` in regular C++ extensions.
+fn parse_value<T: FromStr>(value: Option<String>) -> Option<T> {
+    value?.trim().parse().ok()
+}
+
+/// Returns the name of the environment variable [`EnvOverride`] reads for the given key.
 ///
-/// Each `get` function is called whenever a config value is requested from Lua or C++.
-/// Return [`Some`] to override a value with your own, or [`None`] to let another function handle it.
+/// The name is the key in uppercase prefixed with `DEFOLD_`, with every character
+/// that isn't alphanumeric replaced by `_`.
 ///
 /// # Examples
 /// ```
-/// # const LOG_DOMAIN: &str = "DOCTEST";
 /// use dmsdk::*;
 ///
-/// fn plugin_create(config: dmconfigfile::ConfigFile) {
-///     dmlog::info!("Config plugin created");
-/// }
+/// assert_eq!(dmconfigfile::env_var_name("display.width"), "DEFOLD_DISPLAY_WIDTH");
+/// assert_eq!(dmconfigfile::env_var_name("project.title"), "DEFOLD_PROJECT_TITLE");
+/// ```
+pub fn env_var_name(key: &str) -> String {
+    let key = key.trim_start_matches('.');
+    let mut name = String::with_capacity(ENV_VAR_PREFIX.len() + key.len());
+    name.push_str(ENV_VAR_PREFIX);
+    name.extend(key.chars().map(|c| {
+        if c.is_ascii_alphanumeric() {
+            c.to_ascii_uppercase()
+        } else {
+            '_'
+        }
+    }));
+    name
+}
+
+const ENV_VAR_PREFIX: &str = "DEFOLD_";
+
+/// Ready-made plugin that overrides config values with environment variables.
 ///
-/// fn plugin_destroy(config: dmconfigfile::ConfigFile) {
-///     dmlog::info!("Config plugin destroyed");
-/// }
+/// See [`env_var_name()`] for how keys are mapped to variable names.
+/// Values that can't be parsed as the requested type are ignored.
+///
+/// # Examples
+/// ```ignore
+/// use dmsdk::*;
+///
+/// declare_configfile_extension!(ENV_OVERRIDE, dmconfigfile::EnvOverride);
+/// ```
+#[derive(Debug, Default)]
+pub struct EnvOverride;
+
+impl ConfigPlugin for EnvOverride {
+    fn create(_config: ConfigFile) -> Self {
+        Self
+    }
+
+    fn get_string(
+        &mut self,
+        _config: ConfigFile,
+        key: &str,
+        _default_value: &str,
+    ) -> Option<String> {
+        std::env::var(env_var_name(key)).ok()
+    }
+
+    fn get_int(&mut self, config: ConfigFile, key: &str, _default_value: i32) -> Option<i32> {
+        parse_value(self.get_string(config, key, ""))
+    }
+
+    fn get_float(&mut self, config: ConfigFile, key: &str, _default_value: f32) -> Option<f32> {
+        parse_value(self.get_string(config, key, ""))
+    }
+}
+
+/// Name of the file [`ProjectOverride`] reads overrides from.
+pub const OVERRIDE_FILE_NAME: &str = ".override.ini";
+
+/// Returns the path of the [`OVERRIDE_FILE_NAME`] file next to the running executable.
+pub fn override_file_path() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    Some(exe.parent()?.join(OVERRIDE_FILE_NAME))
+}
+
+/// Where a value returned by [`ProjectOverride::get()`] came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverrideSource {
+    /// An environment variable named after the key, see [`env_var_name()`].
+    Environment,
+    /// The override file, see [`override_file_path()`].
+    File,
+}
+
+/// Ready-made plugin that overrides `game.project` values without rebuilding the game.
+///
+/// Values are looked up in this order, the first one found wins:
+/// 1. The `DEFOLD_<SECTION>_<KEY>` environment variable, see [`env_var_name()`].
+/// 2. The `.override.ini` file next to the executable, see [`override_file_path()`].
+/// 3. Other config plugins, and finally `game.project` itself.
+///
+/// The override file uses the same format as `game.project`:
+///
+/// ```ini
+/// [display]
+/// width = 1280
+///
+/// [my_game]
+/// server_url = http://localhost:8080
+/// ```
+///
+/// Every overridden key is logged with [`Severity::Debug`](dmlog::Severity::Debug) the first time it's requested.
+///
+/// # Examples
+/// ```ignore
+/// use dmsdk::*;
 ///
-/// fn get_string(config: dmconfigfile::ConfigFile, key: &str, default_value: &str) -> Option<String> {
-///     if key == "project.title" {
-///         Some("My project now!".to_owned())
-///     } else {
-///         None
+/// declare_configfile_extension!(PROJECT_OVERRIDE, dmconfigfile::ProjectOverride);
+/// ```
+#[derive(Debug, Default)]
+pub struct ProjectOverride {
+    file_values: HashMap<String, String>,
+    logged_keys: HashSet<String>,
+}
+
+impl ProjectOverride {
+    /// Creates a new [`ProjectOverride`] using the given override file contents.
+    ///
+    /// # Examples
+    /// ```
+    /// use dmsdk::*;
+    /// use dmconfigfile::{OverrideSource, ProjectOverride};
+    ///
+    /// let overrides = ProjectOverride::from_ini("[display]\nwidth = 1280\n; height = 720\n");
+    ///
+    /// assert_eq!(
+    ///     overrides.get("display.width"),
+    ///     Some(("1280".to_owned(), OverrideSource::File))
+    /// );
+    /// assert_eq!(overrides.get("display.height"), None);
+    /// ```
+    pub fn from_ini(contents: &str) -> Self {
+        Self {
+            file_values: parse_ini(contents),
+            logged_keys: HashSet::new(),
+        }
+    }
+
+    /// Returns the override for `key` and where it came from, if there is one.
+    pub fn get(&self, key: &str) -> Option<(String, OverrideSource)> {
+        if let Ok(value) = std::env::var(env_var_name(key)) {
+            return Some((value, OverrideSource::Environment));
+        }
+
+        self.file_values
+            .get(key)
+            .map(|value| (value.clone(), OverrideSource::File))
+    }
+}
+
+impl ConfigPlugin for ProjectOverride {
+    fn create(_config: ConfigFile) -> Self {
+        let Some(path) = override_file_path() else {
+            return Self::default();
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                let plugin = Self::from_ini(&contents);
+                dmlog::debug!(
+                    "Loaded {} override(s) from {}",
+                    plugin.file_values.len(),
+                    path.display()
+                );
+                plugin
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(err) => {
+                dmlog::warning!("Failed to read {}: {err}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    fn get_string(
+        &mut self,
+        _config: ConfigFile,
+        key: &str,
+        _default_value: &str,
+    ) -> Option<String> {
+        let (value, source) = self.get(key)?;
+        if self.logged_keys.insert(key.to_owned()) {
+            let origin = match source {
+                OverrideSource::Environment => format!("${}", env_var_name(key)),
+                OverrideSource::File => OVERRIDE_FILE_NAME.to_owned(),
+            };
+            dmlog::debug!("Overriding {key} with \"{value}\" from {origin}");
+        }
+        Some(value)
+    }
+
+    fn get_int(&mut self, config: ConfigFile, key: &str, _default_value: i32) -> Option<i32> {
+        parse_value(self.get_string(config, key, ""))
+    }
+
+    fn get_float(&mut self, config: ConfigFile, key: &str, _default_value: f32) -> Option<f32> {
+        parse_value(self.get_string(config, key, ""))
+    }
+}
+
+/// Parses `game.project`-style contents into a map of `section.key` to value.
+fn parse_ini(contents: &str) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    let mut section = "";
+
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim();
+        } else if let Some((key, value)) = line.split_once('=') {
+            values.insert(format!("{section}.{}", key.trim()), value.trim().to_owned());
+        }
+    }
+
+    values
+}
+
+/// Returns the name of the platform the engine is running on, as used by [`PlatformOverride`].
+///
+/// One of `"android"`, `"ios"`, `"macos"`, `"linux"`, `"windows"` or `"web"`.
+pub fn platform() -> &'static str {
+    if cfg!(target_os = "android") {
+        "android"
+    } else if cfg!(target_os = "ios") {
+        "ios"
+    } else if cfg!(target_os = "macos") {
+        "macos"
+    } else if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(any(target_os = "emscripten", target_arch = "wasm32")) {
+        "web"
+    } else {
+        "linux"
+    }
+}
+
+/// Ready-made plugin that lets `game.project` specify per-platform values.
+///
+/// When `section.key` is requested, `section.key.<platform>` is returned instead if it exists,
+/// where `<platform>` is the result of [`platform()`]:
+///
+/// ```ini
+/// [display]
+/// width = 960
+/// width.android = 720
+/// ```
+///
+/// # Examples
+/// ```ignore
+/// use dmsdk::*;
+///
+/// declare_configfile_extension!(PLATFORM_OVERRIDE, dmconfigfile::PlatformOverride);
+/// ```
+#[derive(Debug, Default)]
+pub struct PlatformOverride;
+
+impl ConfigPlugin for PlatformOverride {
+    fn create(_config: ConfigFile) -> Self {
+        Self
+    }
+
+    fn get_string(
+        &mut self,
+        config: ConfigFile,
+        key: &str,
+        _default_value: &str,
+    ) -> Option<String> {
+        let platform = platform();
+        if key.rsplit('.').next() == Some(platform) {
+            return None;
+        }
+
+        find_string(config, &format!("{key}.{platform}"))
+    }
+
+    fn get_int(&mut self, config: ConfigFile, key: &str, _default_value: i32) -> Option<i32> {
+        parse_value(self.get_string(config, key, ""))
+    }
+
+    fn get_float(&mut self, config: ConfigFile, key: &str, _default_value: f32) -> Option<f32> {
+        parse_value(self.get_string(config, key, ""))
+    }
+}
+
+#[doc(hidden)]
+pub type RawPluginLifecycle = unsafe extern "C" fn(dmConfigFile::HConfig);
+#[doc(hidden)]
+pub type RawPluginGetter<T> =
+    unsafe extern "C" fn(dmConfigFile::HConfig, *const c_char, T, *mut T) -> bool;
+#[doc(hidden)]
+pub type Desc = [u8; DESC_BUFFER_SIZE as usize];
+
+#[doc(hidden)]
+pub const DESC_BUFFER_SIZE: u32 = 64;
+
+/// Equivalent to `DM_DECLARE_CONFIGFILE_EXTENSION` in regular C++ extensions.
+///
+/// Registers the given [`ConfigPlugin`] under the name `$symbol`.
+///
+/// # Examples
+/// ```ignore
+/// use dmsdk::*;
+///
+/// struct MyConfigPlugin;
+///
+/// impl dmconfigfile::ConfigPlugin for MyConfigPlugin {
+///     fn create(_config: dmconfigfile::ConfigFile) -> Self {
+///         Self
 ///     }
-/// }
 ///
-/// fn get_int(config: dmconfigfile::ConfigFile, key: &str, default_value: i32) -> Option<i32> {
-///     if key == "custom_section.my_value" {
-///         Some(123)
-///     } else {
-///         None
+///     fn get_string(
+///         &mut self,
+///         _config: dmconfigfile::ConfigFile,
+///         key: &str,
+///         _default_value: &str,
+///     ) -> Option<String> {
+///         if key == "project.title" {
+///             Some("My project now!".to_owned())
+///         } else {
+///             None
+///         }
 ///     }
-/// }
 ///
-/// fn get_float(config: dmconfigfile::ConfigFile, key: &str, default_value: f32) -> Option<f32> {
-///     Some(default_value * 10.0)
+///     fn get_int(&mut self, _config: dmconfigfile::ConfigFile, key: &str, _default_value: i32) -> Option<i32> {
+///         if key == "custom_section.my_value" {
+///             Some(123)
+///         } else {
+///             None
+///         }
+///     }
+///
+///     fn get_float(&mut self, _config: dmconfigfile::ConfigFile, _key: &str, default_value: f32) -> Option<f32> {
+///         Some(default_value * 10.0)
+///     }
 /// }
 ///
-/// declare_configfile_extension!(
-///     MY_CONFIG_PLUGIN,
-///     Some(plugin_create),
-///     Some(plugin_destroy),
-///     Some(get_string),
-///     Some(get_int),
-///     Some(get_float)
-/// );
+/// declare_configfile_extension!(MY_CONFIG_PLUGIN, MyConfigPlugin);
 /// ```
 #[macro_export]
 macro_rules! declare_configfile_extension {
-    ($symbol:ident, $create:expr, $destroy:expr, $get_string:expr, $get_int:expr, $get_float:expr) => {
-        paste! {
-            static mut [<$symbol _PLUGIN_DESC>]: dmconfigfile::Desc = [0u8; dmconfigfile::DESC_BUFFER_SIZE as usize];
+    ($symbol:ident, $plugin:ty) => {
+        dmsdk::paste! {
+            static mut [<$symbol _PLUGIN_DESC>]: dmsdk::dmconfigfile::Desc = [0u8; dmsdk::dmconfigfile::DESC_BUFFER_SIZE as usize];
+            static [<$symbol _PLUGIN>]: dmsdk::dmconfigfile::PluginCell<$plugin> = dmsdk::dmconfigfile::PluginCell::new();
 
-            declare_plugin_lifecycle!([<$symbol _plugin_create>], $create);
-            declare_plugin_lifecycle!([<$symbol _plugin_destroy>], $destroy);
-            declare_plugin_string_getter!([<$symbol _plugin_get_string>], $get_string);
-            declare_plugin_getter!([<$symbol _plugin_get_int>], $get_int, i32);
-            declare_plugin_getter!([<$symbol _plugin_get_float>], $get_float, f32);
+            #[no_mangle]
+            unsafe extern "C" fn [<$symbol _plugin_create>](config: dmsdk::dmconfigfile::RawConfigFile) {
+                [<$symbol _PLUGIN>].create(config);
+            }
+
+            #[no_mangle]
+            unsafe extern "C" fn [<$symbol _plugin_destroy>](config: dmsdk::dmconfigfile::RawConfigFile) {
+                [<$symbol _PLUGIN>].destroy(config);
+            }
+
+            #[no_mangle]
+            unsafe extern "C" fn [<$symbol _plugin_get_string>](
+                config: dmsdk::dmconfigfile::RawConfigFile,
+                key: *const core::ffi::c_char,
+                default_value: *const core::ffi::c_char,
+                out: *mut *const core::ffi::c_char,
+            ) -> bool {
+                [<$symbol _PLUGIN>].get_string(config, key, default_value, out)
+            }
+
+            #[no_mangle]
+            unsafe extern "C" fn [<$symbol _plugin_get_int>](
+                config: dmsdk::dmconfigfile::RawConfigFile,
+                key: *const core::ffi::c_char,
+                default_value: i32,
+                out: *mut i32,
+            ) -> bool {
+                [<$symbol _PLUGIN>].get_int(config, key, default_value, out)
+            }
+
+            #[no_mangle]
+            unsafe extern "C" fn [<$symbol _plugin_get_float>](
+                config: dmsdk::dmconfigfile::RawConfigFile,
+                key: *const core::ffi::c_char,
+                default_value: f32,
+                out: *mut f32,
+            ) -> bool {
+                [<$symbol _PLUGIN>].get_float(config, key, default_value, out)
+            }
 
             #[no_mangle]
-            #[dmextension::ctor]
+            #[dmsdk::ctor]
             unsafe fn $symbol() {
-                dmconfigfile::register(
-                    &mut [<$symbol _PLUGIN_DESC>],
+                dmsdk::dmconfigfile::register(
+                    &mut *core::ptr::addr_of_mut!([<$symbol _PLUGIN_DESC>]),
                     stringify!($symbol),
                     [<$symbol _plugin_create>],
                     [<$symbol _plugin_destroy>],
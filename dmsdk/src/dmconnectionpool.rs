@@ -0,0 +1,313 @@
+//! Pool of keep-alive connections, with optional TLS.
+//!
+//! Connections returned to the pool are kept open and handed out again by [`Pool::dial()`] for the same host,
+//! port and TLS setting, which saves the TCP and TLS handshakes when talking to the same backend repeatedly.
+//!
+//! TLS connections only verify the server certificate if `network.ssl_certificates` is set in `game.project`,
+//! the same as [`TlsStream`](crate::dmsocket::TlsStream).
+
+use crate::dmsocket::{self, RawSocket, RawTlsSocket};
+use dmsdk_ffi::{dmConnectionPool, dmSocket};
+use std::{
+    ffi::CString,
+    fmt,
+    io::{self, Read, Write},
+    net::Shutdown,
+    sync::Arc,
+    time::Duration,
+};
+
+/// Connection pool error.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    OutOfResources,
+    Socket(dmsocket::Error),
+    HandshakeFailed,
+    ShutDown,
+    InvalidHost,
+    Unknown,
+}
+
+impl Error {
+    fn new(result: dmConnectionPool::Result, socket_result: dmSocket::Result) -> Self {
+        match result {
+            dmConnectionPool::Result_RESULT_OUT_OF_RESOURCES => Self::OutOfResources,
+            dmConnectionPool::Result_RESULT_SOCKET_ERROR => Self::Socket(socket_result.into()),
+            dmConnectionPool::Result_RESULT_HANDSHAKE_FAILED => Self::HandshakeFailed,
+            dmConnectionPool::Result_RESULT_SHUT_DOWN => Self::ShutDown,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfResources => f.write_str("connection pool is full"),
+            Self::Socket(err) => write!(f, "socket error: {err}"),
+            Self::HandshakeFailed => f.write_str("TLS handshake failed"),
+            Self::ShutDown => f.write_str("connection pool is shut down"),
+            Self::InvalidHost => f.write_str("host name contains a nul byte"),
+            Self::Unknown => f.write_str("unknown connection pool error"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Socket(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::Socket(err) => err.into(),
+            Error::InvalidHost => io::Error::new(io::ErrorKind::InvalidInput, err),
+            _ => io::Error::other(err),
+        }
+    }
+}
+
+/// [`Result`](core::result::Result) alias with an error type of [`Error`].
+pub type Result<T> = core::result::Result<T, Error>;
+
+struct RawPool(dmConnectionPool::HPool);
+
+// The engine's pool guards its state with a mutex
+unsafe impl Send for RawPool {}
+unsafe impl Sync for RawPool {}
+
+impl Drop for RawPool {
+    fn drop(&mut self) {
+        unsafe { dmConnectionPool::Delete(self.0) };
+    }
+}
+
+/// Connection pool handle. Clones refer to the same pool, which is deleted once every clone and
+/// [`Connection`] is dropped.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+/// use dmconnectionpool::Pool;
+/// use std::{
+///     io::{Read, Write},
+///     time::Duration,
+/// };
+///
+/// fn ping(pool: &Pool) -> std::io::Result<String> {
+///     let mut connection = pool.dial("example.com", 443, true, Duration::from_secs(5))?;
+///     write!(connection, "GET /ping HTTP/1.1\r\nHost: example.com\r\n\r\n")?;
+///
+///     let mut buf = [0; 1024];
+///     let len = connection.read(&mut buf)?;
+///     // Dropping the connection returns it to the pool for the next request
+///     Ok(String::from_utf8_lossy(&buf[..len]).into_owned())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Pool {
+    raw: Arc<RawPool>,
+}
+
+impl Pool {
+    /// Default maximum number of connections, open or kept alive.
+    pub const DEFAULT_MAX_CONNECTIONS: u32 = 64;
+    /// Default number of seconds an idle connection is kept alive.
+    pub const DEFAULT_MAX_KEEP_ALIVE: u32 = 10;
+
+    /// Creates a pool with the default limits.
+    pub fn new() -> Result<Self> {
+        Self::with_limits(Self::DEFAULT_MAX_CONNECTIONS, Self::DEFAULT_MAX_KEEP_ALIVE)
+    }
+
+    /// Creates a pool of at most `max_connections` connections, keeping idle ones alive for `max_keep_alive`
+    /// seconds.
+    pub fn with_limits(max_connections: u32, max_keep_alive: u32) -> Result<Self> {
+        let params = dmConnectionPool::Params {
+            m_MaxConnections: max_connections,
+            m_MaxKeepAlive: max_keep_alive,
+        };
+        let mut raw = std::ptr::null_mut();
+        let result = unsafe { dmConnectionPool::New(&params, &mut raw) };
+        if result != dmConnectionPool::Result_RESULT_OK {
+            return Err(Error::new(result, dmSocket::Result_RESULT_OK));
+        }
+
+        Ok(Self {
+            raw: Arc::new(RawPool(raw)),
+        })
+    }
+
+    /// Returns a connection to `host` on the given port, reusing a kept-alive one if possible.
+    ///
+    /// `timeout` limits both connecting and the TLS handshake.
+    pub fn dial(&self, host: &str, port: u16, tls: bool, timeout: Duration) -> Result<Connection> {
+        let host = CString::new(host).map_err(|_| Error::InvalidHost)?;
+        let timeout = timeout.as_micros().min(i32::MAX as u128) as i32;
+        let mut handle = 0;
+        let mut socket_result = dmSocket::Result_RESULT_OK;
+        let result = unsafe {
+            dmConnectionPool::Dial(
+                self.raw.0,
+                host.as_ptr(),
+                port,
+                tls,
+                timeout,
+                &mut handle,
+                &mut socket_result,
+            )
+        };
+        if result != dmConnectionPool::Result_RESULT_OK {
+            return Err(Error::new(result, socket_result));
+        }
+
+        Ok(Connection {
+            pool: self.clone(),
+            handle,
+            tls,
+            keep_alive: true,
+        })
+    }
+
+    /// Shuts down every connection in the pool. Returns how many were in use.
+    ///
+    /// Dialing fails with [`Error::ShutDown`] afterwards.
+    pub fn shutdown(&self, how: Shutdown) -> u32 {
+        let how = match how {
+            Shutdown::Read => dmSocket::ShutdownType_SHUTDOWNTYPE_READ,
+            Shutdown::Write => dmSocket::ShutdownType_SHUTDOWNTYPE_WRITE,
+            Shutdown::Both => dmSocket::ShutdownType_SHUTDOWNTYPE_READWRITE,
+        };
+        unsafe { dmConnectionPool::Shutdown(self.raw.0, how) }
+    }
+}
+
+impl fmt::Debug for Pool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Pool").field(&self.raw.0).finish()
+    }
+}
+
+/// Connection taken from a [`Pool`].
+///
+/// Dropping it returns it to the pool to be reused, unless it failed or [`Connection::close()`] was called.
+pub struct Connection {
+    pool: Pool,
+    handle: dmConnectionPool::HConnection,
+    tls: bool,
+    keep_alive: bool,
+}
+
+impl Connection {
+    /// Returns `true` if this connection uses TLS.
+    pub fn is_tls(&self) -> bool {
+        self.tls
+    }
+
+    /// Returns how many times this connection has been reused.
+    pub fn reuse_count(&self) -> u32 {
+        unsafe { dmConnectionPool::GetReuseCount(self.pool.raw.0, self.handle) }
+    }
+
+    /// Returns the raw socket of this connection.
+    pub fn raw_socket(&self) -> RawSocket {
+        unsafe { dmConnectionPool::GetSocket(self.pool.raw.0, self.handle) }
+    }
+
+    fn raw_tls_socket(&self) -> RawTlsSocket {
+        unsafe { dmConnectionPool::GetSSLSocket(self.pool.raw.0, self.handle) }
+    }
+
+    /// Sets how long a read may wait, [`None`] to wait forever.
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> dmsocket::Result<()> {
+        let timeout = dmsocket::timeout_micros(timeout);
+        let result = unsafe {
+            if self.tls {
+                dmsdk_ffi::dmSSLSocket::SetReceiveTimeout(self.raw_tls_socket(), timeout)
+            } else {
+                dmSocket::SetReceiveTimeout(self.raw_socket(), timeout)
+            }
+        };
+        match result {
+            dmSocket::Result_RESULT_OK => Ok(()),
+            err => Err(err.into()),
+        }
+    }
+
+    /// Closes this connection instead of returning it to the pool.
+    pub fn close(mut self) {
+        self.keep_alive = false;
+    }
+
+    fn track<T>(&mut self, result: dmsocket::Result<T>) -> io::Result<T> {
+        match result {
+            Err(
+                dmsocket::Error::WouldBlock
+                | dmsocket::Error::TryAgain
+                | dmsocket::Error::Interrupted,
+            ) => {}
+            Err(_) => self.keep_alive = false,
+            Ok(_) => {}
+        }
+        Ok(result?)
+    }
+}
+
+impl Read for Connection {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let result = if self.tls {
+            dmsocket::tls_receive(self.raw_tls_socket(), buf)
+        } else {
+            dmsocket::receive(self.raw_socket(), buf)
+        };
+        self.track(result)
+    }
+}
+
+impl Write for Connection {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let result = if self.tls {
+            dmsocket::tls_send(self.raw_tls_socket(), buf)
+        } else {
+            dmsocket::send(self.raw_socket(), buf)
+        };
+        self.track(result)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl dmsocket::AsRawSocket for Connection {
+    fn as_raw_socket(&self) -> RawSocket {
+        self.raw_socket()
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        unsafe {
+            if self.keep_alive {
+                dmConnectionPool::Return(self.pool.raw.0, self.handle);
+            } else {
+                dmConnectionPool::Close(self.pool.raw.0, self.handle);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Connection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Connection")
+            .field("handle", &self.handle)
+            .field("tls", &self.tls)
+            .finish()
+    }
+}
@@ -0,0 +1,314 @@
+//! Hashing, Base64 and encryption using the engine's implementations.
+//!
+//! Prefer these over external crates when binary size matters, since the engine already ships the code.
+
+use crate::lua;
+use dmsdk_ffi::dmCrypt;
+use std::{fmt, io};
+
+/// Encryption error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// Keys must be between 1 and [`MAX_KEY_LEN`] bytes long.
+    InvalidKey,
+    /// The input does not fit in a `u32`.
+    TooLarge,
+    /// The input is not valid Base64.
+    InvalidBase64,
+    /// The engine reported an error.
+    Failed,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidKey => write!(f, "key must be between 1 and {MAX_KEY_LEN} bytes long"),
+            Self::TooLarge => f.write_str("input is larger than 4 GiB"),
+            Self::InvalidBase64 => f.write_str("input is not valid Base64"),
+            Self::Failed => f.write_str("encryption failed"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        io::Error::new(io::ErrorKind::InvalidInput, err)
+    }
+}
+
+/// [`Result`](core::result::Result) alias with an error type of [`Error`].
+pub type Result<T> = core::result::Result<T, Error>;
+
+fn len(data: &[u8]) -> Result<u32> {
+    u32::try_from(data.len()).map_err(|_| Error::TooLarge)
+}
+
+macro_rules! hashers {
+    ($($(#[$meta:meta])* $func:ident, $raw:ident, $name:literal, $len:literal;)*) => {
+        $(
+            #[doc = concat!("Returns the ", $name, " digest of `data`.")]
+            ///
+            /// The engine only hashes whole buffers, so there's no streaming version.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `data` is larger than 4 GiB.
+            $(#[$meta])*
+            pub fn $func(data: &[u8]) -> [u8; $len] {
+                let mut digest = [0; $len];
+                unsafe {
+                    dmCrypt::$raw(data.as_ptr(), len(data).unwrap(), digest.as_mut_ptr());
+                }
+                digest
+            }
+        )*
+    };
+}
+
+hashers! {
+    md5, HashMd5, "MD5", 16;
+    sha1, HashSha1, "SHA-1", 20;
+    ///
+    /// # Examples
+    /// ```
+    /// use dmsdk::*;
+    ///
+    /// fn checksum(save_data: &[u8]) -> String {
+    ///     dmcrypt::to_hex(&dmcrypt::sha256(save_data))
+    /// }
+    /// ```
+    sha256, HashSha256, "SHA-256", 32;
+    sha512, HashSha512, "SHA-512", 64;
+}
+
+/// Returns `bytes` as a lowercase hexadecimal string, as commonly used to display digests.
+///
+/// # Examples
+/// ```
+/// use dmsdk::dmcrypt;
+///
+/// assert_eq!(dmcrypt::to_hex(&[0x00, 0xab, 0x10]), "00ab10");
+/// ```
+pub fn to_hex(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(hex, "{byte:02x}");
+    }
+    hex
+}
+
+/// Encodes `data` as standard, padded Base64.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// fn encode_save(save: &[u8]) -> String {
+///     dmcrypt::base64_encode(save).unwrap()
+/// }
+/// ```
+pub fn base64_encode(data: &[u8]) -> Result<String> {
+    // Four characters per started group of three bytes, plus the nul terminator the engine writes
+    let mut encoded = vec![0; data.len().div_ceil(3) * 4 + 1];
+    let mut encoded_len = len(&encoded)?;
+    let ok = unsafe {
+        dmCrypt::Base64Encode(
+            data.as_ptr(),
+            len(data)?,
+            encoded.as_mut_ptr(),
+            &mut encoded_len,
+        )
+    };
+    if !ok {
+        return Err(Error::Failed);
+    }
+
+    encoded.truncate(encoded_len as usize);
+    // Base64 output is always ASCII
+    Ok(String::from_utf8(encoded).unwrap())
+}
+
+/// Decodes standard, padded Base64.
+pub fn base64_decode(encoded: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    let encoded = encoded.as_ref();
+    let mut decoded = vec![0; encoded.len().div_ceil(4) * 3];
+    let mut decoded_len = len(&decoded)?;
+    let ok = unsafe {
+        dmCrypt::Base64Decode(
+            encoded.as_ptr(),
+            len(encoded)?,
+            decoded.as_mut_ptr(),
+            &mut decoded_len,
+        )
+    };
+    if !ok {
+        return Err(Error::InvalidBase64);
+    }
+
+    decoded.truncate(decoded_len as usize);
+    Ok(decoded)
+}
+
+/// Maximum key length accepted by [`encrypt()`] and [`decrypt()`].
+pub const MAX_KEY_LEN: usize = 16;
+
+/// Encryption algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Algorithm {
+    /// XTEA in counter mode. The output is the same length as the input.
+    #[default]
+    Xtea,
+}
+
+impl From<Algorithm> for dmCrypt::Algorithm {
+    fn from(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::Xtea => dmCrypt::Algorithm_ALGORITHM_XTEA,
+        }
+    }
+}
+
+type RawCrypt =
+    unsafe extern "C" fn(dmCrypt::Algorithm, *mut u8, u32, *const u8, u32) -> dmCrypt::Result;
+
+fn crypt(raw: RawCrypt, algorithm: Algorithm, data: &mut [u8], key: &[u8]) -> Result<()> {
+    if key.is_empty() || key.len() > MAX_KEY_LEN {
+        return Err(Error::InvalidKey);
+    }
+
+    let result = unsafe {
+        raw(
+            algorithm.into(),
+            data.as_mut_ptr(),
+            len(data)?,
+            key.as_ptr(),
+            key.len() as u32,
+        )
+    };
+    match result {
+        dmCrypt::Result_RESULT_OK => Ok(()),
+        _ => Err(Error::Failed),
+    }
+}
+
+/// Encrypts `data` in place.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+/// use dmcrypt::Algorithm;
+///
+/// fn obfuscate(save: &mut [u8]) -> dmcrypt::Result<()> {
+///     dmcrypt::encrypt(Algorithm::Xtea, save, b"aQj8CScgNP4VsfXK")
+/// }
+/// ```
+pub fn encrypt(algorithm: Algorithm, data: &mut [u8], key: &[u8]) -> Result<()> {
+    crypt(dmCrypt::Encrypt, algorithm, data, key)
+}
+
+/// Decrypts `data` in place.
+pub fn decrypt(algorithm: Algorithm, data: &mut [u8], key: &[u8]) -> Result<()> {
+    crypt(dmCrypt::Decrypt, algorithm, data, key)
+}
+
+macro_rules! lua_hashers {
+    ($($func:ident),*) => {
+        $(
+            pub(super) extern "C" fn $func(l: lua::StatePtr) -> i32 {
+                let l = unsafe { lua::State::new(l) };
+                let data = lua::check_bytes(l, 1);
+                lua::push_bytes(l, &super::$func(&data));
+                1
+            }
+        )*
+    };
+}
+
+mod lua_functions {
+    use super::{Algorithm, Error};
+    use crate::lua;
+
+    lua_hashers!(md5, sha1, sha256, sha512);
+
+    pub(super) extern "C" fn base64_encode(l: lua::StatePtr) -> i32 {
+        let l = unsafe { lua::State::new(l) };
+        let data = lua::check_bytes(l, 1);
+        match super::base64_encode(&data) {
+            Ok(encoded) => lua::push_string(l, &encoded),
+            Err(err) => {
+                lua::error!(l, "{err}");
+            }
+        }
+        1
+    }
+
+    pub(super) extern "C" fn base64_decode(l: lua::StatePtr) -> i32 {
+        let l = unsafe { lua::State::new(l) };
+        let encoded = lua::check_bytes(l, 1);
+        match super::base64_decode(encoded) {
+            Ok(decoded) => lua::push_bytes(l, &decoded),
+            Err(err) => {
+                lua::error!(l, "{err}");
+            }
+        }
+        1
+    }
+
+    fn crypt(l: lua::State, encrypt: bool) -> i32 {
+        let mut data = lua::check_bytes(l, 1);
+        let key = lua::check_bytes(l, 2);
+        let result = if encrypt {
+            super::encrypt(Algorithm::Xtea, &mut data, &key)
+        } else {
+            super::decrypt(Algorithm::Xtea, &mut data, &key)
+        };
+        match result {
+            Ok(()) => lua::push_bytes(l, &data),
+            Err(Error::InvalidKey) => {
+                lua::error!(l, "bad argument #2 ({})", Error::InvalidKey);
+            }
+            Err(err) => {
+                lua::error!(l, "{err}");
+            }
+        }
+        1
+    }
+
+    pub(super) extern "C" fn encrypt_xtea(l: lua::StatePtr) -> i32 {
+        crypt(unsafe { lua::State::new(l) }, true)
+    }
+
+    pub(super) extern "C" fn decrypt_xtea(l: lua::StatePtr) -> i32 {
+        crypt(unsafe { lua::State::new(l) }, false)
+    }
+}
+
+/// Lua versions of this module's functions, to be passed to [`lua::register()`].
+///
+/// Hashes are returned as raw binary strings, like Defold's own `crypt` module does.
+/// Registering them into an existing table adds to it, so they can share a module with your own functions.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// fn lua_init(l: lua::State) {
+///     // rust.sha256(str), rust.base64_encode(str), rust.encrypt_xtea(data, key), ...
+///     lua::register(l, "rust", dmcrypt::LUA_FUNCTIONS);
+///     lua::pop(l, 1);
+/// }
+/// ```
+pub const LUA_FUNCTIONS: lua::Reg = &[
+    ("md5", lua_functions::md5),
+    ("sha1", lua_functions::sha1),
+    ("sha256", lua_functions::sha256),
+    ("sha512", lua_functions::sha512),
+    ("base64_encode", lua_functions::base64_encode),
+    ("base64_decode", lua_functions::base64_decode),
+    ("encrypt_xtea", lua_functions::encrypt_xtea),
+    ("decrypt_xtea", lua_functions::decrypt_xtea),
+];
@@ -1,4 +1,7 @@
 //! Functions for interacting with the engine.
+//!
+//! These are kept for compatibility, [`AppParams`](dmextension::AppParams) and [`Params`](dmextension::Params)
+//! provide the same engine systems directly.
 
 use dmsdk_ffi::dmEngine;
 
@@ -10,17 +13,19 @@ pub fn get_config_file(app_params: dmextension::AppParams) -> dmconfigfile::Conf
 }
 
 /// Get the web server from an instance of [`AppParams`](dmextension::AppParams).
+#[deprecated(note = "use `AppParams::web_server()` instead")]
 pub fn get_web_server(app_params: dmextension::AppParams) -> dmwebserver::Server {
-    unsafe { dmEngine::GetWebServer(app_params.ptr) }
+    app_params.web_server()
 }
 
 /// Get the game object register from an instance of [`AppParams`](dmextension::AppParams).
+#[deprecated(note = "use `AppParams::game_object_register()` instead")]
 pub fn get_game_object_register(app_params: dmextension::AppParams) -> dmgameobject::Register {
-    unsafe { dmEngine::GetGameObjectRegister(app_params.ptr) }
+    app_params.game_object_register()
 }
 
 /// Get the HID context from an instance of [`AppParams`](dmextension::AppParams).
+#[deprecated(note = "use `AppParams::hid_context()` instead")]
 pub fn get_hid_context(app_params: dmextension::AppParams) -> dmhid::Context {
-    let ptr = unsafe { dmEngine::GetHIDContext(app_params.ptr) };
-    dmhid::Context::new(ptr)
+    app_params.hid_context()
 }
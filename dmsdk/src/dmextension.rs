@@ -1,9 +1,14 @@
 //! Functions for creating and controlling engine native extension libraries.
 
 use crate::*;
-use dmsdk_ffi::dmExtension;
+use dmsdk_ffi::{dmEngine, dmExtension, dmResource};
 use libc::c_void;
-use std::ffi::CString;
+use std::{
+    cell::{Cell, RefCell, UnsafeCell},
+    ffi::CString,
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
 
 #[doc(hidden)]
 pub use ctor::ctor;
@@ -32,14 +37,22 @@ pub enum Result {
 }
 
 /// Event to be handled by [`Extension::on_event`].
-#[allow(missing_docs)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Event {
+    /// The app gained focus.
     ActivateApp,
+    /// The app lost focus.
     DeactivateApp,
+    /// The app window was minimized. Only sent on desktop platforms.
     IconifyApp,
+    /// The app window was restored. Only sent on desktop platforms.
     DeiconifyApp,
-    Unknown,
+    /// The engine finished initializing.
+    EngineInitialized,
+    /// The engine is about to be deleted.
+    EngineDelete,
+    /// An event this version of `dmsdk` doesn't know about, with its raw ID.
+    Unknown(u32),
 }
 
 impl From<Result> for i32 {
@@ -54,11 +67,13 @@ impl From<Result> for i32 {
 impl From<u32> for Event {
     fn from(id: u32) -> Self {
         match id {
-            0 => Self::ActivateApp,
-            1 => Self::DeactivateApp,
-            2 => Self::IconifyApp,
-            3 => Self::DeiconifyApp,
-            _ => Self::Unknown,
+            dmExtension::EventID_EVENT_ID_ACTIVATEAPP => Self::ActivateApp,
+            dmExtension::EventID_EVENT_ID_DEACTIVATEAPP => Self::DeactivateApp,
+            dmExtension::EventID_EVENT_ID_ICONIFYAPP => Self::IconifyApp,
+            dmExtension::EventID_EVENT_ID_DEICONIFYAPP => Self::DeiconifyApp,
+            dmExtension::EventID_EVENT_ID_ENGINE_INITIALIZED => Self::EngineInitialized,
+            dmExtension::EventID_EVENT_ID_ENGINE_DELETE => Self::EngineDelete,
+            _ => Self::Unknown(id),
         }
     }
 }
@@ -69,6 +84,23 @@ impl From<i32> for Event {
     }
 }
 
+impl From<Event> for u32 {
+    fn from(event: Event) -> Self {
+        match event {
+            Event::ActivateApp => dmExtension::EventID_EVENT_ID_ACTIVATEAPP,
+            Event::DeactivateApp => dmExtension::EventID_EVENT_ID_DEACTIVATEAPP,
+            Event::IconifyApp => dmExtension::EventID_EVENT_ID_ICONIFYAPP,
+            Event::DeiconifyApp => dmExtension::EventID_EVENT_ID_DEICONIFYAPP,
+            Event::EngineInitialized => dmExtension::EventID_EVENT_ID_ENGINE_INITIALIZED,
+            Event::EngineDelete => dmExtension::EventID_EVENT_ID_ENGINE_DELETE,
+            Event::Unknown(id) => id,
+        }
+    }
+}
+
+/// Resource factory handle.
+pub type ResourceFactory = dmResource::HFactory;
+
 /// Params passed to [`Extension::app_init`] and [`Extension::app_final`].
 #[derive(Clone, Copy)]
 pub struct AppParams {
@@ -83,6 +115,8 @@ pub struct AppParams {
 pub struct Params {
     /// Project config file.
     pub config: dmconfigfile::ConfigFile,
+    /// Resource factory.
+    pub resource_factory: ResourceFactory,
     /// Lua state.
     pub l: lua::State,
     #[doc(hidden)]
@@ -98,6 +132,48 @@ impl AppParams {
             ptr: params,
         }
     }
+
+    /// Returns the engine's web server.
+    pub fn web_server(&self) -> dmwebserver::Server {
+        unsafe { dmEngine::GetWebServer(self.ptr) }.into()
+    }
+
+    /// Returns the game object register.
+    pub fn game_object_register(&self) -> dmgameobject::Register {
+        unsafe { dmEngine::GetGameObjectRegister(self.ptr) }
+    }
+
+    /// Returns the HID context.
+    pub fn hid_context(&self) -> dmhid::Context {
+        dmhid::Context::new(unsafe { dmEngine::GetHIDContext(self.ptr) })
+    }
+}
+
+/// Engine systems that are only reachable through [`AppParams`], kept around for [`Params`].
+#[derive(Clone, Copy)]
+struct EngineContext {
+    web_server: dmwebserver::Server,
+    game_object_register: dmgameobject::Register,
+    hid_context: dmhid::Context,
+}
+
+thread_local! {
+    static ENGINE_CONTEXT: Cell<Option<EngineContext>> = const { Cell::new(None) };
+}
+
+impl EngineContext {
+    fn capture(params: AppParams) {
+        let context = Self {
+            web_server: params.web_server(),
+            game_object_register: params.game_object_register(),
+            hid_context: params.hid_context(),
+        };
+        ENGINE_CONTEXT.with(|engine| engine.set(Some(context)));
+    }
+
+    fn get() -> Option<Self> {
+        ENGINE_CONTEXT.with(Cell::get)
+    }
 }
 
 impl Params {
@@ -106,153 +182,518 @@ impl Params {
     pub unsafe fn from(params: RawParams) -> Self {
         Self {
             config: (*params).m_ConfigFile.into(),
+            resource_factory: (*params).m_ResourceFactory,
             l: lua::State::new((*params).m_L),
             ptr: params,
         }
     }
+
+    /// Returns the engine's web server.
+    ///
+    /// Returns [`None`] if called off the main thread.
+    pub fn web_server(&self) -> Option<dmwebserver::Server> {
+        EngineContext::get().map(|engine| engine.web_server)
+    }
+
+    /// Returns the game object register.
+    ///
+    /// Returns [`None`] if called off the main thread.
+    pub fn game_object_register(&self) -> Option<dmgameobject::Register> {
+        EngineContext::get().map(|engine| engine.game_object_register)
+    }
+
+    /// Returns the HID context.
+    ///
+    /// Returns [`None`] if called off the main thread.
+    pub fn hid_context(&self) -> Option<dmhid::Context> {
+        EngineContext::get().map(|engine| engine.hid_context)
+    }
 }
 
-pub trait Extension {
-    fn app_init(&mut self, params: AppParams) -> Result {
+/// Native extension, registered with [`declare_extension!`].
+///
+/// The extension is created right before [`Extension::app_init`] and dropped right after [`Extension::app_final`],
+/// so restarting the engine (e.g. with `sys.reboot()`) starts with fresh state.
+///
+/// All callbacks run on the main thread, so the extension doesn't need to be [`Send`].
+/// Use [`DeclaredExtension::with`] to reach the extension from elsewhere on the main thread (e.g. Lua functions),
+/// and [`SharedState`] for any state that worker threads need.
+pub trait Extension: 'static {
+    /// Called when the app starts, right after the extension is created.
+    fn app_init(&mut self, _params: AppParams) -> Result {
         Result::Ok
     }
-    fn app_final(&mut self, params: AppParams) -> Result {
+
+    /// Called when the app shuts down, right before the extension is dropped.
+    fn app_final(&mut self, _params: AppParams) -> Result {
         Result::Ok
     }
-    fn ext_init(&mut self, params: Params) -> Result {
+
+    /// Called when the engine initializes the extension, after [`Extension::app_init`].
+    fn ext_init(&mut self, _params: Params) -> Result {
         Result::Ok
     }
-    fn ext_final(&mut self, params: Params) -> Result {
+
+    /// Called when the engine finalizes the extension, before [`Extension::app_final`].
+    fn ext_final(&mut self, _params: Params) -> Result {
         Result::Ok
     }
-    fn on_update(&mut self, params: Params) -> Result {
+
+    /// Called once per frame.
+    fn on_update(&mut self, _params: Params) -> Result {
         Result::Ok
     }
-    fn on_event(&mut self, params: Params, event: Event) {}
+
+    /// Called when the engine sends an [`Event`].
+    fn on_event(&mut self, _params: Params, _event: Event) {}
+
+    /// Returns a short human-readable description of the extension's state, shown by tools like
+    /// [`debug_server`](crate::debug_server).
+    fn debug_summary(&self) -> Option<String> {
+        None
+    }
 }
 
-#[doc(hidden)]
-#[macro_export]
-macro_rules! __app_callback {
-    ($ext_name:ident, $fn_name:ident) => {
-		dmsdk::paste! {
-			#[no_mangle]
-			unsafe extern "C" fn [<$ext_name:snake:lower _ $fn_name>](params: dmsdk::dmextension::RawAppParams) -> i32 {
-				[<$ext_name:snake:upper>].lock().unwrap_or_else(|err| {
-					panic!(
-						"failed to lock mutex for {}: {}",
-						stringify!($ext_name),
-						err
-					)
-				})
-				.$fn_name(dmsdk::dmextension::AppParams::from(params)).into()
-			}
-		}
-	};
+/// Implemented for every extension declared with [`declare_extension!`].
+pub trait DeclaredExtension: Extension + Sized {
+    #[doc(hidden)]
+    fn __cell() -> &'static ExtensionCell<Self>;
+
+    /// Runs `f` with the extension.
+    ///
+    /// Fails instead of blocking if called off the main thread, before the extension is created,
+    /// or while the extension is already in use further up the stack
+    /// (e.g. from a Lua function called from [`Extension::on_update`]).
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use dmsdk::*;
+    /// use dmextension::DeclaredExtension;
+    ///
+    /// #[derive(Default)]
+    /// struct Counter {
+    ///     count: isize,
+    /// }
+    ///
+    /// impl dmextension::Extension for Counter {}
+    ///
+    /// fn increment(l: lua::State) -> i32 {
+    ///     match Counter::with(|counter| {
+    ///         counter.count += 1;
+    ///         counter.count
+    ///     }) {
+    ///         Ok(count) => lua::push_integer(l, count),
+    ///         Err(err) => lua::error!(l, "{err}"),
+    ///     }
+    ///
+    ///     1
+    /// }
+    ///
+    /// declare_extension!(Counter);
+    /// # fn main() {}
+    /// ```
+    fn with<R>(f: impl FnOnce(&mut Self) -> R) -> core::result::Result<R, AccessError> {
+        Self::__cell().with(f)
+    }
 }
 
-#[doc(hidden)]
-#[macro_export]
-macro_rules! __ext_callback {
-    ($ext_name:ident, $fn_name:ident) => {
-		dmsdk::paste! {
-			#[no_mangle]
-			unsafe extern "C" fn [<$ext_name:snake:lower _ $fn_name>](params: dmsdk::dmextension::RawParams) -> i32 {
-				[<$ext_name:snake:upper>].lock().unwrap_or_else(|err| {
-					panic!(
-						"failed to lock mutex for {}: {}",
-						stringify!($ext_name),
-						err
-					)
-				})
-				.$fn_name(dmsdk::dmextension::Params::from(params)).into()
-			}
-		}
-	};
+/// Reason the extension couldn't be accessed by [`DeclaredExtension::with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessError {
+    /// Extensions can only be accessed from the main thread.
+    WrongThread,
+    /// The extension hasn't been created yet, or has already been dropped.
+    NotCreated,
+    /// The extension is already in use further up the stack.
+    AlreadyInUse,
 }
 
+impl std::fmt::Display for AccessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::WrongThread => "extensions can only be accessed from the main thread",
+            Self::NotCreated => "extension hasn't been created",
+            Self::AlreadyInUse => "extension is already in use (re-entrant call)",
+        })
+    }
+}
+
+impl std::error::Error for AccessError {}
+
+thread_local! {
+    static IS_MAIN_THREAD: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Information about an extension declared with [`declare_extension!`], see [`extensions()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionInfo {
+    /// Name the extension was declared with.
+    pub name: &'static str,
+    /// Whether the extension currently exists, i.e. it's between [`Extension::app_init`] and [`Extension::app_final`].
+    pub created: bool,
+    /// Result of [`Extension::debug_summary`], or [`None`] if the extension is in use further up the stack.
+    pub summary: Option<String>,
+}
+
+trait DynExtensionCell {
+    fn info(&self) -> ExtensionInfo;
+}
+
+thread_local! {
+    static EXTENSIONS: RefCell<Vec<&'static dyn DynExtensionCell>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns every extension declared with [`declare_extension!`] that has been initialized at least once.
+///
+/// Returns an empty list if called off the main thread.
+pub fn extensions() -> Vec<ExtensionInfo> {
+    EXTENSIONS.with(|extensions| extensions.borrow().iter().map(|cell| cell.info()).collect())
+}
+
+/// Returns `true` if called from the thread the engine runs extensions on.
+///
+/// Always returns `false` before the first extension is initialized.
+pub fn is_main_thread() -> bool {
+    IS_MAIN_THREAD.with(Cell::get)
+}
+
+/// State that can be shared between an extension and worker threads.
+///
+/// This is a thin wrapper around an [`Arc`]`<`[`Mutex`]`<T>>`, cloning it creates a new handle to the same state.
+#[derive(Debug, Default)]
+pub struct SharedState<T> {
+    inner: Arc<Mutex<T>>,
+}
+
+impl<T> SharedState<T> {
+    /// Creates a new [`SharedState`] holding `value`.
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(value)),
+        }
+    }
+
+    /// Locks the state, blocking the current thread until it's available.
+    ///
+    /// A panic on another thread while it held the lock doesn't make the state inaccessible.
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Clone for SharedState<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+/// Main thread only storage for an [`Extension`] declared with [`declare_extension!`].
 #[doc(hidden)]
-#[macro_export]
-macro_rules! __event_callback {
-    ($ext_name:ident) => {
-		dmsdk::paste! {
-			#[no_mangle]
-			unsafe extern "C" fn [<$ext_name:snake:lower _on_event>](params: dmsdk::dmextension::RawParams, event: dmsdk::dmextension::RawEvent) {
-				[<$ext_name:snake:upper>].lock().unwrap_or_else(|err| {
-					panic!(
-						"failed to lock mutex for {}: {}",
-						stringify!($ext_name),
-						err
-					)
-				})
-				.on_event(dmsdk::dmextension::Params::from(params), dmsdk::dmextension::Event::from((*event).m_Event));
-			}
-		}
-	};
+pub struct ExtensionCell<T> {
+    name: &'static str,
+    in_use: Cell<bool>,
+    state: UnsafeCell<Option<T>>,
+}
+
+// SAFETY: The state is only ever touched after checking that we're on the main thread
+unsafe impl<T> Sync for ExtensionCell<T> {}
+
+struct CellGuard<'a, T> {
+    cell: &'a ExtensionCell<T>,
+}
+
+impl<T> Deref for CellGuard<'_, T> {
+    type Target = Option<T>;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The guard has exclusive access to the state while it exists
+        unsafe { &*self.cell.state.get() }
+    }
+}
+
+impl<T> DerefMut for CellGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: The guard has exclusive access to the state while it exists
+        unsafe { &mut *self.cell.state.get() }
+    }
+}
+
+impl<T> Drop for CellGuard<'_, T> {
+    fn drop(&mut self) {
+        self.cell.in_use.set(false);
+    }
+}
+
+impl<T: Extension> DynExtensionCell for ExtensionCell<T> {
+    fn info(&self) -> ExtensionInfo {
+        let (created, summary) = match self.borrow() {
+            Ok(state) => (
+                state.is_some(),
+                state.as_ref().and_then(Extension::debug_summary),
+            ),
+            Err(_) => (true, None),
+        };
+
+        ExtensionInfo {
+            name: self.name,
+            created,
+            summary,
+        }
+    }
+}
+
+impl<T: Extension> ExtensionCell<T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            in_use: Cell::new(false),
+            state: UnsafeCell::new(None),
+        }
+    }
+
+    fn borrow(&self) -> core::result::Result<CellGuard<'_, T>, AccessError> {
+        if !is_main_thread() {
+            return Err(AccessError::WrongThread);
+        }
+        if self.in_use.replace(true) {
+            return Err(AccessError::AlreadyInUse);
+        }
+
+        Ok(CellGuard { cell: self })
+    }
+
+    fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> core::result::Result<R, AccessError> {
+        let mut state = self.borrow()?;
+        let extension = state.as_mut().ok_or(AccessError::NotCreated)?;
+        Ok(f(extension))
+    }
+
+    fn report(&self, callback: &str, err: AccessError) -> i32 {
+        dmlog::error!("{}::{callback} failed: {err}", self.name);
+        Result::InitError.into()
+    }
+
+    /// # Safety
+    ///
+    /// `params` must point to valid app params.
+    pub unsafe fn app_init(
+        &'static self,
+        params: RawAppParams,
+        constructor: fn(AppParams) -> T,
+    ) -> i32 {
+        IS_MAIN_THREAD.with(|is_main_thread| is_main_thread.set(true));
+        EXTENSIONS.with(|extensions| {
+            let mut extensions = extensions.borrow_mut();
+            let this: &'static dyn DynExtensionCell = self;
+            if !extensions.iter().any(|cell| std::ptr::addr_eq(*cell, this)) {
+                extensions.push(this);
+            }
+        });
+
+        let params = AppParams::from(params);
+        EngineContext::capture(params);
+        let extension = constructor(params);
+        match self.borrow() {
+            Ok(mut state) => state.insert(extension).app_init(params).into(),
+            Err(err) => self.report("app_init", err),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `params` must point to valid app params.
+    pub unsafe fn app_final(&self, params: RawAppParams) -> i32 {
+        match self.borrow() {
+            Ok(mut state) => {
+                let result = match state.as_mut() {
+                    Some(extension) => extension.app_final(AppParams::from(params)),
+                    None => Result::Ok,
+                };
+                *state = None;
+                result.into()
+            }
+            Err(err) => self.report("app_final", err),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `params` must point to valid extension params.
+    pub unsafe fn callback(
+        &self,
+        name: &str,
+        params: RawParams,
+        f: fn(&mut T, Params) -> Result,
+    ) -> i32 {
+        match self.borrow() {
+            Ok(mut state) => match state.as_mut() {
+                Some(extension) => f(extension, Params::from(params)).into(),
+                None => Result::Ok.into(),
+            },
+            Err(err) => self.report(name, err),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `params` must point to valid extension params.
+    pub unsafe fn on_update(&'static self, params: RawParams) -> i32 {
+        // Main thread jobs run once per frame, ahead of the first extension that was initialized
+        let first = EXTENSIONS.with(|extensions| {
+            let this: &'static dyn DynExtensionCell = self;
+            extensions
+                .borrow()
+                .first()
+                .is_some_and(|cell| std::ptr::addr_eq(*cell, this))
+        });
+        if first {
+            mainthread::drain();
+            #[cfg(feature = "async")]
+            executor::poll();
+        }
+
+        self.callback("on_update", params, T::on_update)
+    }
+
+    /// # Safety
+    ///
+    /// `params` and `event` must point to valid extension params and a valid event.
+    pub unsafe fn on_event(&self, params: RawParams, event: RawEvent) {
+        match self.borrow() {
+            Ok(mut state) => {
+                if let Some(extension) = state.as_mut() {
+                    extension.on_event(Params::from(params), Event::from((*event).m_Event));
+                }
+            }
+            Err(err) => {
+                self.report("on_event", err);
+            }
+        }
+    }
 }
 
 /// Equivalent to `DM_DECLARE_EXTENSION` in regular C++ extensions.
 ///
+/// By default the extension is created with [`Default::default()`].
+/// Pass a constructor as the second argument to create it from the [`AppParams`] instead.
+/// Any number of extensions can be declared in the same crate.
+///
 /// # Examples
-/// ```
+/// ```ignore
 /// use dmsdk::*;
-/// use dmextension::{Params, Event, Extension}
+/// use dmextension::{AppParams, Event, Extension, Params};
+///
+/// const LOG_DOMAIN: &str = "MY_EXTENSION";
 ///
 /// #[derive(Default)]
 /// struct MyExtension;
 ///
 /// // See the Extension trait documentation for all available functions
 /// impl Extension for MyExtension {
-///     fn ext_init(&mut self, params: Params) -> dmextension::Result {
+///     fn ext_init(&mut self, _params: Params) -> dmextension::Result {
 ///         dmlog::info!("Registered extension MyExtension");
 ///
 ///         dmextension::Result::Ok
 ///     }
 ///
-///     fn on_event(&mut self, params: Params, event: Event) {
-///         dmlong::info!("Received event: {:?}", event);
+///     fn on_event(&mut self, _params: Params, event: Event) {
+///         dmlog::info!("Received event: {:?}", event);
 ///     }
 /// }
 ///
+/// struct Settings {
+///     width: i32,
+/// }
+///
+/// impl Settings {
+///     fn new(params: AppParams) -> Self {
+///         Self {
+///             width: dmconfigfile::get_int(params.config, "display.width", 960),
+///         }
+///     }
+/// }
+///
+/// impl Extension for Settings {}
+///
 /// declare_extension!(MyExtension);
+/// declare_extension!(Settings, Settings::new);
+/// # fn main() {}
 /// ```
 #[macro_export]
 macro_rules! declare_extension {
     ($name:ident) => {
+        dmsdk::declare_extension!($name, |_| ::core::default::Default::default());
+    };
+    ($name:ident, $constructor:expr) => {
         dmsdk::paste! {
-			const LOG_DOMAIN: &str = stringify!([<$name:upper>]);
-			mod __init_extension {
-				use super::$name as [<$name Struct>];
-				use dmsdk::dmextension::Extension;
-
-				static mut [<$name:snake:upper _DESC>]: dmsdk::dmextension::Desc = [0u8; dmsdk::dmextension::DESC_BUFFER_SIZE];
-
-				dmsdk::lazy_static! {
-					static ref [<$name:snake:upper>]: std::sync::Mutex<[<$name Struct>]> = std::sync::Mutex::new([<$name Struct>]::default());
-				}
-
-				dmsdk::__app_callback!($name, app_init);
-				dmsdk::__app_callback!($name, app_final);
-				dmsdk::__ext_callback!($name, ext_init);
-				dmsdk::__ext_callback!($name, ext_final);
-				dmsdk::__ext_callback!($name, on_update);
-				dmsdk::__event_callback!($name);
-
-				#[allow(non_snake_case)]
-				#[no_mangle]
-				unsafe fn $name() {
-					dmsdk::dmextension::__register(
-						stringify!($name),
-						&mut [<$name:snake:upper _DESC>],
-						[<$name:snake:lower _app_init>],
-						[<$name:snake:lower _app_final>],
-						[<$name:snake:lower _ext_init>],
-						[<$name:snake:lower _ext_final>],
-						[<$name:snake:lower _on_update>],
-						[<$name:snake:lower _on_event>],
-					);
-				}
-			}
+            #[doc(hidden)]
+            #[allow(non_snake_case)]
+            mod [<__extension_ $name:snake>] {
+                use super::*;
+
+                static mut [<$name:snake:upper _DESC>]: dmsdk::dmextension::Desc = [0u8; dmsdk::dmextension::DESC_BUFFER_SIZE];
+                static [<$name:snake:upper _EXTENSION>]: dmsdk::dmextension::ExtensionCell<$name> = dmsdk::dmextension::ExtensionCell::new(stringify!($name));
+
+                impl dmsdk::dmextension::DeclaredExtension for $name {
+                    fn __cell() -> &'static dmsdk::dmextension::ExtensionCell<Self> {
+                        &[<$name:snake:upper _EXTENSION>]
+                    }
+                }
+
+                #[no_mangle]
+                unsafe extern "C" fn [<$name:snake:lower _app_init>](params: dmsdk::dmextension::RawAppParams) -> i32 {
+                    dmsdk::dmprofile::scope!(concat!(stringify!($name), "::app_init"));
+                    let constructor: fn(dmsdk::dmextension::AppParams) -> $name = $constructor;
+                    [<$name:snake:upper _EXTENSION>].app_init(params, constructor)
+                }
+
+                #[no_mangle]
+                unsafe extern "C" fn [<$name:snake:lower _app_final>](params: dmsdk::dmextension::RawAppParams) -> i32 {
+                    dmsdk::dmprofile::scope!(concat!(stringify!($name), "::app_final"));
+                    [<$name:snake:upper _EXTENSION>].app_final(params)
+                }
+
+                #[no_mangle]
+                unsafe extern "C" fn [<$name:snake:lower _ext_init>](params: dmsdk::dmextension::RawParams) -> i32 {
+                    dmsdk::dmprofile::scope!(concat!(stringify!($name), "::ext_init"));
+                    [<$name:snake:upper _EXTENSION>].callback("ext_init", params, <$name as dmsdk::dmextension::Extension>::ext_init)
+                }
+
+                #[no_mangle]
+                unsafe extern "C" fn [<$name:snake:lower _ext_final>](params: dmsdk::dmextension::RawParams) -> i32 {
+                    dmsdk::dmprofile::scope!(concat!(stringify!($name), "::ext_final"));
+                    [<$name:snake:upper _EXTENSION>].callback("ext_final", params, <$name as dmsdk::dmextension::Extension>::ext_final)
+                }
+
+                #[no_mangle]
+                unsafe extern "C" fn [<$name:snake:lower _on_update>](params: dmsdk::dmextension::RawParams) -> i32 {
+                    dmsdk::dmprofile::scope!(concat!(stringify!($name), "::on_update"));
+                    [<$name:snake:upper _EXTENSION>].on_update(params)
+                }
+
+                #[no_mangle]
+                unsafe extern "C" fn [<$name:snake:lower _on_event>](params: dmsdk::dmextension::RawParams, event: dmsdk::dmextension::RawEvent) {
+                    dmsdk::dmprofile::scope!(concat!(stringify!($name), "::on_event"));
+                    [<$name:snake:upper _EXTENSION>].on_event(params, event)
+                }
+
+                #[allow(non_snake_case)]
+                #[no_mangle]
+                unsafe fn $name() {
+                    dmsdk::dmextension::__register(
+                        stringify!($name),
+                        &mut *::core::ptr::addr_of_mut!([<$name:snake:upper _DESC>]),
+                        [<$name:snake:lower _app_init>],
+                        [<$name:snake:lower _app_final>],
+                        [<$name:snake:lower _ext_init>],
+                        [<$name:snake:lower _ext_final>],
+                        [<$name:snake:lower _on_update>],
+                        [<$name:snake:lower _on_event>],
+                    );
+                }
+            }
         }
     };
 }
@@ -274,7 +715,7 @@ pub fn __register(
     unsafe {
         dmsdk_ffi::ExtensionRegister(
             desc.as_mut_ptr() as *mut c_void,
-            11,
+            desc.len() as u32,
             name.as_ptr(),
             Some(app_init),
             Some(app_final),
@@ -1,13 +1,16 @@
 use std::fmt::Debug;
 
-use crate::dmvmath::{Point3, Quat, Vector3};
+use crate::{
+    dmhash::Hash,
+    dmvmath::{Point3, Quat, Vector3},
+};
 
 use dmsdk_ffi::dmGameObject;
 
 /// Game object register.
 pub type Register = dmGameObject::HRegister;
 
-const UNNAMED_IDENTIFIER: u64 = 12415623704795185700;
+const UNNAMED_IDENTIFIER: Hash = Hash::from_bytes(b"__unnamed__");
 
 #[allow(missing_docs)]
 pub enum Error {
@@ -62,8 +65,8 @@ impl Instance {
     }
 
     /// Returns the ID of this game object, if it has one.
-    pub fn id(&self) -> Option<u64> {
-        let hash = unsafe { dmGameObject::GetIdentifier(self.ptr) };
+    pub fn id(&self) -> Option<Hash> {
+        let hash = Hash::from_raw(unsafe { dmGameObject::GetIdentifier(self.ptr) });
         if hash == UNNAMED_IDENTIFIER {
             None
         } else {
@@ -77,18 +80,18 @@ impl Instance {
     }
 
     /// Sets the position of this game object.
-    pub fn set_position(&self, position: Point3) {
-        unsafe { dmGameObject::SetPosition(self.ptr, position.into()) }
+    pub fn set_position(&self, position: impl Into<Point3>) {
+        unsafe { dmGameObject::SetPosition(self.ptr, position.into().into()) }
     }
 
     /// Sets the rotation of this game object.
-    pub fn set_rotation(&self, rotation: Quat) {
-        unsafe { dmGameObject::SetRotation(self.ptr, rotation.into()) }
+    pub fn set_rotation(&self, rotation: impl Into<Quat>) {
+        unsafe { dmGameObject::SetRotation(self.ptr, rotation.into().into()) }
     }
 
     /// Sets the scale of this game object.
-    pub fn set_scale(&self, scale: Vector3) {
-        unsafe { dmGameObject::SetScale1(self.ptr, scale.into()) }
+    pub fn set_scale(&self, scale: impl Into<Vector3>) {
+        unsafe { dmGameObject::SetScale1(self.ptr, scale.into().into()) }
     }
 }
 
@@ -1,4 +1,23 @@
-use std::ffi::CString;
+//! Hashing functions, the same ones the engine uses for IDs, message names and properties.
+//!
+//! # Examples
+//! ```
+//! use dmsdk::*;
+//!
+//! const JUMP: Hash = hash!("jump");
+//!
+//! fn on_input(action_id: Hash) {
+//!     if action_id == JUMP {
+//!         println!("{action_id} pressed");
+//!     }
+//! }
+//! ```
+
+use dmsdk_ffi::HashState64;
+use std::{
+    ffi::{c_void, CStr, CString},
+    fmt, io,
+};
 
 /// Returns a 32-bit hash of the given string slice.
 pub fn hash32(s: &str) -> u32 {
@@ -11,3 +30,339 @@ pub fn hash64(s: &str) -> u64 {
     let s = CString::new(s).unwrap();
     unsafe { dmsdk_ffi::dmHashString64(s.as_ptr()) }
 }
+
+const M: u64 = 0xc6a4a7935bd1e995;
+const R: u32 = 47;
+
+const fn mix(h: u64, k: u64) -> u64 {
+    let mut k = k.wrapping_mul(M);
+    k ^= k >> R;
+    k = k.wrapping_mul(M);
+    h.wrapping_mul(M) ^ k
+}
+
+// Same algorithm as `dmHashBuffer64()`, a 64-bit variant of MurmurHash2A with a seed of 0
+const fn hash_bytes64(bytes: &[u8]) -> u64 {
+    let mut h = 0;
+    let mut i = 0;
+    while i + 8 <= bytes.len() {
+        let k = u64::from_le_bytes([
+            bytes[i],
+            bytes[i + 1],
+            bytes[i + 2],
+            bytes[i + 3],
+            bytes[i + 4],
+            bytes[i + 5],
+            bytes[i + 6],
+            bytes[i + 7],
+        ]);
+        h = mix(h, k);
+        i += 8;
+    }
+
+    let mut tail = 0;
+    let mut shift = 0;
+    while i < bytes.len() {
+        tail |= (bytes[i] as u64) << shift;
+        shift += 8;
+        i += 1;
+    }
+    h = mix(h, tail);
+    h = mix(h, bytes.len() as u64);
+
+    h ^= h >> R;
+    h = h.wrapping_mul(M);
+    h ^ (h >> R)
+}
+
+/// 64-bit hash, as used by the engine for IDs, message names and properties.
+///
+/// Formatting a hash shows the string it was made from if the engine knows it,
+/// which is only the case in debug builds. Otherwise it shows the hash in hexadecimal.
+///
+/// With the `serde` feature, hashes are serialized as integers, and can be deserialized from either integers
+/// or the strings they were made from.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct Hash(u64);
+
+impl Hash {
+    /// Wraps a hash computed elsewhere, e.g. one returned by the engine.
+    pub const fn from_raw(raw: u64) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the hash as a plain integer.
+    pub const fn raw(self) -> u64 {
+        self.0
+    }
+
+    /// Hashes `bytes` without going through the engine, which also works in `const` contexts.
+    ///
+    /// The result is the same as the engine's, but isn't registered for reverse lookups.
+    /// [`hash!`] is a shorthand for this with a string literal.
+    pub const fn from_bytes(bytes: &[u8]) -> Self {
+        Self(hash_bytes64(bytes))
+    }
+
+    /// Returns the data this hash was made from, if the engine knows it.
+    ///
+    /// Reverse lookups only work in debug builds of the engine, and only for data hashed with reverse lookups enabled,
+    /// such as by [`hash64()`] or [`Hasher64::new_reversible()`]. The data is cut off at its first nul byte.
+    ///
+    /// This can be called from any thread.
+    pub fn reverse(self) -> Option<Vec<u8>> {
+        // Everything the engine allocates for the result, freed once it's copied
+        let mut allocations: Vec<*mut c_void> = Vec::new();
+        let mut allocator = dmsdk_ffi::dmAllocator {
+            m_Alloc: Some(reverse_alloc),
+            m_Free: Some(reverse_free),
+            m_UserData: (&mut allocations as *mut Vec<*mut c_void>).cast(),
+        };
+
+        let reversed = unsafe {
+            let ptr = dmsdk_ffi::dmHashReverseSafe64Alloc(&mut allocator, self.0);
+            let bytes = CStr::from_ptr(ptr).to_bytes().to_vec();
+            for allocation in allocations {
+                libc::free(allocation);
+            }
+            bytes
+        };
+
+        // Unknown hashes come back as "<unknown:hash>", or "<unknown>" if allocating failed
+        if reversed == b"<unknown>" || reversed == format!("<unknown:{}>", self.0).as_bytes() {
+            None
+        } else {
+            Some(reversed)
+        }
+    }
+}
+
+unsafe extern "C" fn reverse_alloc(size: usize, user_data: *mut c_void) -> *mut c_void {
+    let ptr = libc::malloc(size);
+    if !ptr.is_null() {
+        (*user_data.cast::<Vec<*mut c_void>>()).push(ptr);
+    }
+    ptr
+}
+
+unsafe extern "C" fn reverse_free(ptr: *mut c_void, user_data: *mut c_void) {
+    let allocations = &mut *user_data.cast::<Vec<*mut c_void>>();
+    if let Some(index) = allocations.iter().position(|&allocation| allocation == ptr) {
+        allocations.swap_remove(index);
+        libc::free(ptr);
+    }
+}
+
+impl From<u64> for Hash {
+    fn from(raw: u64) -> Self {
+        Self(raw)
+    }
+}
+
+impl From<Hash> for u64 {
+    fn from(hash: Hash) -> Self {
+        hash.0
+    }
+}
+
+/// Hashes the string with the engine, registering it for reverse lookups.
+impl From<&str> for Hash {
+    fn from(s: &str) -> Self {
+        Self(hash64(s))
+    }
+}
+
+impl fmt::Display for Hash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.reverse() {
+            Some(bytes) => f.write_str(&String::from_utf8_lossy(&bytes)),
+            None => write!(f, "{:#018x}", self.0),
+        }
+    }
+}
+
+impl fmt::Debug for Hash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.reverse() {
+            Some(bytes) => f
+                .debug_tuple("Hash")
+                .field(&String::from_utf8_lossy(&bytes))
+                .finish(),
+            None => write!(f, "Hash({:#018x})", self.0),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Hash {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Hash {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = Hash;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a hash or a string")
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Hash, E> {
+                Ok(Hash(v))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Hash, E> {
+                Ok(Hash::from_bytes(v.as_bytes()))
+            }
+        }
+
+        // Strings are only accepted where they can be told apart from numbers
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(Visitor)
+        } else {
+            deserializer.deserialize_u64(Visitor)
+        }
+    }
+}
+
+/// Hashes a string at compile time, giving the same [`Hash`] as the engine.
+///
+/// The argument can be any `&str` constant, not only a literal.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// const CONTACT_POINT_RESPONSE: Hash = hash!("contact_point_response");
+///
+/// // The ID the engine gives to game objects without one
+/// assert_eq!(hash!("__unnamed__").raw(), 12415623704795185700);
+/// assert_eq!(hash!("").raw(), 0);
+/// ```
+#[macro_export]
+macro_rules! __internal_hash {
+    ($s:expr) => {{
+        const HASH: dmsdk::dmhash::Hash = dmsdk::dmhash::Hash::from_bytes(str::as_bytes($s));
+        HASH
+    }};
+}
+
+#[doc(inline)]
+pub use crate::__internal_hash as hash;
+
+/// Incrementally computes a 64-bit hash, giving the same result as hashing all the data at once.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// fn hash_path(parts: &[&str]) -> Hash {
+///     let mut hasher = Hasher64::new();
+///     for part in parts {
+///         hasher.update(b"/");
+///         hasher.update(part.as_bytes());
+///     }
+///     hasher.finish()
+/// }
+/// ```
+pub struct Hasher64 {
+    state: HashState64,
+    reversible: bool,
+}
+
+impl Hasher64 {
+    // Filled in by `dmHashInit64()` or `dmHashClone64()`
+    const UNINIT_STATE: HashState64 = HashState64 {
+        m_Hash: 0,
+        m_Tail: 0,
+        m_Count: 0,
+        m_Size: 0,
+        m_ReverseHashEntryIndex: 0,
+    };
+
+    fn with_reverse(reversible: bool) -> Self {
+        let mut state = Self::UNINIT_STATE;
+        unsafe { dmsdk_ffi::dmHashInit64(&mut state, reversible) };
+        Self { state, reversible }
+    }
+
+    /// Creates a hasher.
+    pub fn new() -> Self {
+        Self::with_reverse(false)
+    }
+
+    /// Creates a hasher that registers the hashed data, so [`Hash::reverse()`] can find it in debug builds.
+    pub fn new_reversible() -> Self {
+        Self::with_reverse(true)
+    }
+
+    /// Adds `data` to the hash.
+    pub fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(u32::MAX as usize) {
+            unsafe {
+                dmsdk_ffi::dmHashUpdateBuffer64(
+                    &mut self.state,
+                    chunk.as_ptr().cast::<c_void>(),
+                    chunk.len() as u32,
+                )
+            }
+        }
+    }
+
+    /// Returns the hash of all the data added so far.
+    pub fn finish(mut self) -> Hash {
+        let hash = unsafe { dmsdk_ffi::dmHashFinal64(&mut self.state) };
+        // Finalizing already releases the state
+        std::mem::forget(self);
+        Hash(hash)
+    }
+}
+
+impl Default for Hasher64 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for Hasher64 {
+    fn clone(&self) -> Self {
+        let mut state = Self::UNINIT_STATE;
+        unsafe { dmsdk_ffi::dmHashClone64(&mut state, &self.state, self.reversible) };
+        Self {
+            state,
+            reversible: self.reversible,
+        }
+    }
+}
+
+impl Drop for Hasher64 {
+    fn drop(&mut self) {
+        unsafe { dmsdk_ffi::dmHashRelease64(&mut self.state) }
+    }
+}
+
+impl io::Write for Hasher64 {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Hasher64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hasher64")
+            .field("size", &self.state.m_Size)
+            .field("reversible", &self.reversible)
+            .finish()
+    }
+}
@@ -0,0 +1,560 @@
+//! Hash tables that share their memory layout with the engine's `dmHashTable`.
+//!
+//! [`HashTable`] is both an owned table that can be handed over to C++ code, and a view of tables owned by the
+//! engine, such as the ones inside component or resource structs.
+//!
+//! Like `dmHashTable`, it never runs destructors for its values, so only [`Copy`] values can be inserted.
+//! Keys are unsigned integers, usually hashes for [`HashTable64`].
+//!
+//! # Examples
+//! ```
+//! use dmsdk::{dmhashtable::HashTable64, *};
+//!
+//! let mut scores = HashTable64::new();
+//! scores.insert(hash!("player").raw(), 10);
+//! scores.insert(hash!("enemy").raw(), 3);
+//!
+//! if let Some(score) = scores.get_mut(hash!("player").raw()) {
+//!     *score += 5;
+//! }
+//! assert_eq!(scores.get(hash!("player").raw()), Some(&15));
+//! assert_eq!(scores.values().sum::<i32>(), 18);
+//! ```
+
+use dmsdk_ffi::{__BindgenBitfieldUnit, dmHashTable, dmHashTable_Entry};
+use std::{alloc, fmt, iter::FusedIterator, marker::PhantomData, mem, ptr};
+
+/// The engine's `dmHashTable<K, V>`, as found in the structs it shares with extensions.
+pub type RawHashTable<K, V> = dmHashTable<K, V>;
+
+type Entry<K, V> = dmHashTable_Entry<K, V>;
+
+// Marks the end of a bucket's list of entries, and of the free list
+const INVALID_INDEX: u32 = 0xffffffff;
+
+// `dmHashTable::STATE_USER_ALLOCATED`, which the bindings get wrong
+const STATE_USER_ALLOCATED: u16 = 0x1;
+
+/// Allocates `count` items with `malloc()`, like `dmHashTable` does.
+fn malloc_array<T>(count: usize) -> *mut T {
+    let layout = alloc::Layout::array::<T>(count).expect("hash table capacity overflow");
+    let ptr = unsafe { libc::malloc(layout.size()) }.cast::<T>();
+    if ptr.is_null() && layout.size() != 0 {
+        alloc::handle_alloc_error(layout);
+    }
+    ptr
+}
+
+/// Hash table with the same layout as the engine's `dmHashTable<K, V>`.
+///
+/// The table has a fixed number of buckets and room for a fixed number of entries, which [`HashTable::insert()`]
+/// grows as needed. Use [`HashTable::set_capacity()`] to choose them up front, like in C++.
+///
+/// An owned `HashTable` frees its memory when dropped, unless it was given away with [`HashTable::into_raw()`].
+/// References to tables owned by the engine can be made with [`HashTable::from_raw()`] and
+/// [`HashTable::from_raw_mut()`].
+///
+/// Tables made by C++ code over memory they don't own (*user-allocated* tables) can't grow,
+/// and inserting past their capacity panics.
+#[repr(transparent)]
+pub struct HashTable<K, V>(dmHashTable<K, V>);
+
+/// Table with 16-bit keys, like `dmHashTable16`.
+pub type HashTable16<V> = HashTable<u16, V>;
+/// Table with 32-bit keys, like `dmHashTable32`.
+pub type HashTable32<V> = HashTable<u32, V>;
+/// Table with 64-bit keys, like `dmHashTable64`.
+pub type HashTable64<V> = HashTable<u64, V>;
+
+unsafe impl<K: Send, V: Send> Send for HashTable<K, V> {}
+unsafe impl<K: Sync, V: Sync> Sync for HashTable<K, V> {}
+
+impl<K, V> HashTable<K, V> {
+    /// Creates an empty table, without allocating.
+    pub const fn new() -> Self {
+        Self(dmHashTable {
+            m_HashTable: ptr::null_mut(),
+            m_HashTableSize: 0,
+            m_InitialEntries: ptr::null_mut(),
+            m_InitialEntriesNextFree: ptr::null_mut(),
+            m_InitialEntriesEnd: ptr::null_mut(),
+            m_FreeEntries: INVALID_INDEX,
+            m_Count: 0,
+            _bitfield_align_1: [],
+            _bitfield_1: __BindgenBitfieldUnit::new([0]),
+            _phantom_0: PhantomData,
+            _phantom_1: PhantomData,
+        })
+    }
+
+    /// Returns a reference to an existing `dmHashTable`.
+    ///
+    /// For `dmHashTable16`, `dmHashTable32` and `dmHashTable64`, pass their `_base` field.
+    ///
+    /// # Safety
+    /// `raw` must be a valid table, e.g. one constructed by C++ code.
+    pub unsafe fn from_raw(raw: &RawHashTable<K, V>) -> &Self {
+        &*(raw as *const RawHashTable<K, V>).cast::<Self>()
+    }
+
+    /// Returns a mutable reference to an existing `dmHashTable`.
+    ///
+    /// Growing the table reallocates its memory the same way `dmHashTable::SetCapacity()` does,
+    /// so the engine can keep using it afterwards.
+    ///
+    /// # Safety
+    /// `raw` must be a valid table, e.g. one constructed by C++ code.
+    pub unsafe fn from_raw_mut(raw: &mut RawHashTable<K, V>) -> &mut Self {
+        &mut *(raw as *mut RawHashTable<K, V>).cast::<Self>()
+    }
+
+    /// Gives the table away, e.g. to store it in a struct that C++ code will destroy.
+    pub fn into_raw(self) -> RawHashTable<K, V> {
+        let raw = unsafe { ptr::read(&self.0) };
+        mem::forget(self);
+        raw
+    }
+
+    /// Returns a reference to the underlying `dmHashTable`, e.g. to pass it to an engine function.
+    pub fn as_raw(&self) -> &RawHashTable<K, V> {
+        &self.0
+    }
+
+    /// Returns a mutable reference to the underlying `dmHashTable`, e.g. to pass it to an engine function.
+    pub fn as_raw_mut(&mut self) -> &mut RawHashTable<K, V> {
+        &mut self.0
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.0.m_Count as usize
+    }
+
+    /// Returns `true` if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.0.m_Count == 0
+    }
+
+    /// Returns the number of entries the table can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        if self.0.m_InitialEntries.is_null() {
+            0
+        } else {
+            unsafe {
+                self.0
+                    .m_InitialEntriesEnd
+                    .offset_from(self.0.m_InitialEntries) as usize
+            }
+        }
+    }
+
+    /// Returns the number of buckets.
+    pub fn table_size(&self) -> usize {
+        self.0.m_HashTableSize as usize
+    }
+
+    /// Returns `true` if the table's memory is owned by someone else, which prevents it from growing.
+    pub fn is_user_allocated(&self) -> bool {
+        self.0.m_State() & STATE_USER_ALLOCATED != 0
+    }
+
+    /// Returns an iterator over the entries, in the same order as `dmHashTable::Iterate()`.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            raw: RawIter::new(&self.0),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the entries, with mutable references to the values.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            raw: RawIter::new(&self.0),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the keys.
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the values.
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Returns an iterator over mutable references to the values.
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> + '_ {
+        self.iter_mut().map(|(_, value)| value)
+    }
+
+    /// Removes every entry, keeping the capacity.
+    pub fn clear(&mut self) {
+        if !self.0.m_HashTable.is_null() {
+            unsafe { ptr::write_bytes(self.0.m_HashTable, 0xff, self.table_size()) };
+        }
+        self.0.m_InitialEntriesNextFree = self.0.m_InitialEntries;
+        self.0.m_FreeEntries = INVALID_INDEX;
+        self.0.m_Count = 0;
+    }
+
+    fn entry(&self, index: u32) -> *mut Entry<K, V> {
+        unsafe { self.0.m_InitialEntries.add(index as usize) }
+    }
+
+    fn bucket(&self, index: usize) -> *mut u32 {
+        unsafe { self.0.m_HashTable.add(index) }
+    }
+
+    fn allocate_entry(&mut self) -> *mut Entry<K, V> {
+        if self.0.m_InitialEntriesNextFree != self.0.m_InitialEntriesEnd {
+            let entry = self.0.m_InitialEntriesNextFree;
+            self.0.m_InitialEntriesNextFree = unsafe { entry.add(1) };
+            entry
+        } else {
+            assert_ne!(self.0.m_FreeEntries, INVALID_INDEX, "hash table is full");
+            let entry = self.entry(self.0.m_FreeEntries);
+            self.0.m_FreeEntries = unsafe { (*entry).m_Next };
+            entry
+        }
+    }
+}
+
+impl<K: Copy + Eq + Into<u64>, V> HashTable<K, V> {
+    fn bucket_index(&self, key: K) -> usize {
+        (key.into() % self.0.m_HashTableSize as u64) as usize
+    }
+
+    fn find(&self, key: K) -> Option<*mut Entry<K, V>> {
+        if self.0.m_HashTableSize == 0 {
+            return None;
+        }
+        let mut index = unsafe { *self.bucket(self.bucket_index(key)) };
+        while index != INVALID_INDEX {
+            let entry = self.entry(index);
+            unsafe {
+                if (*entry).m_Key == key {
+                    return Some(entry);
+                }
+                index = (*entry).m_Next;
+            }
+        }
+        None
+    }
+
+    /// Returns a reference to the value of `key`.
+    pub fn get(&self, key: K) -> Option<&V> {
+        self.find(key).map(|entry| unsafe { &(*entry).m_Value })
+    }
+
+    /// Returns a mutable reference to the value of `key`.
+    pub fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        self.find(key).map(|entry| unsafe { &mut (*entry).m_Value })
+    }
+
+    /// Returns `true` if the table has an entry for `key`.
+    pub fn contains_key(&self, key: K) -> bool {
+        self.find(key).is_some()
+    }
+
+    /// Adds an entry for `key`, which must not be in the table yet. Same as `dmHashTable::Put()` for new keys.
+    fn push_entry(&mut self, key: K, value: V) {
+        let entry = self.allocate_entry();
+        unsafe {
+            ptr::write(
+                entry,
+                Entry {
+                    m_Key: key,
+                    m_Value: value,
+                    m_Next: INVALID_INDEX,
+                    _phantom_0: PhantomData,
+                    _phantom_1: PhantomData,
+                },
+            )
+        };
+        let index = unsafe { entry.offset_from(self.0.m_InitialEntries) } as u32;
+
+        // New entries go at the end of their bucket's list
+        let mut next = self.bucket(self.bucket_index(key));
+        unsafe {
+            while *next != INVALID_INDEX {
+                next = ptr::addr_of_mut!((*self.entry(*next)).m_Next);
+            }
+            *next = index;
+        }
+        self.0.m_Count += 1;
+    }
+
+    /// Removes the entry for `key` and returns its value.
+    pub fn remove(&mut self, key: K) -> Option<V> {
+        if self.0.m_HashTableSize == 0 {
+            return None;
+        }
+        let mut next = self.bucket(self.bucket_index(key));
+        unsafe {
+            while *next != INVALID_INDEX {
+                let index = *next;
+                let entry = self.entry(index);
+                if (*entry).m_Key == key {
+                    // Unlink it from its bucket and put it on the free list
+                    *next = (*entry).m_Next;
+                    (*entry).m_Next = self.0.m_FreeEntries;
+                    self.0.m_FreeEntries = index;
+                    self.0.m_Count -= 1;
+                    return Some(ptr::read(&(*entry).m_Value));
+                }
+                next = ptr::addr_of_mut!((*entry).m_Next);
+            }
+        }
+        None
+    }
+
+    /// Reallocates the table with `table_size` buckets and room for `capacity` entries, keeping its entries.
+    ///
+    /// # Panics
+    /// Panics if the table is user-allocated, if `table_size` is 0, if `capacity` is less than [`HashTable::len()`],
+    /// or if either doesn't fit in a `u32`.
+    pub fn set_capacity(&mut self, table_size: usize, capacity: usize) {
+        assert!(
+            !self.is_user_allocated(),
+            "can't change the capacity of a user-allocated hash table"
+        );
+        assert!(table_size > 0, "hash table must have at least one bucket");
+        assert!(
+            capacity >= self.len(),
+            "hash table capacity {capacity} is less than its length {}",
+            self.len()
+        );
+        let table_size = u32::try_from(table_size)
+            .ok()
+            .filter(|&size| size != INVALID_INDEX)
+            .expect("hash table size overflow");
+        assert!(
+            capacity < INVALID_INDEX as usize,
+            "hash table capacity overflow"
+        );
+
+        let mut table = Self::new();
+        table.0.m_HashTableSize = table_size;
+        table.0.m_HashTable = malloc_array(table_size as usize);
+        unsafe { ptr::write_bytes(table.0.m_HashTable, 0xff, table_size as usize) };
+        table.0.m_InitialEntries = malloc_array(capacity);
+        table.0.m_InitialEntriesNextFree = table.0.m_InitialEntries;
+        table.0.m_InitialEntriesEnd = unsafe { table.0.m_InitialEntries.add(capacity) };
+
+        // Moves the entries over, leaving the old ones to be freed without dropping anything
+        for entry in RawIter::new(&self.0) {
+            unsafe { table.push_entry((*entry).m_Key, ptr::read(&(*entry).m_Value)) };
+        }
+        mem::swap(self, &mut table);
+    }
+
+    /// Makes sure there's room for at least `additional` more entries, growing the capacity geometrically.
+    ///
+    /// Growing keeps about two thirds as many buckets as entries.
+    ///
+    /// # Panics
+    /// Panics if the table needs to grow but is user-allocated.
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self.len() + additional;
+        if required > self.capacity() {
+            let capacity = required.max(self.capacity() * 2).max(8);
+            self.set_capacity(capacity * 2 / 3, capacity);
+        }
+    }
+}
+
+impl<K: Copy + Eq + Into<u64>, V: Copy> HashTable<K, V> {
+    /// Creates an empty table with room for `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut table = Self::new();
+        table.reserve(capacity);
+        table
+    }
+
+    /// Sets the value of `key`, returning the previous one if there was one.
+    ///
+    /// The table grows if it's full.
+    ///
+    /// # Panics
+    /// Panics if the table is full and user-allocated.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(entry) = self.find(key) {
+            return Some(mem::replace(unsafe { &mut (*entry).m_Value }, value));
+        }
+        self.reserve(1);
+        self.push_entry(key, value);
+        None
+    }
+}
+
+impl<K, V> Default for HashTable<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> Drop for HashTable<K, V> {
+    fn drop(&mut self) {
+        if !self.is_user_allocated() {
+            unsafe {
+                libc::free(self.0.m_InitialEntries.cast());
+                libc::free(self.0.m_HashTable.cast());
+            }
+        }
+    }
+}
+
+impl<K: Copy + Eq + Into<u64>, V: Copy> Clone for HashTable<K, V> {
+    fn clone(&self) -> Self {
+        let mut table = Self::new();
+        if self.table_size() > 0 {
+            table.set_capacity(self.table_size(), self.capacity());
+        }
+        table.extend(self.iter().map(|(&key, &value)| (key, value)));
+        table
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for HashTable<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Copy + Eq + Into<u64>, V: Copy> Extend<(K, V)> for HashTable<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Copy + Eq + Into<u64>, V: Copy> FromIterator<(K, V)> for HashTable<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut table = Self::new();
+        table.extend(iter);
+        table
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a HashTable<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut HashTable<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Walks the buckets and their lists of entries.
+struct RawIter<K, V> {
+    buckets: *const u32,
+    table_size: u32,
+    entries: *mut Entry<K, V>,
+    bucket: u32,
+    next: u32,
+    remaining: usize,
+}
+
+impl<K, V> RawIter<K, V> {
+    fn new(table: &RawHashTable<K, V>) -> Self {
+        Self {
+            buckets: table.m_HashTable,
+            table_size: table.m_HashTableSize,
+            entries: table.m_InitialEntries,
+            bucket: 0,
+            next: INVALID_INDEX,
+            remaining: table.m_Count as usize,
+        }
+    }
+}
+
+impl<K, V> Iterator for RawIter<K, V> {
+    type Item = *mut Entry<K, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next == INVALID_INDEX {
+            if self.bucket >= self.table_size {
+                return None;
+            }
+            self.next = unsafe { *self.buckets.add(self.bucket as usize) };
+            self.bucket += 1;
+        }
+        let entry = unsafe { self.entries.add(self.next as usize) };
+        self.next = unsafe { (*entry).m_Next };
+        self.remaining -= 1;
+        Some(entry)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// Iterator over the entries of a [`HashTable`], returned by [`HashTable::iter()`].
+pub struct Iter<'a, K, V> {
+    raw: RawIter<K, V>,
+    marker: PhantomData<&'a HashTable<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.raw
+            .next()
+            .map(|entry| unsafe { (&(*entry).m_Key, &(*entry).m_Value) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            raw: RawIter { ..self.raw },
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Iterator over the entries of a [`HashTable`] with mutable values, returned by [`HashTable::iter_mut()`].
+pub struct IterMut<'a, K, V> {
+    raw: RawIter<K, V>,
+    marker: PhantomData<&'a mut HashTable<K, V>>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.raw
+            .next()
+            .map(|entry| unsafe { (&(*entry).m_Key, &mut (*entry).m_Value) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
@@ -14,7 +14,7 @@ unsafe impl Sync for Context {}
 impl Context {
     /// Creates a new [`Context`] from the given pointer.
     ///
-    /// You probably want [`dmengine::get_hid_context()`](crate::dmengine::get_hid_context()) instead.
+    /// You probably want [`AppParams::hid_context()`](crate::dmextension::AppParams::hid_context()) instead.
     pub fn new(ptr: *mut dmHID::Context) -> Self {
         Self { ptr }
     }
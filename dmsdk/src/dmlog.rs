@@ -1,4 +1,11 @@
 //! Logging macros.
+//!
+//! Messages are logged under the domain given by a `LOG_DOMAIN` constant at the root of your crate:
+//!
+//! ```
+//! const LOG_DOMAIN: &str = "MY_EXTENSION";
+//! # fn main() {}
+//! ```
 
 #![allow(clippy::crate_in_macro_def)]
 
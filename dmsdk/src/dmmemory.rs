@@ -0,0 +1,162 @@
+//! Engine memory allocation and allocation statistics.
+//!
+//! [`EngineAllocator`] is a global allocator that gets its memory from `dmMemory::AlignedMalloc()`,
+//! the same way the engine allocates aligned memory, so Rust allocations are made and tracked like the engine's own.
+//! It isn't used unless you make it the global allocator of your extension:
+//!
+//! ```ignore
+//! use dmsdk::*;
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: dmmemory::EngineAllocator = dmmemory::EngineAllocator;
+//! ```
+//!
+//! Every allocation it makes is counted in [`stats()`]. Each extension crate has its own global allocator,
+//! so the statistics only cover the crate they're queried from.
+//!
+//! # Examples
+//! ```
+//! use dmsdk::*;
+//!
+//! fn on_level_loaded() {
+//!     let stats = dmmemory::stats();
+//!     println!(
+//!         "{} bytes in {} allocations, {} bytes at most",
+//!         stats.bytes,
+//!         stats.live_allocations(),
+//!         stats.peak_bytes,
+//!     );
+//!     dmmemory::reset_peak();
+//! }
+//! ```
+
+use crate::lua;
+use dmsdk_ffi::dmMemory;
+use std::{
+    alloc::{GlobalAlloc, Layout},
+    ptr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static DEALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Global allocator that allocates through the engine and keeps [`stats()`] up to date.
+///
+/// Allocations are limited to 4 GiB each, the most `dmMemory::AlignedMalloc()` can allocate at once.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EngineAllocator;
+
+unsafe impl GlobalAlloc for EngineAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let Ok(size) = u32::try_from(layout.size()) else {
+            return ptr::null_mut();
+        };
+        // `posix_memalign()` needs at least the alignment of a pointer
+        let alignment = layout.align().max(std::mem::size_of::<usize>()) as u32;
+
+        let mut memory = ptr::null_mut();
+        if dmMemory::AlignedMalloc(&mut memory, alignment, size) != dmMemory::Result_RESULT_OK {
+            return ptr::null_mut();
+        }
+
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        let bytes = BYTES.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK_BYTES.fetch_max(bytes, Ordering::Relaxed);
+        memory.cast()
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        dmMemory::AlignedFree(ptr.cast());
+        DEALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+/// Snapshot of what [`EngineAllocator`] has allocated, returned by [`stats()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Number of bytes currently allocated.
+    pub bytes: usize,
+    /// Highest value [`Stats::bytes`] has reached, since the start or the last [`reset_peak()`].
+    pub peak_bytes: usize,
+    /// Number of allocations made since the start, including the ones that have been freed.
+    pub allocations: usize,
+    /// Number of allocations freed since the start.
+    pub deallocations: usize,
+}
+
+impl Stats {
+    /// Returns the number of allocations that haven't been freed yet.
+    pub fn live_allocations(&self) -> usize {
+        self.allocations.saturating_sub(self.deallocations)
+    }
+}
+
+/// Returns the allocation statistics of this crate.
+///
+/// Everything is zero if [`EngineAllocator`] isn't the global allocator.
+/// Reallocations count as an allocation and a deallocation.
+pub fn stats() -> Stats {
+    Stats {
+        bytes: BYTES.load(Ordering::Relaxed),
+        peak_bytes: PEAK_BYTES.load(Ordering::Relaxed),
+        allocations: ALLOCATIONS.load(Ordering::Relaxed),
+        deallocations: DEALLOCATIONS.load(Ordering::Relaxed),
+    }
+}
+
+/// Lowers [`Stats::peak_bytes`] to the number of bytes currently allocated, to measure the peak of a new period.
+pub fn reset_peak() {
+    PEAK_BYTES.store(BYTES.load(Ordering::Relaxed), Ordering::Relaxed);
+}
+
+/// Pushes `stats` onto the stack as a table with the fields of [`Stats`], and `live_allocations`.
+pub fn push_stats(l: lua::State, stats: &Stats) {
+    lua::new_table(l);
+    for (name, value) in [
+        ("bytes", stats.bytes),
+        ("peak_bytes", stats.peak_bytes),
+        ("allocations", stats.allocations),
+        ("deallocations", stats.deallocations),
+        ("live_allocations", stats.live_allocations()),
+    ] {
+        lua::push_integer(l, value as isize);
+        lua::set_field(l, -2, name);
+    }
+}
+
+mod lua_functions {
+    use crate::lua;
+
+    pub(super) extern "C" fn memory_stats(l: lua::StatePtr) -> i32 {
+        super::push_stats(unsafe { lua::State::new(l) }, &super::stats());
+        1
+    }
+
+    pub(super) extern "C" fn reset_memory_peak(_l: lua::StatePtr) -> i32 {
+        super::reset_peak();
+        0
+    }
+}
+
+/// Lua versions of this module's functions, to be passed to [`lua::register()`].
+///
+/// Registering them into an existing table adds to it, so they can share a module with your own functions.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// fn lua_init(l: lua::State) {
+///     // rust.memory_stats().peak_bytes, rust.reset_memory_peak()
+///     lua::register(l, "rust", dmmemory::LUA_FUNCTIONS);
+///     lua::pop(l, 1);
+/// }
+/// ```
+pub const LUA_FUNCTIONS: lua::Reg = &[
+    ("memory_stats", lua_functions::memory_stats),
+    ("reset_memory_peak", lua_functions::reset_memory_peak),
+];
@@ -0,0 +1,422 @@
+//! Profiler scopes, properties and text annotations.
+//!
+//! Everything here only reaches the profiler with the `profile` feature enabled.
+//! Without it, [`scope!`] and [`text!`] expand to nothing and the rest does nothing.
+//!
+//! With the feature enabled, callbacks of extensions declared with [`declare_extension!`](crate::declare_extension)
+//! and functions registered with [`declare_functions!`](crate::lua::declare_functions) get scopes of their own.
+//!
+//! # Examples
+//! ```
+//! use dmsdk::*;
+//!
+//! static ENEMIES: dmprofile::Property<u32> =
+//!     dmprofile::Property::<u32>::new(c"Enemies", c"Enemies updated this frame", 0).frame_reset();
+//!
+//! fn update_enemies(enemies: &mut [f32]) {
+//!     dmprofile::scope!("update_enemies");
+//!
+//!     for health in enemies.iter_mut() {
+//!         *health += 1.0;
+//!         ENEMIES.add(1);
+//!     }
+//! }
+//! ```
+
+#![allow(clippy::crate_in_macro_def)]
+
+#[cfg(feature = "profile")]
+use dmsdk_ffi::dmProfile;
+use dmsdk_ffi::{rmtProperty, rmtPropertyValue};
+#[cfg(feature = "profile")]
+use std::ffi::CString;
+use std::{
+    cell::UnsafeCell,
+    ffi::CStr,
+    fmt,
+    marker::PhantomData,
+    sync::{atomic::AtomicU64, Mutex, PoisonError},
+};
+
+/// Profiles a whole function, under its own name or the one given.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// #[dmprofile::function]
+/// fn find_path(from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+///     vec![from, to]
+/// }
+///
+/// #[dmprofile::function("Physics step")]
+/// fn step(dt: f32) {
+///     // ...
+/// }
+/// ```
+#[doc(inline)]
+pub use dmsdk_macros::function;
+
+/// Profiler scope that ends when dropped. Usually created with [`scope!`].
+#[must_use = "the scope ends as soon as it's dropped"]
+pub struct Scope {
+    #[cfg(feature = "profile")]
+    raw: dmProfile::ProfileScope,
+    // Scopes have to end on the thread they started on
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Scope {
+    /// Starts a scope with a name that isn't known at compile time.
+    ///
+    /// Prefer [`scope!`] for fixed names, it hashes the name only once.
+    pub fn new(name: &str) -> Self {
+        #[cfg(feature = "profile")]
+        {
+            let name = CString::new(name.replace('\0', "")).unwrap();
+            let mut name_hash = 0;
+            Self::start(name.as_c_str(), &mut name_hash)
+        }
+        #[cfg(not(feature = "profile"))]
+        {
+            let _ = name;
+            Self {
+                _not_send: PhantomData,
+            }
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn __new_static(name: &'static str, name_hash: &'static AtomicU64) -> Self {
+        #[cfg(feature = "profile")]
+        {
+            let name = CStr::from_bytes_with_nul(name.as_bytes()).unwrap();
+            Self::start(name, name_hash.as_ptr())
+        }
+        #[cfg(not(feature = "profile"))]
+        {
+            let _ = (name, name_hash);
+            Self {
+                _not_send: PhantomData,
+            }
+        }
+    }
+
+    #[cfg(feature = "profile")]
+    fn start(name: &CStr, name_hash: *mut u64) -> Self {
+        let mut raw = dmProfile::ProfileScope { valid: 0 };
+        unsafe { raw.StartScope(name.as_ptr(), name_hash) };
+        Self {
+            raw,
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        #[cfg(feature = "profile")]
+        unsafe {
+            self.raw.EndScope();
+        }
+    }
+}
+
+impl fmt::Debug for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scope").finish_non_exhaustive()
+    }
+}
+
+/// Profiles the rest of the enclosing block under the given name.
+///
+/// The name must be a string literal, or a macro like [`concat!`] that expands to one.
+/// Use [`Scope::new()`] for names built at runtime.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// fn rebuild_navmesh() {
+///     dmprofile::scope!("rebuild_navmesh");
+///
+///     for chunk in 0..16 {
+///         dmprofile::scope!("chunk");
+///         // ...
+///     }
+/// }
+/// ```
+#[cfg(feature = "profile")]
+#[macro_export]
+macro_rules! __internal_profile_scope {
+    ($name:expr) => {
+        let _profile_scope = {
+            static NAME_HASH: ::core::sync::atomic::AtomicU64 =
+                ::core::sync::atomic::AtomicU64::new(0);
+            dmsdk::dmprofile::Scope::__new_static(concat!($name, "\0"), &NAME_HASH)
+        };
+    };
+}
+
+/// Profiles the rest of the enclosing block under the given name.
+///
+/// The name must be a string literal, or a macro like [`concat!`] that expands to one.
+/// Use [`Scope::new()`] for names built at runtime.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// fn rebuild_navmesh() {
+///     dmprofile::scope!("rebuild_navmesh");
+///
+///     for chunk in 0..16 {
+///         dmprofile::scope!("chunk");
+///         // ...
+///     }
+/// }
+/// ```
+#[cfg(not(feature = "profile"))]
+#[macro_export]
+macro_rules! __internal_profile_scope {
+    ($name:expr) => {};
+}
+
+/// Sends a formatted message to the profiler.
+///
+/// The arguments are the same as [`format!`].
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// fn load_level(name: &str) {
+///     dmprofile::text!("Loading level {name}");
+/// }
+/// ```
+#[cfg(feature = "profile")]
+#[macro_export]
+macro_rules! __internal_profile_text {
+    ($($arg:tt)*) => {
+        dmsdk::dmprofile::log_text(&format!($($arg)*));
+    };
+}
+
+/// Sends a formatted message to the profiler.
+///
+/// The arguments are the same as [`format!`].
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// fn load_level(name: &str) {
+///     dmprofile::text!("Loading level {name}");
+/// }
+/// ```
+#[cfg(not(feature = "profile"))]
+#[macro_export]
+macro_rules! __internal_profile_text {
+    ($($arg:tt)*) => {
+        // Never runs, but keeps the arguments in use
+        if false {
+            let _ = format!($($arg)*);
+        }
+    };
+}
+
+#[doc(inline)]
+pub use crate::{__internal_profile_scope as scope, __internal_profile_text as text};
+
+/// Sends `text` to the profiler.
+pub fn log_text(text: &str) {
+    #[cfg(feature = "profile")]
+    {
+        let text = CString::new(text.replace('\0', "")).unwrap();
+        unsafe { dmProfile::LogText(c"%s".as_ptr(), text.as_ptr()) };
+    }
+    #[cfg(not(feature = "profile"))]
+    let _ = text;
+}
+
+/// Marks a profiler frame that ends when dropped.
+///
+/// The engine already marks its own frames, this is only useful for code running outside of them,
+/// e.g. a tool driving the engine's libraries itself.
+pub struct Frame {
+    #[cfg(feature = "profile")]
+    raw: dmProfile::HProfile,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Frame {
+    /// Begins a new frame.
+    pub fn begin() -> Self {
+        Self {
+            #[cfg(feature = "profile")]
+            raw: unsafe { dmProfile::BeginFrame() },
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl Drop for Frame {
+    fn drop(&mut self) {
+        #[cfg(feature = "profile")]
+        unsafe {
+            dmProfile::EndFrame(self.raw);
+        }
+    }
+}
+
+impl fmt::Debug for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Frame").finish_non_exhaustive()
+    }
+}
+
+/// Named value shown in the profiler, such as a counter.
+///
+/// Properties have to be `static`, since the profiler keeps track of them once they're first changed.
+/// Numeric properties can be added to with [`Property::add()`], and [`Property::frame_reset()`] makes them
+/// go back to their default value every frame, which suits per-frame counters.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// static CONNECTIONS: dmprofile::Property<i32> =
+///     dmprofile::Property::<i32>::new(c"Connections", c"Open sockets", 0);
+///
+/// fn on_connect() {
+///     CONNECTIONS.add(1);
+/// }
+///
+/// fn on_disconnect() {
+///     CONNECTIONS.add(-1);
+/// }
+/// ```
+pub struct Property<T> {
+    raw: UnsafeCell<rmtProperty>,
+    lock: Mutex<()>,
+    _value: PhantomData<T>,
+}
+
+// The raw property is only touched with the lock held
+unsafe impl<T: Send> Sync for Property<T> {}
+
+impl<T> Property<T> {
+    const fn with_raw(
+        kind: dmsdk_ffi::rmtPropertyType,
+        name: &'static CStr,
+        description: &'static CStr,
+        default: rmtPropertyValue,
+    ) -> Self {
+        Self {
+            raw: UnsafeCell::new(rmtProperty {
+                initialised: 0,
+                type_: kind,
+                flags: dmsdk_ffi::rmtPropertyFlags_RMT_PropertyFlags_NoFlags,
+                value: default,
+                lastFrameValue: default,
+                prevValue: default,
+                prevValueFrame: 0,
+                name: name.as_ptr(),
+                description: description.as_ptr(),
+                defaultValue: default,
+                parent: std::ptr::null_mut(),
+                firstChild: std::ptr::null_mut(),
+                lastChild: std::ptr::null_mut(),
+                nextSibling: std::ptr::null_mut(),
+                nameHash: 0,
+                uniqueID: 0,
+            }),
+            lock: Mutex::new(()),
+            _value: PhantomData,
+        }
+    }
+
+    /// Resets the property to its default value every frame.
+    pub const fn frame_reset(mut self) -> Self {
+        self.raw.get_mut().flags = dmsdk_ffi::rmtPropertyFlags_RMT_PropertyFlags_FrameReset;
+        self
+    }
+
+    fn update(&self, f: impl FnOnce(&mut rmtProperty)) {
+        let _lock = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        f(unsafe { &mut *self.raw.get() });
+    }
+
+    /// Sets the property back to its default value.
+    pub fn reset(&self) {
+        self.update(|raw| {
+            raw.value = raw.defaultValue;
+            #[cfg(feature = "profile")]
+            unsafe {
+                dmsdk_ffi::_rmt_PropertySetValue(raw);
+            }
+        });
+    }
+}
+
+impl<T> fmt::Debug for Property<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Property").finish_non_exhaustive()
+    }
+}
+
+macro_rules! properties {
+    ($($ty:ident => $kind:ident, $field:ident, $add:tt;)*) => {
+        $(
+            impl Property<$ty> {
+                #[doc = concat!("Creates a `", stringify!($ty), "` property.")]
+                pub const fn new(name: &'static CStr, description: &'static CStr, default: $ty) -> Self {
+                    Self::with_raw(
+                        dmsdk_ffi::$kind,
+                        name,
+                        description,
+                        rmtPropertyValue { $field: properties!(@raw $ty, default) },
+                    )
+                }
+
+                /// Sets the property to `value`.
+                pub fn set(&self, value: $ty) {
+                    self.update(|raw| {
+                        raw.value = rmtPropertyValue { $field: properties!(@raw $ty, value) };
+                        #[cfg(feature = "profile")]
+                        unsafe {
+                            dmsdk_ffi::_rmt_PropertySetValue(raw);
+                        }
+                    });
+                }
+
+                properties!(@add $add, $ty, $field);
+            }
+        )*
+    };
+    (@raw bool, $value:expr) => { $value as u32 };
+    (@raw $ty:ty, $value:expr) => { $value };
+    (@add no, $ty:ty, $field:ident) => {};
+    (@add yes, $ty:ty, $field:ident) => {
+        /// Adds `delta` to the property.
+        pub fn add(&self, delta: $ty) {
+            self.update(|raw| {
+                unsafe { raw.value.$field += delta };
+                #[cfg(feature = "profile")]
+                unsafe {
+                    dmsdk_ffi::_rmt_PropertyAddValue(raw, rmtPropertyValue { $field: delta });
+                }
+            });
+        }
+    };
+}
+
+properties! {
+    bool => rmtPropertyType_RMT_PropertyType_rmtBool, Bool, no;
+    i32 => rmtPropertyType_RMT_PropertyType_rmtS32, S32, yes;
+    u32 => rmtPropertyType_RMT_PropertyType_rmtU32, U32, yes;
+    f32 => rmtPropertyType_RMT_PropertyType_rmtF32, F32, yes;
+    i64 => rmtPropertyType_RMT_PropertyType_rmtS64, S64, yes;
+    u64 => rmtPropertyType_RMT_PropertyType_rmtU64, U64, yes;
+    f64 => rmtPropertyType_RMT_PropertyType_rmtF64, F64, yes;
+}
@@ -0,0 +1,167 @@
+//! Pure Rust ciphers for resource encryption.
+//!
+//! Nothing here calls into the engine, so the same code can encrypt archives on the host (see the `encrypt-archive`
+//! tool) and decrypt them at runtime through [`set_cipher()`](super::set_cipher).
+//!
+//! [`Cipher`] uses AES-128 in CBC mode with ciphertext stealing (the CS3 variant of NIST SP 800-38A), which keeps
+//! resources at their original size. The engine's decryption hook only receives the resource data, so there's no
+//! room for a random IV: the same resource always encrypts to the same bytes, and resources starting with the same
+//! 16 byte blocks share those encrypted blocks. Resources shorter than a block can't be chained at all and are
+//! XORed with a block derived from their length. This keeps assets away from casual extraction tools, but should
+//! not be relied on to protect secrets.
+
+use aes::{
+    cipher::{BlockDecrypt, BlockEncrypt, KeyInit},
+    Aes128, Block,
+};
+
+/// Key used by Defold's build tools for the resources they encrypt themselves, such as compiled Lua.
+pub const DEFAULT_XTEA_KEY: [u8; 16] = *b"aQj8CScgNP4VsfXK";
+
+const BLOCK_LEN: usize = 16;
+
+/// AES-128 resource cipher.
+///
+/// # Examples
+/// ```
+/// use dmsdk::dmresource::cipher::Cipher;
+///
+/// let cipher = Cipher::new(b"0123456789abcdef");
+/// let mut data = *b"hello, world";
+///
+/// cipher.encrypt(&mut data);
+/// assert_ne!(&data, b"hello, world");
+///
+/// cipher.decrypt(&mut data);
+/// assert_eq!(&data, b"hello, world");
+/// ```
+#[derive(Clone)]
+pub struct Cipher {
+    aes: Aes128,
+}
+
+impl Cipher {
+    /// Creates a cipher with the given key.
+    pub fn new(key: &[u8; 16]) -> Self {
+        Self {
+            aes: Aes128::new(key.into()),
+        }
+    }
+
+    /// Encrypts a resource in place.
+    pub fn encrypt(&self, data: &mut [u8]) {
+        if data.len() < BLOCK_LEN {
+            self.xor_short(data);
+            return;
+        }
+
+        // Everything before the last block, full or not, is plain CBC
+        let tail = (data.len() - 1) / BLOCK_LEN * BLOCK_LEN;
+        let mut previous = Block::default();
+        for block in data[..tail].chunks_exact_mut(BLOCK_LEN) {
+            let block = Block::from_mut_slice(block);
+            xor(block, &previous);
+            self.aes.encrypt_block(block);
+            previous = *block;
+        }
+
+        let last = &mut data[tail..];
+        let mut padded = Block::default();
+        padded[..last.len()].copy_from_slice(last);
+        xor(&mut padded, &previous);
+        self.aes.encrypt_block(&mut padded);
+        if tail == 0 {
+            last.copy_from_slice(&padded);
+            return;
+        }
+
+        // The last two blocks swap places, and the one that moves to the end loses the bytes it doesn't need
+        last.copy_from_slice(&previous[..last.len()]);
+        data[tail - BLOCK_LEN..tail].copy_from_slice(&padded);
+    }
+
+    /// Decrypts a resource encrypted with [`Cipher::encrypt()`] in place.
+    pub fn decrypt(&self, data: &mut [u8]) {
+        if data.len() < BLOCK_LEN {
+            self.xor_short(data);
+            return;
+        }
+
+        let tail = (data.len() - 1) / BLOCK_LEN * BLOCK_LEN;
+        if tail > 0 {
+            // Decrypting the full block gives the padded last block XORed with the stolen one, whose missing bytes
+            // are the padding
+            let (head, last) = data.split_at_mut(tail);
+            let full = Block::from_mut_slice(&mut head[tail - BLOCK_LEN..]);
+            self.aes.decrypt_block(full);
+            for (i, byte) in last.iter_mut().enumerate() {
+                let stolen = *byte;
+                *byte ^= full[i];
+                full[i] = stolen;
+            }
+        }
+
+        let mut previous = Block::default();
+        for block in data[..tail.max(BLOCK_LEN)].chunks_exact_mut(BLOCK_LEN) {
+            let block = Block::from_mut_slice(block);
+            let encrypted = *block;
+            self.aes.decrypt_block(block);
+            xor(block, &previous);
+            previous = encrypted;
+        }
+    }
+
+    fn xor_short(&self, data: &mut [u8]) {
+        let mut keystream = Block::default();
+        keystream[0] = data.len() as u8;
+        self.aes.encrypt_block(&mut keystream);
+        xor(data, &keystream);
+    }
+}
+
+// Keys stay out of debug output
+impl std::fmt::Debug for Cipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Cipher(..)")
+    }
+}
+
+/// Encrypts or decrypts `data` in place the way the engine does with XTEA, so
+/// `cipher::xtea(&key, data)` matches [`dmcrypt::encrypt(Algorithm::Xtea, data, &key)`](crate::dmcrypt::encrypt).
+///
+/// This is only here to undo the encryption Defold's build tools apply with [`DEFAULT_XTEA_KEY`] on the host,
+/// where the engine isn't available. It runs XTEA in counter mode starting from zero for every resource,
+/// so it offers no protection of its own.
+pub fn xtea(key: &[u8; 16], data: &mut [u8]) {
+    const ROUNDS: u32 = 32;
+    const DELTA: u32 = 0x9e37_79b9;
+
+    let mut words = [0; 4];
+    for (word, bytes) in words.iter_mut().zip(key.chunks_exact(4)) {
+        *word = u32::from_be_bytes(bytes.try_into().unwrap());
+    }
+
+    for (counter, block) in data.chunks_mut(8).enumerate() {
+        let mut v0 = 0u32;
+        let mut v1 = counter as u32;
+        let mut sum = 0u32;
+        for _ in 0..ROUNDS {
+            v0 = v0.wrapping_add(
+                ((v1 << 4) ^ (v1 >> 5)).wrapping_add(v1)
+                    ^ sum.wrapping_add(words[(sum & 3) as usize]),
+            );
+            sum = sum.wrapping_add(DELTA);
+            v1 = v1.wrapping_add(
+                ((v0 << 4) ^ (v0 >> 5)).wrapping_add(v0)
+                    ^ sum.wrapping_add(words[((sum >> 11) & 3) as usize]),
+            );
+        }
+        xor(block, &(((v0 as u64) << 32) | v1 as u64).to_be_bytes());
+    }
+}
+
+fn xor(block: &mut [u8], keystream: &[u8]) {
+    for (byte, key) in block.iter_mut().zip(keystream) {
+        *byte ^= key;
+    }
+}
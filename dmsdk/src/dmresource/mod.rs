@@ -0,0 +1,189 @@
+//! Resource decryption.
+
+use libc::c_void;
+use std::{
+    fmt,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{PoisonError, RwLock},
+};
+
+pub mod cipher;
+//pub mod types; TODO update this
+
+pub use cipher::Cipher;
+
+/// Resource error.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    InvalidData,
+    DdfError,
+    ResourceNotFound,
+    MissingFileExtension,
+    AlreadyRegistered,
+    InvalidArgument,
+    UnknownResourceType,
+    OutOfMemory,
+    IoError,
+    NotLoaded,
+    OutOfResources,
+    StreamBufferTooSmall,
+    FormatError,
+    ConstantError,
+    NotSupported,
+    ResourceLoopError,
+    Pending,
+    InvalidFileExtension,
+    VersionMismatch,
+    SignatureMismatch,
+    Unknown,
+}
+
+macro_rules! resource_results {
+    ($($variant:ident => $result:ident,)*) => {
+        impl From<i32> for Error {
+            fn from(x: i32) -> Self {
+                match x {
+                    $(dmsdk_ffi::$result => Self::$variant,)*
+                    _ => Self::Unknown,
+                }
+            }
+        }
+
+        impl From<Error> for i32 {
+            fn from(err: Error) -> Self {
+                match err {
+                    $(Error::$variant => dmsdk_ffi::$result,)*
+                    Error::Unknown => dmsdk_ffi::ResourceResult_RESOURCE_RESULT_UNKNOWN_ERROR,
+                }
+            }
+        }
+    };
+}
+
+resource_results! {
+    InvalidData => ResourceResult_RESOURCE_RESULT_INVALID_DATA,
+    DdfError => ResourceResult_RESOURCE_RESULT_DDF_ERROR,
+    ResourceNotFound => ResourceResult_RESOURCE_RESULT_RESOURCE_NOT_FOUND,
+    MissingFileExtension => ResourceResult_RESOURCE_RESULT_MISSING_FILE_EXTENSION,
+    AlreadyRegistered => ResourceResult_RESOURCE_RESULT_ALREADY_REGISTERED,
+    InvalidArgument => ResourceResult_RESOURCE_RESULT_INVAL,
+    UnknownResourceType => ResourceResult_RESOURCE_RESULT_UNKNOWN_RESOURCE_TYPE,
+    OutOfMemory => ResourceResult_RESOURCE_RESULT_OUT_OF_MEMORY,
+    IoError => ResourceResult_RESOURCE_RESULT_IO_ERROR,
+    NotLoaded => ResourceResult_RESOURCE_RESULT_NOT_LOADED,
+    OutOfResources => ResourceResult_RESOURCE_RESULT_OUT_OF_RESOURCES,
+    StreamBufferTooSmall => ResourceResult_RESOURCE_RESULT_STREAMBUFFER_TOO_SMALL,
+    FormatError => ResourceResult_RESOURCE_RESULT_FORMAT_ERROR,
+    ConstantError => ResourceResult_RESOURCE_RESULT_CONSTANT_ERROR,
+    NotSupported => ResourceResult_RESOURCE_RESULT_NOT_SUPPORTED,
+    ResourceLoopError => ResourceResult_RESOURCE_RESULT_RESOURCE_LOOP_ERROR,
+    Pending => ResourceResult_RESOURCE_RESULT_PENDING,
+    InvalidFileExtension => ResourceResult_RESOURCE_RESULT_INVALID_FILE_EXTENSION,
+    VersionMismatch => ResourceResult_RESOURCE_RESULT_VERSION_MISMATCH,
+    SignatureMismatch => ResourceResult_RESOURCE_RESULT_SIGNATURE_MISMATCH,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::InvalidData => "invalid resource data",
+            Self::DdfError => "failed to parse resource data",
+            Self::ResourceNotFound => "resource not found",
+            Self::MissingFileExtension => "resource path has no file extension",
+            Self::AlreadyRegistered => "resource type is already registered",
+            Self::InvalidArgument => "invalid argument",
+            Self::UnknownResourceType => "unknown resource type",
+            Self::OutOfMemory => "out of memory",
+            Self::IoError => "I/O error",
+            Self::NotLoaded => "resource is not loaded",
+            Self::OutOfResources => "out of resources",
+            Self::StreamBufferTooSmall => "stream buffer too small",
+            Self::FormatError => "resource format error",
+            Self::ConstantError => "shader constant error",
+            Self::NotSupported => "not supported",
+            Self::ResourceLoopError => "resource loop detected",
+            Self::Pending => "resource is pending",
+            Self::InvalidFileExtension => "invalid file extension",
+            Self::VersionMismatch => "version mismatch",
+            Self::SignatureMismatch => "signature mismatch",
+            Self::Unknown => "unknown resource error",
+        })
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// [`Result`](core::result::Result) alias with an error type of [`Error`].
+pub type Result<T> = core::result::Result<T, Error>;
+
+type Decryption = Box<dyn Fn(&mut [u8]) -> Result<()> + Send + Sync>;
+
+static DECRYPTION: RwLock<Option<Decryption>> = RwLock::new(None);
+
+unsafe extern "C" fn decrypt_resource(buffer: *mut c_void, buffer_len: u32) -> i32 {
+    let decryption = DECRYPTION.read().unwrap_or_else(PoisonError::into_inner);
+    let Some(decrypt) = decryption.as_ref() else {
+        return dmsdk_ffi::ResourceResult_RESOURCE_RESULT_NOT_SUPPORTED;
+    };
+
+    let data = std::slice::from_raw_parts_mut(buffer as *mut u8, buffer_len as usize);
+    // Unwinding into the engine would abort, so a panicking decryption fails the load instead
+    match catch_unwind(AssertUnwindSafe(|| decrypt(data))) {
+        Ok(Ok(())) => dmsdk_ffi::ResourceResult_RESOURCE_RESULT_OK,
+        Ok(Err(err)) => err.into(),
+        Err(_) => Error::Unknown.into(),
+    }
+}
+
+/// Replaces the engine's resource decryption with `decrypt`.
+///
+/// `decrypt` is called with the stored bytes of every archive entry flagged as encrypted, from whichever thread
+/// loads the resource, and must decrypt them in place. A panic fails the load of that resource.
+/// Install it before any resources load, e.g. from a [`ctor`](crate::dmextension::ctor)
+/// or [`Extension::app_init()`](crate::dmextension::Extension::app_init).
+///
+/// This also applies to the resources Defold's build tools encrypt on their own, such as compiled Lua,
+/// so the archive must be encrypted with a scheme covering those too. The `encrypt-archive` tool shipped with this
+/// crate does that for [`Cipher`]s installed with [`set_cipher()`].
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// fn install() {
+///     dmresource::set_decryption(|data| {
+///         for byte in data {
+///             *byte ^= 0x5a;
+///         }
+///         Ok(())
+///     });
+/// }
+/// ```
+pub fn set_decryption(decrypt: impl Fn(&mut [u8]) -> Result<()> + Send + Sync + 'static) {
+    let mut decryption = DECRYPTION.write().unwrap_or_else(PoisonError::into_inner);
+    let first = decryption.is_none();
+    *decryption = Some(Box::new(decrypt));
+
+    if first {
+        unsafe { dmsdk_ffi::ResourceRegisterDecryptionFunction(Some(decrypt_resource)) };
+    }
+}
+
+/// Decrypts resources with `cipher`, matching archives encrypted by the `encrypt-archive` tool with the same key.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+/// use dmresource::Cipher;
+///
+/// fn install_cipher() {
+///     dmresource::set_cipher(Cipher::new(b"0123456789abcdef"));
+/// }
+/// ```
+pub fn set_cipher(cipher: Cipher) {
+    set_decryption(move |data| {
+        cipher.decrypt(data);
+        Ok(())
+    });
+}
@@ -2,14 +2,157 @@
 
 use dmsdk_ffi::dmScript;
 
-use crate::{dmgameobject, dmvmath, lua};
+use crate::{dmgameobject, dmhash::Hash, dmvmath, lua};
 
 /// Returns the game object instance the calling script belongs to.
 pub fn check_go_instance(l: lua::State) -> dmgameobject::Instance {
     unsafe { dmgameobject::Instance::new(dmScript::CheckGOInstance(l.ptr())) }
 }
 
-/// Pushes a [`Vector3`](dmvmath::Vector3) onto the stack.
-pub fn push_vector3(l: lua::State, v: dmvmath::Vector3) {
-    unsafe { dmScript::PushVector3(l.ptr(), &v.into()) }
+/// Pushes a [`Vector3`](dmvmath::Vector3), or anything that converts into one, onto the stack.
+pub fn push_vector3(l: lua::State, v: impl Into<dmvmath::Vector3>) {
+    unsafe { dmScript::PushVector3(l.ptr(), &v.into().into()) }
+}
+
+/// Returns the `vector3` at index `i`, or raises a Lua error if it's something else.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// // Called from Lua as `my_extension.double(vmath.vector3(1, 2, 3))`
+/// fn double(l: lua::State) -> i32 {
+///     let v = dmscript::check_vector3(l, 1);
+///     dmscript::push_vector3(l, v * 2.0);
+///     1
+/// }
+/// ```
+pub fn check_vector3(l: lua::State, i: i32) -> dmvmath::Vector3 {
+    unsafe { (*dmScript::CheckVector3(l.ptr(), i)).into() }
+}
+
+/// Pushes a [`Vector4`](dmvmath::Vector4), or anything that converts into one, onto the stack.
+pub fn push_vector4(l: lua::State, v: impl Into<dmvmath::Vector4>) {
+    unsafe { dmScript::PushVector4(l.ptr(), &v.into().into()) }
+}
+
+/// Returns the `vector4` at index `i`, or raises a Lua error if it's something else.
+pub fn check_vector4(l: lua::State, i: i32) -> dmvmath::Vector4 {
+    unsafe { (*dmScript::CheckVector4(l.ptr(), i)).into() }
+}
+
+/// Pushes a [`Quat`](dmvmath::Quat), or anything that converts into one, onto the stack.
+pub fn push_quat(l: lua::State, q: impl Into<dmvmath::Quat>) {
+    unsafe { dmScript::PushQuat(l.ptr(), &q.into().into()) }
+}
+
+/// Returns the `quat` at index `i`, or raises a Lua error if it's something else.
+pub fn check_quat(l: lua::State, i: i32) -> dmvmath::Quat {
+    unsafe { (*dmScript::CheckQuat(l.ptr(), i)).into() }
+}
+
+/// Pushes a [`Matrix4`](dmvmath::Matrix4), or anything that converts into one, onto the stack.
+pub fn push_matrix4(l: lua::State, m: impl Into<dmvmath::Matrix4>) {
+    unsafe { dmScript::PushMatrix4(l.ptr(), &m.into().into()) }
+}
+
+/// Returns the `matrix4` at index `i`, or raises a Lua error if it's something else.
+pub fn check_matrix4(l: lua::State, i: i32) -> dmvmath::Matrix4 {
+    unsafe { (*dmScript::CheckMatrix4(l.ptr(), i)).into() }
+}
+
+/// Pushes a [`Hash`], or anything that converts into one, onto the stack.
+pub fn push_hash(l: lua::State, hash: impl Into<Hash>) {
+    unsafe { dmScript::PushHash(l.ptr(), hash.into().raw()) }
+}
+
+/// Returns the hash at index `i`, hashing it first if it's a string, or raises a Lua error if it's something else.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// // Called from Lua as `my_extension.team(hash("player"))` or `my_extension.team("player")`
+/// fn team(l: lua::State) -> i32 {
+///     let id = dmscript::check_hash(l, 1);
+///     lua::push_string(l, if id == hash!("player") { "allies" } else { "enemies" });
+///     1
+/// }
+/// ```
+pub fn check_hash(l: lua::State, i: i32) -> Hash {
+    Hash::from_raw(unsafe { dmScript::CheckHashOrString(l.ptr(), i) })
+}
+
+/// Returns `true` if the value at index `i` is a hash.
+pub fn is_hash(l: lua::State, i: i32) -> bool {
+    unsafe { dmScript::IsHash(l.ptr(), i) }
+}
+
+/// Lua function stored together with the script instance that passed it, to be called later.
+///
+/// Must be created, called and dropped on the main thread.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+/// use dmscript::LuaCallback;
+///
+/// // Called from Lua as `my_extension.after_load(function(self, value) ... end)`
+/// fn after_load(l: lua::State) -> i32 {
+///     let callback = LuaCallback::new(l, 1);
+///
+///     // Later, e.g. in `Extension::on_update()`
+///     callback.call(|l| {
+///         lua::push_integer(l, 42);
+///         1
+///     });
+///
+///     0
+/// }
+/// ```
+pub struct LuaCallback {
+    ptr: *mut dmScript::LuaCallbackInfo,
+}
+
+impl LuaCallback {
+    /// Creates a callback from the function at index `i` of the stack.
+    pub fn new(l: lua::State, i: i32) -> Self {
+        Self {
+            ptr: unsafe { dmScript::CreateCallback(l.ptr(), i) },
+        }
+    }
+
+    /// Returns `false` if the script instance the callback belongs to has been deleted.
+    pub fn is_valid(&self) -> bool {
+        unsafe { dmScript::IsCallbackValid(self.ptr) }
+    }
+
+    /// Calls the function with the script instance as first argument, followed by the values `push_args` pushes.
+    ///
+    /// `push_args` returns how many values it pushed. Errors raised by the function are logged by the engine.
+    /// Returns `false` if the callback couldn't be called.
+    pub fn call<F: FnOnce(lua::State) -> i32>(&self, push_args: F) -> bool {
+        if !self.is_valid() {
+            return false;
+        }
+
+        unsafe {
+            let l = lua::State::new(dmScript::GetCallbackLuaContext(self.ptr));
+            if !dmScript::SetupCallback(self.ptr) {
+                return false;
+            }
+
+            let nargs = push_args(l);
+            dmScript::PCall(l.ptr(), 1 + nargs, 0);
+            dmScript::TeardownCallback(self.ptr);
+        }
+
+        true
+    }
+}
+
+impl Drop for LuaCallback {
+    fn drop(&mut self) {
+        unsafe { dmScript::DestroyCallback(self.ptr) }
+    }
 }
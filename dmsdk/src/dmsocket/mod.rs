@@ -0,0 +1,782 @@
+//! Networking through the engine's socket layer.
+//!
+//! These types mirror [`std::net`], but go through `dmSocket` so they behave the same way as the engine's own
+//! networking on every platform, including the ones where [`std::net`] isn't available.
+//!
+//! [`TlsStream`] adds TLS on top of a [`TcpStream`]. To service many sockets from the main thread without blocking
+//! it, register them with a [`Reactor`].
+//!
+//! The SDK headers don't declare the functions for binding and listening, so sockets can only connect out:
+//! there's no `TcpListener`, [`UdpSocket`] only talks to the peer it's connected to, and the reuse address option,
+//! which only matters when binding, isn't wrapped.
+//!
+//! # Examples
+//! ```
+//! use dmsdk::*;
+//! use dmsocket::TcpStream;
+//! use std::io::{Read, Write};
+//!
+//! fn fetch_status(host: &str) -> std::io::Result<String> {
+//!     let mut stream = TcpStream::connect_host(host, 80)?;
+//!     write!(stream, "HEAD / HTTP/1.0\r\nHost: {host}\r\n\r\n")?;
+//!
+//!     let mut response = String::new();
+//!     stream.read_to_string(&mut response)?;
+//!     Ok(response.lines().next().unwrap_or_default().to_owned())
+//! }
+//! ```
+
+use crate::dmarray;
+use dmsdk_ffi::{dmSSLSocket, dmSocket};
+use libc::c_void;
+use std::{
+    ffi::{CStr, CString},
+    fmt,
+    io::{self, Read, Write},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, Shutdown},
+    time::Duration,
+};
+
+mod reactor;
+mod tls;
+
+pub use reactor::*;
+pub use tls::*;
+
+#[doc(hidden)]
+pub type RawSocket = dmSocket::Socket;
+#[doc(hidden)]
+pub type RawAddress = dmSocket::Address;
+
+#[cfg(windows)]
+mod ffi {
+    #[link(name = "ws2_32")]
+    extern "system" {
+        pub fn getsockopt(
+            socket: usize,
+            level: i32,
+            name: i32,
+            value: *mut libc::c_char,
+            len: *mut i32,
+        ) -> i32;
+    }
+}
+
+macro_rules! socket_errors {
+    ($($variant:ident => $result:path,)*) => {
+        /// Socket error.
+        #[allow(missing_docs)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Error {
+            $($variant,)*
+            Unknown,
+        }
+
+        impl From<i32> for Error {
+            fn from(x: i32) -> Self {
+                match x {
+                    $($result => Self::$variant,)*
+                    _ => Self::Unknown,
+                }
+            }
+        }
+
+        impl From<Error> for i32 {
+            fn from(err: Error) -> Self {
+                match err {
+                    $(Error::$variant => $result,)*
+                    Error::Unknown => dmSocket::Result_RESULT_UNKNOWN,
+                }
+            }
+        }
+    };
+}
+
+socket_errors! {
+    PermissionDenied => dmSocket::Result_RESULT_ACCES,
+    AddressFamilyNotSupported => dmSocket::Result_RESULT_AFNOSUPPORT,
+    WouldBlock => dmSocket::Result_RESULT_WOULDBLOCK,
+    BadDescriptor => dmSocket::Result_RESULT_BADF,
+    ConnectionReset => dmSocket::Result_RESULT_CONNRESET,
+    DestinationAddressRequired => dmSocket::Result_RESULT_DESTADDRREQ,
+    Fault => dmSocket::Result_RESULT_FAULT,
+    HostUnreachable => dmSocket::Result_RESULT_HOSTUNREACH,
+    Interrupted => dmSocket::Result_RESULT_INTR,
+    InvalidArgument => dmSocket::Result_RESULT_INVAL,
+    AlreadyConnected => dmSocket::Result_RESULT_ISCONN,
+    TooManyOpenFiles => dmSocket::Result_RESULT_MFILE,
+    MessageTooLong => dmSocket::Result_RESULT_MSGSIZE,
+    NetworkDown => dmSocket::Result_RESULT_NETDOWN,
+    NetworkUnreachable => dmSocket::Result_RESULT_NETUNREACH,
+    NoBufferSpace => dmSocket::Result_RESULT_NOBUFS,
+    NotConnected => dmSocket::Result_RESULT_NOTCONN,
+    NotASocket => dmSocket::Result_RESULT_NOTSOCK,
+    OperationNotSupported => dmSocket::Result_RESULT_OPNOTSUPP,
+    BrokenPipe => dmSocket::Result_RESULT_PIPE,
+    ProtocolNotSupported => dmSocket::Result_RESULT_PROTONOSUPPORT,
+    WrongProtocolType => dmSocket::Result_RESULT_PROTOTYPE,
+    TimedOut => dmSocket::Result_RESULT_TIMEDOUT,
+    AddressNotAvailable => dmSocket::Result_RESULT_ADDRNOTAVAIL,
+    ConnectionRefused => dmSocket::Result_RESULT_CONNREFUSED,
+    AddressInUse => dmSocket::Result_RESULT_ADDRINUSE,
+    ConnectionAborted => dmSocket::Result_RESULT_CONNABORTED,
+    InProgress => dmSocket::Result_RESULT_INPROGRESS,
+    HostNotFound => dmSocket::Result_RESULT_HOST_NOT_FOUND,
+    TryAgain => dmSocket::Result_RESULT_TRY_AGAIN,
+    NoRecovery => dmSocket::Result_RESULT_NO_RECOVERY,
+    NoData => dmSocket::Result_RESULT_NO_DATA,
+    TlsInitFailed => dmSSLSocket::Result_RESULT_SSL_INIT_FAILED,
+    TlsHandshakeFailed => dmSSLSocket::Result_RESULT_HANDSHAKE_FAILED,
+}
+
+impl Error {
+    /// Returns the closest matching [`io::ErrorKind`].
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            Self::PermissionDenied => io::ErrorKind::PermissionDenied,
+            Self::WouldBlock | Self::TryAgain => io::ErrorKind::WouldBlock,
+            Self::ConnectionReset => io::ErrorKind::ConnectionReset,
+            Self::ConnectionRefused => io::ErrorKind::ConnectionRefused,
+            Self::ConnectionAborted => io::ErrorKind::ConnectionAborted,
+            Self::NotConnected => io::ErrorKind::NotConnected,
+            Self::AddressInUse => io::ErrorKind::AddrInUse,
+            Self::AddressNotAvailable => io::ErrorKind::AddrNotAvailable,
+            Self::BrokenPipe => io::ErrorKind::BrokenPipe,
+            Self::TimedOut => io::ErrorKind::TimedOut,
+            Self::Interrupted => io::ErrorKind::Interrupted,
+            Self::InvalidArgument => io::ErrorKind::InvalidInput,
+            Self::HostNotFound | Self::NoData => io::ErrorKind::NotFound,
+            Self::OperationNotSupported | Self::ProtocolNotSupported => io::ErrorKind::Unsupported,
+            _ => io::ErrorKind::Other,
+        }
+    }
+}
+
+impl From<io::ErrorKind> for Error {
+    fn from(kind: io::ErrorKind) -> Self {
+        match kind {
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+            io::ErrorKind::WouldBlock => Self::WouldBlock,
+            io::ErrorKind::ConnectionReset => Self::ConnectionReset,
+            io::ErrorKind::ConnectionRefused => Self::ConnectionRefused,
+            io::ErrorKind::ConnectionAborted => Self::ConnectionAborted,
+            io::ErrorKind::HostUnreachable => Self::HostUnreachable,
+            io::ErrorKind::NetworkUnreachable => Self::NetworkUnreachable,
+            io::ErrorKind::NetworkDown => Self::NetworkDown,
+            io::ErrorKind::NotConnected => Self::NotConnected,
+            io::ErrorKind::AddrInUse => Self::AddressInUse,
+            io::ErrorKind::AddrNotAvailable => Self::AddressNotAvailable,
+            io::ErrorKind::BrokenPipe => Self::BrokenPipe,
+            io::ErrorKind::TimedOut => Self::TimedOut,
+            io::ErrorKind::Interrupted => Self::Interrupted,
+            io::ErrorKind::InvalidInput => Self::InvalidArgument,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TlsInitFailed => return f.write_str("TLS initialization failed"),
+            Self::TlsHandshakeFailed => return f.write_str("TLS handshake failed"),
+            _ => {}
+        }
+
+        let s = unsafe { dmSocket::ResultToString((*self).into()) };
+        if s.is_null() {
+            write!(f, "{self:?}")
+        } else {
+            f.write_str(&unsafe { CStr::from_ptr(s) }.to_string_lossy())
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        io::Error::new(err.kind(), err)
+    }
+}
+
+/// [`Result`](core::result::Result) alias with an error type of [`Error`].
+pub type Result<T> = core::result::Result<T, Error>;
+
+fn check(result: dmSocket::Result) -> Result<()> {
+    if result == dmSocket::Result_RESULT_OK {
+        Ok(())
+    } else {
+        Err(result.into())
+    }
+}
+
+/// IPv4 or IPv6 address in the engine's format.
+///
+/// Converts to and from [`IpAddr`]:
+/// ```
+/// use dmsdk::*;
+/// use dmsocket::Address;
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// let localhost = IpAddr::V4(Ipv4Addr::LOCALHOST);
+/// let address = Address::from(localhost);
+/// assert_eq!(address.ip(), Some(localhost));
+/// assert_eq!(address.to_string(), "127.0.0.1");
+/// ```
+#[derive(Clone, Copy)]
+pub struct Address {
+    raw: RawAddress,
+}
+
+impl Address {
+    /// Returns this address as an [`IpAddr`], or [`None`] if it has no address family.
+    pub fn ip(&self) -> Option<IpAddr> {
+        let words = self.raw.m_address;
+        match self.raw.m_family {
+            dmSocket::Domain_DOMAIN_IPV4 => Some(Ipv4Addr::from(words[3].to_ne_bytes()).into()),
+            dmSocket::Domain_DOMAIN_IPV6 => {
+                let mut octets = [0; 16];
+                for (chunk, word) in octets.chunks_exact_mut(4).zip(words) {
+                    chunk.copy_from_slice(&word.to_ne_bytes());
+                }
+                Some(Ipv6Addr::from(octets).into())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if this is an IPv6 address.
+    pub fn is_ipv6(&self) -> bool {
+        self.raw.m_family == dmSocket::Domain_DOMAIN_IPV6
+    }
+
+    fn domain(&self) -> dmSocket::Domain {
+        self.raw.m_family
+    }
+
+    fn empty() -> Self {
+        Self {
+            raw: RawAddress {
+                m_family: dmSocket::Domain_DOMAIN_MISSING,
+                m_address: [0; 4],
+            },
+        }
+    }
+}
+
+impl From<IpAddr> for Address {
+    fn from(ip: IpAddr) -> Self {
+        let mut address = Self::empty();
+        match ip {
+            IpAddr::V4(ip) => {
+                address.raw.m_family = dmSocket::Domain_DOMAIN_IPV4;
+                address.raw.m_address[3] = u32::from_ne_bytes(ip.octets());
+            }
+            IpAddr::V6(ip) => {
+                address.raw.m_family = dmSocket::Domain_DOMAIN_IPV6;
+                for (word, chunk) in address
+                    .raw
+                    .m_address
+                    .iter_mut()
+                    .zip(ip.octets().chunks_exact(4))
+                {
+                    *word = u32::from_ne_bytes(chunk.try_into().unwrap());
+                }
+            }
+        }
+        address
+    }
+}
+
+impl From<Ipv4Addr> for Address {
+    fn from(ip: Ipv4Addr) -> Self {
+        IpAddr::V4(ip).into()
+    }
+}
+
+impl From<Ipv6Addr> for Address {
+    fn from(ip: Ipv6Addr) -> Self {
+        IpAddr::V6(ip).into()
+    }
+}
+
+impl From<RawAddress> for Address {
+    fn from(raw: RawAddress) -> Self {
+        Self { raw }
+    }
+}
+
+impl From<Address> for RawAddress {
+    fn from(address: Address) -> Self {
+        address.raw
+    }
+}
+
+impl PartialEq for Address {
+    fn eq(&self, other: &Self) -> bool {
+        self.ip() == other.ip()
+    }
+}
+
+impl Eq for Address {}
+
+impl fmt::Debug for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Address").field(&self.ip()).finish()
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.ip() {
+            Some(ip) => ip.fmt(f),
+            None => f.write_str("<no address>"),
+        }
+    }
+}
+
+/// Resolves a host name to an address, preferring whichever family the platform returns first.
+pub fn lookup_host(name: &str) -> Result<Address> {
+    let name = CString::new(name).map_err(|_| Error::InvalidArgument)?;
+    let mut address = Address::empty();
+    check(unsafe { dmSocket::GetHostByName(name.as_ptr(), &mut address.raw, true, true) })?;
+    Ok(address)
+}
+
+/// Types that wrap a raw engine socket.
+pub trait AsRawSocket {
+    /// Returns the raw socket handle.
+    fn as_raw_socket(&self) -> RawSocket;
+}
+
+pub(crate) fn timeout_micros(timeout: Option<Duration>) -> u64 {
+    timeout.map_or(0, |timeout| {
+        timeout.as_micros().clamp(1, u64::MAX as u128) as u64
+    })
+}
+
+fn buffer_len(len: usize) -> i32 {
+    len.min(i32::MAX as usize) as i32
+}
+
+pub(crate) fn send(socket: RawSocket, buf: &[u8]) -> Result<usize> {
+    let mut sent = 0;
+    check(unsafe {
+        dmSocket::Send(
+            socket,
+            buf.as_ptr() as *const c_void,
+            buffer_len(buf.len()),
+            &mut sent,
+        )
+    })?;
+    Ok(sent as usize)
+}
+
+pub(crate) fn receive(socket: RawSocket, buf: &mut [u8]) -> Result<usize> {
+    let mut received = 0;
+    check(unsafe {
+        dmSocket::Receive(
+            socket,
+            buf.as_mut_ptr() as *mut c_void,
+            buffer_len(buf.len()),
+            &mut received,
+        )
+    })?;
+    Ok(received as usize)
+}
+
+/// Reads and clears the pending error of `socket` (`SO_ERROR`), which isn't exposed by `dmSocket`.
+fn take_error(socket: RawSocket) -> Result<Option<Error>> {
+    let fd = unsafe { dmSocket::GetFD(socket) };
+    let mut code: i32 = 0;
+
+    #[cfg(unix)]
+    let failed = {
+        let mut len = std::mem::size_of::<i32>() as libc::socklen_t;
+        unsafe {
+            libc::getsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                libc::SO_ERROR,
+                &mut code as *mut i32 as *mut c_void,
+                &mut len,
+            ) != 0
+        }
+    };
+    #[cfg(windows)]
+    let failed = {
+        const SOL_SOCKET: i32 = 0xffff;
+        const SO_ERROR: i32 = 0x1007;
+        let mut len = std::mem::size_of::<i32>() as i32;
+        unsafe {
+            ffi::getsockopt(
+                fd as usize,
+                SOL_SOCKET,
+                SO_ERROR,
+                &mut code as *mut i32 as *mut libc::c_char,
+                &mut len,
+            ) != 0
+        }
+    };
+
+    if failed {
+        Err(io::Error::last_os_error().kind().into())
+    } else if code == 0 {
+        Ok(None)
+    } else {
+        Ok(Some(io::Error::from_raw_os_error(code).kind().into()))
+    }
+}
+
+/// Owned socket handle, deleted on drop.
+struct Socket {
+    raw: RawSocket,
+}
+
+impl Socket {
+    fn new(
+        domain: dmSocket::Domain,
+        ty: dmSocket::Type,
+        protocol: dmSocket::Protocol,
+    ) -> Result<Self> {
+        let mut raw = dmSocket::INVALID_SOCKET_HANDLE;
+        check(unsafe { dmSocket::New(domain, ty, protocol, &mut raw) })?;
+        Ok(Self { raw })
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) -> Result<()> {
+        check(unsafe { dmSocket::SetBlocking(self.raw, !nonblocking) })
+    }
+
+    fn send(&self, buf: &[u8]) -> Result<usize> {
+        send(self.raw, buf)
+    }
+
+    fn receive(&self, buf: &mut [u8]) -> Result<usize> {
+        receive(self.raw, buf)
+    }
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        unsafe { dmSocket::Delete(self.raw) };
+    }
+}
+
+/// TCP connection.
+///
+/// Reading returns 0 bytes once the peer has closed the connection.
+pub struct TcpStream {
+    socket: Socket,
+}
+
+impl TcpStream {
+    /// Opens a connection to `address` on the given port.
+    pub fn connect(address: impl Into<Address>, port: u16) -> Result<Self> {
+        let address = address.into();
+        let socket = Socket::new(
+            address.domain(),
+            dmSocket::Type_TYPE_STREAM,
+            dmSocket::Protocol_PROTOCOL_TCP,
+        )?;
+        check(unsafe { dmSocket::Connect(socket.raw, address.raw, port.into()) })?;
+        Ok(Self { socket })
+    }
+
+    /// Resolves `host` with [`lookup_host()`] and opens a connection to it on the given port.
+    pub fn connect_host(host: &str, port: u16) -> Result<Self> {
+        Self::connect(lookup_host(host)?, port)
+    }
+
+    /// Starts connecting to `address` without waiting for the connection to be established.
+    ///
+    /// The returned stream is non-blocking. It becomes writable once the connect has finished, see [`Selector`],
+    /// after which [`TcpStream::take_error()`] tells whether it failed.
+    pub fn connect_nonblocking(address: impl Into<Address>, port: u16) -> Result<Self> {
+        let address = address.into();
+        let socket = Socket::new(
+            address.domain(),
+            dmSocket::Type_TYPE_STREAM,
+            dmSocket::Protocol_PROTOCOL_TCP,
+        )?;
+        socket.set_nonblocking(true)?;
+        match check(unsafe { dmSocket::Connect(socket.raw, address.raw, port.into()) }) {
+            Ok(()) | Err(Error::InProgress | Error::WouldBlock) => Ok(Self { socket }),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Makes reads and writes return [`Error::WouldBlock`] instead of waiting.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<()> {
+        self.socket.set_nonblocking(nonblocking)
+    }
+
+    /// Returns and clears the pending error of this connection, such as why a non-blocking connect failed.
+    pub fn take_error(&self) -> Result<Option<Error>> {
+        take_error(self.socket.raw)
+    }
+
+    /// Disables Nagle's algorithm, sending small writes right away.
+    pub fn set_nodelay(&self, nodelay: bool) -> Result<()> {
+        check(unsafe { dmSocket::SetNoDelay(self.socket.raw, nodelay) })
+    }
+
+    /// Acknowledges received data right away instead of delaying it (`TCP_QUICKACK`).
+    ///
+    /// Does nothing on platforms without the option. Where it exists, the system may turn it off again later.
+    pub fn set_quickack(&self, quickack: bool) -> Result<()> {
+        check(unsafe { dmSocket::SetQuickAck(self.socket.raw, quickack) })
+    }
+
+    /// Sets how long a read may wait, [`None`] to wait forever.
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> Result<()> {
+        check(unsafe { dmSocket::SetReceiveTimeout(self.socket.raw, timeout_micros(timeout)) })
+    }
+
+    /// Sets how long a write may wait, [`None`] to wait forever.
+    pub fn set_write_timeout(&self, timeout: Option<Duration>) -> Result<()> {
+        check(unsafe { dmSocket::SetSendTimeout(self.socket.raw, timeout_micros(timeout)) })
+    }
+
+    /// Shuts down the read half, the write half or both halves of this connection.
+    pub fn shutdown(&self, how: Shutdown) -> Result<()> {
+        let how = match how {
+            Shutdown::Read => dmSocket::ShutdownType_SHUTDOWNTYPE_READ,
+            Shutdown::Write => dmSocket::ShutdownType_SHUTDOWNTYPE_WRITE,
+            Shutdown::Both => dmSocket::ShutdownType_SHUTDOWNTYPE_READWRITE,
+        };
+        check(unsafe { dmSocket::Shutdown(self.socket.raw, how) })
+    }
+}
+
+impl Read for TcpStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(self.socket.receive(buf)?)
+    }
+}
+
+impl Write for TcpStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(self.socket.send(buf)?)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl AsRawSocket for TcpStream {
+    fn as_raw_socket(&self) -> RawSocket {
+        self.socket.raw
+    }
+}
+
+impl fmt::Debug for TcpStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TcpStream").field(&self.socket.raw).finish()
+    }
+}
+
+/// UDP socket connected to a single peer.
+///
+/// Datagrams are sent to the connected address, and only datagrams from it are received.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+/// use dmsocket::UdpSocket;
+/// use std::{net::Ipv4Addr, time::Duration};
+///
+/// fn ping(address: Ipv4Addr, port: u16) -> dmsocket::Result<Vec<u8>> {
+///     let socket = UdpSocket::connect(address, port)?;
+///     socket.set_read_timeout(Some(Duration::from_secs(1)))?;
+///     socket.send(b"ping")?;
+///
+///     let mut reply = [0; 512];
+///     let len = socket.recv(&mut reply)?;
+///     Ok(reply[..len].to_vec())
+/// }
+/// ```
+pub struct UdpSocket {
+    socket: Socket,
+}
+
+impl UdpSocket {
+    fn new(address: &Address) -> Result<Self> {
+        let socket = Socket::new(
+            address.domain(),
+            dmSocket::Type_TYPE_DGRAM,
+            dmSocket::Protocol_PROTOCOL_UDP,
+        )?;
+        Ok(Self { socket })
+    }
+
+    fn connect_socket(self, address: Address, port: u16) -> Result<Self> {
+        check(unsafe { dmSocket::Connect(self.socket.raw, address.raw, port.into()) })?;
+        Ok(self)
+    }
+
+    /// Creates a socket connected to `address` on the given port.
+    pub fn connect(address: impl Into<Address>, port: u16) -> Result<Self> {
+        let address = address.into();
+        Self::new(&address)?.connect_socket(address, port)
+    }
+
+    /// Like [`UdpSocket::connect()`], but enables broadcasting first, so `address` can be a broadcast address.
+    pub fn connect_broadcast(address: impl Into<Address>, port: u16) -> Result<Self> {
+        let address = address.into();
+        let socket = Self::new(&address)?;
+        socket.set_broadcast(true)?;
+        socket.connect_socket(address, port)
+    }
+
+    /// Sends a datagram, returning the number of bytes sent.
+    pub fn send(&self, buf: &[u8]) -> Result<usize> {
+        self.socket.send(buf)
+    }
+
+    /// Receives a datagram into `buf`, returning its length. The rest of a datagram that doesn't fit is discarded.
+    pub fn recv(&self, buf: &mut [u8]) -> Result<usize> {
+        self.socket.receive(buf)
+    }
+
+    /// Makes sends and receives return [`Error::WouldBlock`] instead of waiting.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<()> {
+        self.socket.set_nonblocking(nonblocking)
+    }
+
+    /// Allows sending to broadcast addresses (`SO_BROADCAST`).
+    pub fn set_broadcast(&self, broadcast: bool) -> Result<()> {
+        check(unsafe { dmSocket::SetBroadcast(self.socket.raw, broadcast) })
+    }
+
+    /// Sets how long a receive may wait, [`None`] to wait forever.
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> Result<()> {
+        check(unsafe { dmSocket::SetReceiveTimeout(self.socket.raw, timeout_micros(timeout)) })
+    }
+
+    /// Sets how long a send may wait, [`None`] to wait forever.
+    pub fn set_write_timeout(&self, timeout: Option<Duration>) -> Result<()> {
+        check(unsafe { dmSocket::SetSendTimeout(self.socket.raw, timeout_micros(timeout)) })
+    }
+
+    /// Returns and clears the pending error of this socket, such as an ICMP error for an earlier datagram.
+    pub fn take_error(&self) -> Result<Option<Error>> {
+        take_error(self.socket.raw)
+    }
+}
+
+impl AsRawSocket for UdpSocket {
+    fn as_raw_socket(&self) -> RawSocket {
+        self.socket.raw
+    }
+}
+
+impl fmt::Debug for UdpSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("UdpSocket").field(&self.socket.raw).finish()
+    }
+}
+
+/// Kind of readiness checked by a [`Selector`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectorKind {
+    /// The socket can be read from.
+    Read,
+    /// The socket can be written to, or a non-blocking connect has finished.
+    Write,
+    /// An error occurred on the socket.
+    Except,
+}
+
+impl From<SelectorKind> for dmSocket::SelectorKind {
+    fn from(kind: SelectorKind) -> Self {
+        match kind {
+            SelectorKind::Read => dmSocket::SelectorKind_SELECTOR_KIND_READ,
+            SelectorKind::Write => dmSocket::SelectorKind_SELECTOR_KIND_WRITE,
+            SelectorKind::Except => dmSocket::SelectorKind_SELECTOR_KIND_EXCEPT,
+        }
+    }
+}
+
+/// Waits for any of several sockets to become ready.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+/// use dmsocket::{Selector, SelectorKind, TcpStream};
+/// use std::time::Duration;
+///
+/// fn readable(streams: &[TcpStream]) -> dmsocket::Result<Vec<usize>> {
+///     let mut selector = Selector::new();
+///     for stream in streams {
+///         selector.set(SelectorKind::Read, stream);
+///     }
+///
+///     selector.select(Some(Duration::ZERO))?;
+///
+///     Ok((0..streams.len())
+///         .filter(|&i| selector.is_set(SelectorKind::Read, &streams[i]))
+///         .collect())
+/// }
+/// ```
+pub struct Selector {
+    raw: dmSocket::Selector,
+}
+
+impl Selector {
+    /// Creates an empty selector.
+    pub fn new() -> Self {
+        Self {
+            raw: unsafe { dmSocket::Selector::new() },
+        }
+    }
+
+    /// Watches `socket` for `kind` readiness.
+    pub fn set(&mut self, kind: SelectorKind, socket: &impl AsRawSocket) {
+        unsafe { dmSocket::SelectorSet(&mut self.raw, kind.into(), socket.as_raw_socket()) }
+    }
+
+    /// Stops watching `socket` for `kind` readiness.
+    pub fn clear(&mut self, kind: SelectorKind, socket: &impl AsRawSocket) {
+        unsafe { dmSocket::SelectorClear(&mut self.raw, kind.into(), socket.as_raw_socket()) }
+    }
+
+    /// Returns `true` if `socket` was ready for `kind` after the last [`Selector::select()`].
+    pub fn is_set(&mut self, kind: SelectorKind, socket: &impl AsRawSocket) -> bool {
+        unsafe { dmSocket::SelectorIsSet(&mut self.raw, kind.into(), socket.as_raw_socket()) }
+    }
+
+    /// Stops watching every socket.
+    pub fn zero(&mut self) {
+        unsafe { dmSocket::SelectorZero(&mut self.raw) }
+    }
+
+    /// Waits until a watched socket is ready or `timeout` has passed. [`None`] waits forever and
+    /// [`Duration::ZERO`] only polls.
+    pub fn select(&mut self, timeout: Option<Duration>) -> Result<()> {
+        let timeout = timeout.map_or(-1, |timeout| {
+            timeout.as_micros().min(i32::MAX as u128) as i32
+        });
+        check(unsafe { dmSocket::Select(&mut self.raw, timeout) })
+    }
+}
+
+impl Default for Selector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Selector {
+    fn drop(&mut self) {
+        unsafe { dmarray::free_raw(&mut self.raw.m_Poller.m_Pollfds) }
+    }
+}
+
+impl fmt::Debug for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Selector").finish_non_exhaustive()
+    }
+}
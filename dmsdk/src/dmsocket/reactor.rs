@@ -0,0 +1,539 @@
+use super::{Address, Error, Result, Selector, SelectorKind, TcpStream};
+use crate::dmtime;
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+/// Default limit of bytes waiting to be sent on a single connection, see [`Reactor::set_max_buffered()`].
+pub const DEFAULT_MAX_BUFFERED: usize = 64 * 1024;
+
+/// Default limit of bytes read from a single socket per [`Reactor::update()`], see
+/// [`Reactor::set_read_budget()`].
+pub const DEFAULT_READ_BUDGET: usize = 64 * 1024;
+
+/// Identifies a socket registered with a [`Reactor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Token(usize);
+
+/// State of a socket registered with a [`Reactor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// A non-blocking connect is in progress.
+    Connecting,
+    /// The socket is connected.
+    Connected,
+    /// The socket was closed, either by the peer or with [`Context::close()`].
+    Closed,
+    /// The socket failed and was removed.
+    Failed(Error),
+}
+
+/// Something that happened to a socket registered with a [`Reactor`].
+#[derive(Debug)]
+pub enum Event<'a> {
+    /// A non-blocking connect finished.
+    Connected,
+    /// Data was read from a stream.
+    Data(&'a [u8]),
+    /// Every queued byte has been sent, so more data can be queued.
+    Drained,
+    /// Nothing happened on the socket within its timeout. It's removed after this event.
+    TimedOut,
+    /// The peer closed the connection. The socket is removed after this event.
+    Closed,
+    /// The socket failed. It's removed after this event.
+    Error(Error),
+}
+
+type BoxedHandler = Box<dyn FnMut(&mut Context, Event)>;
+
+struct Entry {
+    stream: TcpStream,
+    state: ConnectionState,
+    handler: BoxedHandler,
+    write_buffer: Vec<u8>,
+    timeout: Option<Duration>,
+    last_activity: u64,
+    closing: bool,
+}
+
+impl Entry {
+    fn new(stream: TcpStream, state: ConnectionState, handler: BoxedHandler) -> Self {
+        Self {
+            stream,
+            state,
+            handler,
+            write_buffer: Vec::new(),
+            timeout: None,
+            last_activity: dmtime::get_time(),
+            closing: false,
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        matches!(
+            self.state,
+            ConnectionState::Closed | ConnectionState::Failed(_)
+        ) || (self.closing && self.write_buffer.is_empty())
+    }
+}
+
+/// Shared state handed to every handler.
+struct Shared {
+    next_token: usize,
+    added: Vec<(Token, Entry)>,
+    max_buffered: usize,
+}
+
+impl Shared {
+    fn add(&mut self, entry: Entry) -> Token {
+        let token = Token(self.next_token);
+        self.next_token += 1;
+        self.added.push((token, entry));
+        token
+    }
+}
+
+/// Handle to the socket an [`Event`] is for, passed to its handler.
+pub struct Context<'a> {
+    token: Token,
+    state: &'a mut ConnectionState,
+    write_buffer: &'a mut Vec<u8>,
+    closing: &'a mut bool,
+    shared: &'a mut Shared,
+}
+
+impl Context<'_> {
+    /// Returns the token of the socket.
+    pub fn token(&self) -> Token {
+        self.token
+    }
+
+    /// Returns the state of the socket.
+    pub fn state(&self) -> ConnectionState {
+        *self.state
+    }
+
+    /// Queues `data` to be sent on a stream.
+    ///
+    /// Fails with [`Error::NoBufferSpace`] if that would queue more than the reactor's limit, in which case
+    /// nothing is queued. Wait for [`Event::Drained`] before trying again.
+    pub fn send(&mut self, data: &[u8]) -> Result<()> {
+        queue(self.write_buffer, self.shared.max_buffered, data)
+    }
+
+    /// Closes the socket once everything queued has been sent.
+    pub fn close(&mut self) {
+        *self.closing = true;
+    }
+
+    /// Registers another stream with the same reactor.
+    pub fn add_stream<F>(&mut self, stream: TcpStream, handler: F) -> Result<Token>
+    where
+        F: FnMut(&mut Context, Event) + 'static,
+    {
+        stream.set_nonblocking(true)?;
+        Ok(self.shared.add(Entry::new(
+            stream,
+            ConnectionState::Connected,
+            Box::new(handler),
+        )))
+    }
+}
+
+impl fmt::Debug for Context<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Context")
+            .field("token", &self.token)
+            .field("state", &self.state)
+            .field("buffered", &self.write_buffer.len())
+            .finish()
+    }
+}
+
+fn queue(write_buffer: &mut Vec<u8>, max_buffered: usize, data: &[u8]) -> Result<()> {
+    if write_buffer.len() + data.len() > max_buffered {
+        return Err(Error::NoBufferSpace);
+    }
+    write_buffer.extend_from_slice(data);
+    Ok(())
+}
+
+/// Services many non-blocking sockets from the main thread, without blocking it.
+///
+/// Register sockets together with a handler, then call [`Reactor::update()`] once per frame, typically from
+/// [`Extension::on_update()`](crate::dmextension::Extension::on_update). Every socket is polled with
+/// [`Selector::select()`] and the handlers are called with whatever happened since the last update.
+///
+/// Outgoing data is queued and sent as the socket allows, up to a limit per connection, so a slow peer can't
+/// grow the queue forever. Reads are also capped per update to keep a busy socket from stalling the frame.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+/// use dmextension::{Extension, Params};
+/// use dmsocket::{Event, Reactor};
+/// use std::{net::Ipv4Addr, time::Duration};
+///
+/// #[derive(Default)]
+/// struct Multiplayer {
+///     reactor: Reactor,
+/// }
+///
+/// impl Extension for Multiplayer {
+///     fn ext_init(&mut self, _params: Params) -> dmextension::Result {
+///         let connection = self.reactor.connect(Ipv4Addr::LOCALHOST, 7000, |cx, event| match event {
+///             Event::Connected => {
+///                 let _ = cx.send(b"hello\n");
+///             }
+///             Event::Data(data) => println!("received {} bytes", data.len()),
+///             Event::Closed | Event::TimedOut | Event::Error(_) => println!("disconnected"),
+///             _ => {}
+///         });
+///
+///         match connection {
+///             Ok(token) => {
+///                 self.reactor.set_timeout(token, Some(Duration::from_secs(10)));
+///                 dmextension::Result::Ok
+///             }
+///             Err(_) => dmextension::Result::InitError,
+///         }
+///     }
+///
+///     fn on_update(&mut self, _params: Params) -> dmextension::Result {
+///         self.reactor.update();
+///
+///         dmextension::Result::Ok
+///     }
+/// }
+/// ```
+pub struct Reactor {
+    entries: BTreeMap<Token, Entry>,
+    selector: Selector,
+    shared: Shared,
+    read_buffer: Vec<u8>,
+}
+
+impl Default for Reactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reactor {
+    /// Creates a reactor without any sockets.
+    pub fn new() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            selector: Selector::new(),
+            shared: Shared {
+                next_token: 0,
+                added: Vec::new(),
+                max_buffered: DEFAULT_MAX_BUFFERED,
+            },
+            read_buffer: vec![0; DEFAULT_READ_BUDGET],
+        }
+    }
+
+    /// Sets how many bytes may wait to be sent on a single connection.
+    pub fn set_max_buffered(&mut self, max_buffered: usize) {
+        self.shared.max_buffered = max_buffered;
+    }
+
+    /// Sets how many bytes are read from a single socket per update at most.
+    pub fn set_read_budget(&mut self, read_budget: usize) {
+        self.read_buffer.resize(read_budget.max(1), 0);
+    }
+
+    /// Starts connecting to `address` and registers the connection.
+    ///
+    /// The handler gets [`Event::Connected`] once the connection is established.
+    pub fn connect<F>(
+        &mut self,
+        address: impl Into<Address>,
+        port: u16,
+        handler: F,
+    ) -> Result<Token>
+    where
+        F: FnMut(&mut Context, Event) + 'static,
+    {
+        let stream = TcpStream::connect_nonblocking(address, port)?;
+        Ok(self.insert(Entry::new(
+            stream,
+            ConnectionState::Connecting,
+            Box::new(handler),
+        )))
+    }
+
+    /// Registers a connected stream.
+    pub fn add_stream<F>(&mut self, stream: TcpStream, handler: F) -> Result<Token>
+    where
+        F: FnMut(&mut Context, Event) + 'static,
+    {
+        stream.set_nonblocking(true)?;
+        Ok(self.insert(Entry::new(
+            stream,
+            ConnectionState::Connected,
+            Box::new(handler),
+        )))
+    }
+
+    fn insert(&mut self, entry: Entry) -> Token {
+        let token = self.shared.add(entry);
+        self.entries.extend(self.shared.added.drain(..));
+        token
+    }
+
+    /// Returns the state of a socket, or [`None`] if it's no longer registered.
+    pub fn state(&self, token: Token) -> Option<ConnectionState> {
+        self.entries.get(&token).map(|entry| entry.state)
+    }
+
+    /// Returns the number of bytes waiting to be sent on a socket.
+    pub fn buffered(&self, token: Token) -> usize {
+        self.entries
+            .get(&token)
+            .map_or(0, |entry| entry.write_buffer.len())
+    }
+
+    /// Returns the number of registered sockets.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no sockets are registered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Closes a socket after the next update if nothing happens on it for `timeout`, or never if [`None`].
+    ///
+    /// A connection that is still connecting when the timeout passes fails the same way.
+    pub fn set_timeout(&mut self, token: Token, timeout: Option<Duration>) {
+        if let Some(entry) = self.entries.get_mut(&token) {
+            entry.timeout = timeout;
+            entry.last_activity = dmtime::get_time();
+        }
+    }
+
+    /// Queues `data` to be sent on a stream, see [`Context::send()`].
+    pub fn send(&mut self, token: Token, data: &[u8]) -> Result<()> {
+        let entry = self.entries.get_mut(&token).ok_or(Error::NotConnected)?;
+        queue(&mut entry.write_buffer, self.shared.max_buffered, data)
+    }
+
+    /// Closes a socket once everything queued on it has been sent.
+    pub fn close(&mut self, token: Token) {
+        if let Some(entry) = self.entries.get_mut(&token) {
+            entry.closing = true;
+        }
+    }
+
+    /// Removes a socket right away, dropping anything still queued on it.
+    pub fn remove(&mut self, token: Token) {
+        self.entries.remove(&token);
+    }
+
+    /// Polls every socket without blocking and calls the handlers with what happened.
+    pub fn update(&mut self) {
+        if self.entries.is_empty() {
+            return;
+        }
+
+        self.selector.zero();
+        for entry in self.entries.values() {
+            self.selector.set(SelectorKind::Read, &entry.stream);
+            self.selector.set(SelectorKind::Except, &entry.stream);
+            if entry.state == ConnectionState::Connecting || !entry.write_buffer.is_empty() {
+                self.selector.set(SelectorKind::Write, &entry.stream);
+            }
+        }
+
+        let select_error = self.selector.select(Some(Duration::ZERO)).err();
+        let now = dmtime::get_time();
+
+        for (&token, entry) in self.entries.iter_mut() {
+            let readable = self.selector.is_set(SelectorKind::Read, &entry.stream);
+            let writable = self.selector.is_set(SelectorKind::Write, &entry.stream);
+            let failed = self.selector.is_set(SelectorKind::Except, &entry.stream);
+
+            let mut events = Vec::new();
+            if let Some(err) = select_error.filter(|err| *err != Error::Interrupted) {
+                events.push(Pending::Error(err));
+            } else {
+                service(
+                    entry,
+                    readable,
+                    writable,
+                    failed,
+                    &mut self.read_buffer,
+                    &mut events,
+                );
+            }
+
+            if !events.is_empty() {
+                entry.last_activity = now;
+            } else if let Some(timeout) = entry.timeout {
+                if now.saturating_sub(entry.last_activity) > timeout.as_micros() as u64 {
+                    events.push(Pending::TimedOut);
+                }
+            }
+
+            for event in events {
+                let event = match event {
+                    Pending::Connected => {
+                        entry.state = ConnectionState::Connected;
+                        Event::Connected
+                    }
+                    Pending::Data(range) => Event::Data(&self.read_buffer[range]),
+                    Pending::Drained => Event::Drained,
+                    Pending::TimedOut => {
+                        entry.state = match entry.state {
+                            ConnectionState::Connecting => ConnectionState::Failed(Error::TimedOut),
+                            _ => ConnectionState::Closed,
+                        };
+                        Event::TimedOut
+                    }
+                    Pending::Closed => {
+                        entry.state = ConnectionState::Closed;
+                        Event::Closed
+                    }
+                    Pending::Error(err) => {
+                        entry.state = ConnectionState::Failed(err);
+                        Event::Error(err)
+                    }
+                };
+
+                dispatch(token, entry, &mut self.shared, event);
+            }
+
+            // Send whatever the handlers queued right away instead of waiting for the next update
+            if !entry.is_done() {
+                match flush(entry) {
+                    Some(Pending::Drained) => {
+                        dispatch(token, entry, &mut self.shared, Event::Drained)
+                    }
+                    Some(Pending::Error(err)) => {
+                        entry.state = ConnectionState::Failed(err);
+                        dispatch(token, entry, &mut self.shared, Event::Error(err));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        self.entries.retain(|_, entry| !entry.is_done());
+        self.entries.extend(self.shared.added.drain(..));
+    }
+}
+
+impl fmt::Debug for Reactor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.entries
+                    .iter()
+                    .map(|(token, entry)| (token, entry.state)),
+            )
+            .finish()
+    }
+}
+
+fn dispatch(token: Token, entry: &mut Entry, shared: &mut Shared, event: Event) {
+    let mut cx = Context {
+        token,
+        state: &mut entry.state,
+        write_buffer: &mut entry.write_buffer,
+        closing: &mut entry.closing,
+        shared,
+    };
+    (entry.handler)(&mut cx, event);
+}
+
+/// Event waiting to be passed to a handler, with data referring to the shared read buffer.
+enum Pending {
+    Connected,
+    Data(std::ops::Range<usize>),
+    Drained,
+    TimedOut,
+    Closed,
+    Error(Error),
+}
+
+fn service(
+    entry: &mut Entry,
+    readable: bool,
+    writable: bool,
+    failed: bool,
+    read_buffer: &mut [u8],
+    events: &mut Vec<Pending>,
+) {
+    if entry.state == ConnectionState::Connecting {
+        if !writable && !failed {
+            return;
+        }
+        // A refused connect also shows up as writable, only the pending error tells them apart
+        match entry.stream.take_error() {
+            Ok(None) if !failed => events.push(Pending::Connected),
+            Ok(None) => {
+                events.push(Pending::Error(Error::ConnectionRefused));
+                return;
+            }
+            Ok(Some(err)) | Err(err) => {
+                events.push(Pending::Error(err));
+                return;
+            }
+        }
+    }
+
+    if readable {
+        // Handlers are called after servicing, so each read gets its own slice of the buffer
+        let budget = read_buffer.len();
+        let mut read = 0;
+        while read < budget {
+            match entry.stream.socket.receive(&mut read_buffer[read..budget]) {
+                Ok(0) => {
+                    events.push(Pending::Closed);
+                    return;
+                }
+                Ok(len) => {
+                    events.push(Pending::Data(read..read + len));
+                    read += len;
+                }
+                Err(Error::WouldBlock | Error::TryAgain) => break,
+                Err(err) => {
+                    events.push(Pending::Error(err));
+                    return;
+                }
+            }
+        }
+    } else if failed {
+        events.push(Pending::Error(Error::Unknown));
+        return;
+    }
+
+    if writable {
+        if let Some(event) = flush(entry) {
+            events.push(event);
+        }
+    }
+}
+
+/// Sends as much of the write buffer as the socket takes without blocking.
+fn flush(entry: &mut Entry) -> Option<Pending> {
+    if entry.write_buffer.is_empty() || entry.state != ConnectionState::Connected {
+        return None;
+    }
+
+    let mut sent = 0;
+    let result = loop {
+        if sent == entry.write_buffer.len() {
+            break Some(Pending::Drained);
+        }
+        match entry.stream.socket.send(&entry.write_buffer[sent..]) {
+            Ok(0) | Err(Error::WouldBlock | Error::TryAgain) => break None,
+            Ok(len) => sent += len,
+            Err(err) => break Some(Pending::Error(err)),
+        }
+    };
+    entry.write_buffer.drain(..sent);
+    result
+}
@@ -0,0 +1,150 @@
+use super::{buffer_len, check, timeout_micros, AsRawSocket, Error, RawSocket, Result, TcpStream};
+use dmsdk_ffi::dmSSLSocket;
+use libc::c_void;
+use std::{
+    ffi::CString,
+    fmt,
+    io::{self, Read, Write},
+    time::Duration,
+};
+
+#[doc(hidden)]
+pub type RawTlsSocket = dmSSLSocket::Socket;
+
+pub(crate) fn check_tls(result: dmSSLSocket::Result) -> Result<()> {
+    match result {
+        dmSSLSocket::Result_RESULT_OK => Ok(()),
+        dmSSLSocket::Result_RESULT_WOULDBLOCK => Err(Error::WouldBlock),
+        dmSSLSocket::Result_RESULT_CONNREFUSED => Err(Error::ConnectionRefused),
+        _ => Err(result.into()),
+    }
+}
+
+pub(crate) fn tls_send(socket: RawTlsSocket, buf: &[u8]) -> Result<usize> {
+    let mut sent = 0;
+    check(unsafe {
+        dmSSLSocket::Send(
+            socket,
+            buf.as_ptr() as *const c_void,
+            buffer_len(buf.len()),
+            &mut sent,
+        )
+    })?;
+    Ok(sent as usize)
+}
+
+pub(crate) fn tls_receive(socket: RawTlsSocket, buf: &mut [u8]) -> Result<usize> {
+    let mut received = 0;
+    check(unsafe {
+        dmSSLSocket::Receive(
+            socket,
+            buf.as_mut_ptr() as *mut c_void,
+            buffer_len(buf.len()),
+            &mut received,
+        )
+    })?;
+    Ok(received as usize)
+}
+
+/// TLS connection on top of a [`TcpStream`].
+///
+/// The server certificate is only verified if the game sets `network.ssl_certificates` in its `game.project`,
+/// against the root certificates in that file and the host name passed when connecting. Without it, the
+/// connection is encrypted but any certificate is accepted, including self-signed ones.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+/// use dmsocket::TlsStream;
+/// use std::{
+///     io::{Read, Write},
+///     time::Duration,
+/// };
+///
+/// fn fetch(host: &str) -> std::io::Result<Vec<u8>> {
+///     let mut stream = TlsStream::connect(host, 443, Some(Duration::from_secs(5)))?;
+///     write!(stream, "GET / HTTP/1.0\r\nHost: {host}\r\n\r\n")?;
+///
+///     let mut response = Vec::new();
+///     stream.read_to_end(&mut response)?;
+///     Ok(response)
+/// }
+/// ```
+pub struct TlsStream {
+    raw: RawTlsSocket,
+    stream: TcpStream,
+}
+
+impl TlsStream {
+    /// Connects to `host` on the given port and performs the TLS handshake, waiting at most `timeout` for it.
+    pub fn connect(host: &str, port: u16, timeout: Option<Duration>) -> Result<Self> {
+        Self::from_stream(TcpStream::connect_host(host, port)?, host, timeout)
+    }
+
+    /// Performs the TLS handshake on an already connected stream.
+    ///
+    /// `host` is used for server name indication, and to verify the server certificate if the game is set up
+    /// to, see [`TlsStream`].
+    pub fn from_stream(stream: TcpStream, host: &str, timeout: Option<Duration>) -> Result<Self> {
+        let host = CString::new(host).map_err(|_| Error::InvalidArgument)?;
+        let mut raw = std::ptr::null_mut();
+        check_tls(unsafe {
+            dmSSLSocket::New(
+                stream.as_raw_socket(),
+                host.as_ptr(),
+                timeout_micros(timeout),
+                &mut raw,
+            )
+        })?;
+        Ok(Self { raw, stream })
+    }
+
+    /// Returns the underlying TCP stream.
+    pub fn get_ref(&self) -> &TcpStream {
+        &self.stream
+    }
+
+    /// Sets how long a read may wait, [`None`] to wait forever.
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> Result<()> {
+        check(unsafe { dmSSLSocket::SetReceiveTimeout(self.raw, timeout_micros(timeout)) })
+    }
+
+    /// Sets how long a write may wait, [`None`] to wait forever.
+    pub fn set_write_timeout(&self, timeout: Option<Duration>) -> Result<()> {
+        self.stream.set_write_timeout(timeout)
+    }
+}
+
+impl Read for TlsStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(tls_receive(self.raw, buf)?)
+    }
+}
+
+impl Write for TlsStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(tls_send(self.raw, buf)?)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl AsRawSocket for TlsStream {
+    fn as_raw_socket(&self) -> RawSocket {
+        self.stream.as_raw_socket()
+    }
+}
+
+impl Drop for TlsStream {
+    fn drop(&mut self) {
+        unsafe { dmSSLSocket::Delete(self.raw) };
+    }
+}
+
+impl fmt::Debug for TlsStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TlsStream").field(&self.stream).finish()
+    }
+}
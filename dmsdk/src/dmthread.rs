@@ -0,0 +1,492 @@
+//! Threads, thread-local storage and locks backed by the engine.
+//!
+//! Threads spawned here are named through the engine, so they show up in the profiler,
+//! and behave the same on every platform Defold supports.
+
+use dmsdk_ffi::{dmConditionVariable, dmMutex, dmThread};
+use libc::c_void;
+use std::{
+    cell::UnsafeCell,
+    ffi::CString,
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock,
+    },
+    thread::Result,
+};
+
+/// Stack size of threads spawned with [`spawn()`].
+pub const DEFAULT_STACK_SIZE: u32 = 0x80000;
+
+/// Thread factory, to configure the stack size of a new thread.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// fn start_decoder() -> dmthread::JoinHandle<()> {
+///     dmthread::Builder::new("audio_decoder")
+///         .stack_size(0x20000)
+///         .spawn(|| {
+///             // Decode audio...
+///         })
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Builder {
+    name: String,
+    stack_size: u32,
+}
+
+impl Builder {
+    /// Creates a builder for a thread called `name`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            stack_size: DEFAULT_STACK_SIZE,
+        }
+    }
+
+    /// Sets the stack size of the new thread, in bytes.
+    pub fn stack_size(mut self, size: u32) -> Self {
+        self.stack_size = size;
+        self
+    }
+
+    /// Spawns a thread running `f`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name contains a nul byte.
+    pub fn spawn<F, T>(self, f: F) -> JoinHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let name = CString::new(self.name).expect("thread name contains a nul byte");
+        let packet = Arc::new(Packet(UnsafeCell::new(None)));
+        let their_packet = packet.clone();
+
+        let main: Box<dyn FnOnce() + Send> = Box::new(move || {
+            // Unwinding into the engine would abort, so panics are handed to `join()` instead
+            let result = panic::catch_unwind(AssertUnwindSafe(f));
+            unsafe { *their_packet.0.get() = Some(result) };
+        });
+        let arg = Box::into_raw(Box::new(main));
+
+        let raw = unsafe {
+            dmThread::New(
+                Some(thread_start),
+                self.stack_size,
+                arg as *mut c_void,
+                name.as_ptr(),
+            )
+        };
+
+        JoinHandle {
+            raw: Some(raw),
+            packet,
+        }
+    }
+}
+
+unsafe extern "C" fn thread_start(arg: *mut c_void) {
+    let main = Box::from_raw(arg as *mut Box<dyn FnOnce() + Send>);
+    main();
+}
+
+// Written once by the spawned thread before it exits, read only after joining it
+struct Packet<T>(UnsafeCell<Option<Result<T>>>);
+
+unsafe impl<T: Send> Sync for Packet<T> {}
+
+/// Spawns a thread called `name` running `f`.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// fn checksum_in_background(data: Vec<u8>) -> u32 {
+///     let worker = dmthread::spawn("checksum", move || {
+///         data.iter().map(|&byte| byte as u32).sum()
+///     });
+///
+///     worker.join().unwrap()
+/// }
+/// ```
+pub fn spawn<F, T>(name: &str, f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    Builder::new(name).spawn(f)
+}
+
+/// Renames the calling thread.
+pub fn set_name(name: &str) {
+    let name = CString::new(name).unwrap();
+    unsafe { dmThread::SetThreadName(dmThread::GetCurrentThread(), name.as_ptr()) };
+}
+
+/// Owned permission to join a thread. The thread is detached if this is dropped without joining.
+pub struct JoinHandle<T> {
+    raw: Option<dmThread::Thread>,
+    packet: Arc<Packet<T>>,
+}
+
+impl<T> JoinHandle<T> {
+    /// Waits for the thread to finish and returns what it returned, or the payload it panicked with.
+    pub fn join(mut self) -> Result<T> {
+        if let Some(raw) = self.raw.take() {
+            unsafe { dmThread::Join(raw) };
+        }
+        unsafe { (*self.packet.0.get()).take() }.expect("thread finished without a result")
+    }
+}
+
+impl<T> Drop for JoinHandle<T> {
+    fn drop(&mut self) {
+        if let Some(raw) = self.raw.take() {
+            unsafe { dmThread::Detach(raw) };
+        }
+    }
+}
+
+impl<T> fmt::Debug for JoinHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinHandle").finish_non_exhaustive()
+    }
+}
+
+/// Thread-local value stored with the engine's [`AllocTls`](dmThread::AllocTls).
+///
+/// Each thread initializes its own value the first time it calls [`ThreadLocal::get_or_init()`].
+/// The engine doesn't run destructors when threads exit, so values are never dropped.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+/// use std::cell::Cell;
+///
+/// static REQUESTS: dmthread::ThreadLocal<Cell<u32>> = dmthread::ThreadLocal::new();
+///
+/// fn count_request() -> u32 {
+///     let requests = REQUESTS.get_or_init(|| Cell::new(0));
+///     requests.set(requests.get() + 1);
+///     requests.get()
+/// }
+/// ```
+pub struct ThreadLocal<T: 'static> {
+    key: OnceLock<dmThread::TlsKey>,
+    _marker: PhantomData<T>,
+}
+
+// Every thread only ever sees its own value
+unsafe impl<T> Send for ThreadLocal<T> {}
+unsafe impl<T> Sync for ThreadLocal<T> {}
+
+impl<T: 'static> ThreadLocal<T> {
+    /// Creates an empty thread-local. The engine key is allocated on first use.
+    pub const fn new() -> Self {
+        Self {
+            key: OnceLock::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    fn key(&self) -> dmThread::TlsKey {
+        *self.key.get_or_init(|| unsafe { dmThread::AllocTls() })
+    }
+
+    /// Returns the calling thread's value, if it has been initialized.
+    pub fn get(&self) -> Option<&T> {
+        let ptr = unsafe { dmThread::GetTlsValue(self.key()) } as *const T;
+        unsafe { ptr.as_ref() }
+    }
+
+    /// Returns the calling thread's value, initializing it with `init` first if needed.
+    pub fn get_or_init(&self, init: impl FnOnce() -> T) -> &T {
+        if let Some(value) = self.get() {
+            return value;
+        }
+
+        let value = init();
+        // `init` may have initialized it already, in which case the first value wins
+        if let Some(value) = self.get() {
+            return value;
+        }
+        let ptr = Box::into_raw(Box::new(value));
+        unsafe {
+            dmThread::SetTlsValue(self.key(), ptr as *mut c_void);
+            &*ptr
+        }
+    }
+}
+
+impl<T: 'static> Default for ThreadLocal<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: 'static> Drop for ThreadLocal<T> {
+    fn drop(&mut self) {
+        if let Some(&key) = self.key.get() {
+            unsafe { dmThread::FreeTls(key) };
+        }
+    }
+}
+
+impl<T: 'static> fmt::Debug for ThreadLocal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThreadLocal").finish_non_exhaustive()
+    }
+}
+
+/// Mutual exclusion lock backed by [`dmMutex`].
+///
+/// Unlike [`std::sync::Mutex`], it isn't poisoned when a thread panics while holding it.
+/// The engine's mutexes are recursive, but this one isn't: locking it again from the thread holding it panics,
+/// and [`Mutex::try_lock()`] returns `None`.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+/// use std::sync::Arc;
+///
+/// fn count_in_parallel() -> u32 {
+///     let counter = Arc::new(dmthread::Mutex::new(0));
+///     let workers: Vec<_> = (0..4)
+///         .map(|i| {
+///             let counter = counter.clone();
+///             dmthread::spawn(&format!("counter_{i}"), move || *counter.lock() += 1)
+///         })
+///         .collect();
+///
+///     for worker in workers {
+///         worker.join().unwrap();
+///     }
+///     let total = *counter.lock();
+///     total
+/// }
+/// ```
+pub struct Mutex<T: ?Sized> {
+    raw: dmMutex::HMutex,
+    // Only accessed with `raw` locked, which tells a recursive lock apart from a first one
+    locked: AtomicBool,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for Mutex<T> {}
+unsafe impl<T: ?Sized + Send> Sync for Mutex<T> {}
+
+impl<T> Mutex<T> {
+    /// Creates an unlocked mutex holding `value`.
+    pub fn new(value: T) -> Self {
+        Self {
+            raw: unsafe { dmMutex::New() },
+            locked: AtomicBool::new(false),
+            data: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consumes the mutex, returning the value it holds.
+    pub fn into_inner(self) -> T {
+        let this = std::mem::ManuallyDrop::new(self);
+        unsafe {
+            dmMutex::Delete(this.raw);
+            std::ptr::read(this.data.get())
+        }
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    /// Blocks until the lock is acquired.
+    ///
+    /// # Panics
+    /// Panics if the current thread already holds the lock.
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        unsafe { dmMutex::Lock(self.raw) };
+        assert!(self.acquired(), "Mutex is already locked by this thread");
+        MutexGuard::new(self)
+    }
+
+    /// Acquires the lock if it is free.
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        (unsafe { dmMutex::TryLock(self.raw) } && self.acquired()).then(|| MutexGuard::new(self))
+    }
+
+    /// Marks the just locked mutex as held, or undoes a recursive lock and returns `false`.
+    fn acquired(&self) -> bool {
+        if self.locked.swap(true, Ordering::Relaxed) {
+            unsafe { dmMutex::Unlock(self.raw) };
+            return false;
+        }
+        true
+    }
+
+    /// Returns a mutable reference to the value without locking, since the borrow guarantees exclusive access.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: ?Sized> Drop for Mutex<T> {
+    fn drop(&mut self) {
+        unsafe { dmMutex::Delete(self.raw) };
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Mutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("Mutex");
+        match self.try_lock() {
+            Some(guard) => d.field("data", &&*guard),
+            None => d.field("data", &format_args!("<locked>")),
+        };
+        d.finish()
+    }
+}
+
+/// Scoped lock of a [`Mutex`]. The mutex is unlocked when this is dropped.
+#[must_use = "if unused the Mutex will immediately unlock"]
+pub struct MutexGuard<'a, T: ?Sized> {
+    mutex: &'a Mutex<T>,
+    // The engine's mutexes must be unlocked by the thread that locked them
+    _not_send: PhantomData<*const ()>,
+}
+
+impl<'a, T: ?Sized> MutexGuard<'a, T> {
+    fn new(mutex: &'a Mutex<T>) -> Self {
+        Self {
+            mutex,
+            _not_send: PhantomData,
+        }
+    }
+}
+
+unsafe impl<T: ?Sized + Sync> Sync for MutexGuard<'_, T> {}
+
+impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.locked.store(false, Ordering::Relaxed);
+        unsafe { dmMutex::Unlock(self.mutex.raw) };
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for MutexGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Condition variable backed by [`dmConditionVariable`], used together with a [`Mutex`].
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+/// use dmthread::{Condvar, Mutex};
+/// use std::sync::Arc;
+///
+/// fn wait_for_load() {
+///     let loaded = Arc::new((Mutex::new(false), Condvar::new()));
+///     let loader = loaded.clone();
+///     dmthread::spawn("loader", move || {
+///         let (lock, condvar) = &*loader;
+///         *lock.lock() = true;
+///         condvar.notify_one();
+///     });
+///
+///     let (lock, condvar) = &*loaded;
+///     let _guard = condvar.wait_while(lock.lock(), |loaded| !*loaded);
+/// }
+/// ```
+pub struct Condvar {
+    raw: dmConditionVariable::HConditionVariable,
+}
+
+unsafe impl Send for Condvar {}
+unsafe impl Sync for Condvar {}
+
+impl Condvar {
+    /// Creates a condition variable.
+    pub fn new() -> Self {
+        Self {
+            raw: unsafe { dmConditionVariable::New() },
+        }
+    }
+
+    /// Unlocks the guarded mutex and blocks until notified, then locks it again.
+    ///
+    /// Wakeups can be spurious, so check the condition again afterwards or use [`Condvar::wait_while()`].
+    pub fn wait<'a, T: ?Sized>(&self, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+        guard.mutex.locked.store(false, Ordering::Relaxed);
+        unsafe { dmConditionVariable::Wait(self.raw, guard.mutex.raw) };
+        guard.mutex.locked.store(true, Ordering::Relaxed);
+        guard
+    }
+
+    /// Waits until `condition` returns `false`.
+    pub fn wait_while<'a, T: ?Sized>(
+        &self,
+        mut guard: MutexGuard<'a, T>,
+        mut condition: impl FnMut(&mut T) -> bool,
+    ) -> MutexGuard<'a, T> {
+        while condition(&mut guard) {
+            guard = self.wait(guard);
+        }
+        guard
+    }
+
+    /// Wakes up one waiting thread.
+    pub fn notify_one(&self) {
+        unsafe { dmConditionVariable::Signal(self.raw) };
+    }
+
+    /// Wakes up every waiting thread.
+    pub fn notify_all(&self) {
+        unsafe { dmConditionVariable::Broadcast(self.raw) };
+    }
+}
+
+impl Default for Condvar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Condvar {
+    fn drop(&mut self) {
+        unsafe { dmConditionVariable::Delete(self.raw) };
+    }
+}
+
+impl fmt::Debug for Condvar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Condvar").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,243 @@
+//! URI parsing and percent-encoding, done the same way the engine does it.
+
+use crate::lua;
+use dmsdk_ffi::dmURI;
+use libc::c_char;
+use std::{
+    ffi::{CStr, CString},
+    fmt,
+    str::FromStr,
+};
+
+/// URI error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// A part of the URI is longer than the engine allows, see [`MAX_LOCATION_LEN`] and [`MAX_PATH_LEN`].
+    TooLong,
+    /// The URI contains a nul byte.
+    InvalidUri,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::TooLong => "URI is too long",
+            Self::InvalidUri => "URI contains a nul byte",
+        })
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// [`Result`](core::result::Result) alias with an error type of [`Error`].
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Maximum length of the scheme, in bytes.
+pub const MAX_SCHEME_LEN: usize = dmURI::MAX_SCHEME_LEN as usize - 1;
+/// Maximum length of the location, in bytes.
+pub const MAX_LOCATION_LEN: usize = dmURI::MAX_LOCATION_LEN as usize - 1;
+/// Maximum length of the path, including the query and fragment, in bytes.
+pub const MAX_PATH_LEN: usize = dmURI::MAX_PATH_LEN as usize - 1;
+
+fn to_string(chars: &[c_char]) -> String {
+    let bytes: Vec<u8> = chars
+        .iter()
+        .take_while(|&&c| c != 0)
+        .map(|&c| c as u8)
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// URI split into its parts by the engine.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// fn invite_code(link: &str) -> dmuri::Result<Option<String>> {
+///     let uri: dmuri::Uri = link.parse()?;
+///     if uri.scheme == "mygame" && uri.path == "/invite" {
+///         return Ok(uri.query);
+///     }
+///     Ok(None)
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Uri {
+    /// Scheme, e.g. `http`.
+    pub scheme: String,
+    /// Location, e.g. `example.com:8080`.
+    pub location: String,
+    /// Host name part of the location, e.g. `example.com`.
+    pub hostname: String,
+    /// Port part of the location, if present.
+    pub port: Option<u16>,
+    /// Path, e.g. `/index.html`.
+    pub path: String,
+    /// Query without the leading `?`, if present.
+    pub query: Option<String>,
+    /// Fragment without the leading `#`, if present.
+    pub fragment: Option<String>,
+}
+
+impl Uri {
+    /// Parses `uri` with [`dmURI::Parse`].
+    pub fn parse(uri: &str) -> Result<Self> {
+        let uri = CString::new(uri).map_err(|_| Error::InvalidUri)?;
+        let mut parts = std::mem::MaybeUninit::<dmURI::Parts>::zeroed();
+        let result = unsafe { dmURI::Parse(uri.as_ptr(), parts.as_mut_ptr()) };
+        if result != dmURI::Result_RESULT_OK {
+            return Err(Error::TooLong);
+        }
+        let parts = unsafe { parts.assume_init() };
+
+        // The engine keeps the query and fragment in the path
+        let mut path = to_string(&parts.m_Path);
+        let fragment = path.find('#').map(|i| {
+            let fragment = path[i + 1..].to_owned();
+            path.truncate(i);
+            fragment
+        });
+        let query = path.find('?').map(|i| {
+            let query = path[i + 1..].to_owned();
+            path.truncate(i);
+            query
+        });
+
+        Ok(Self {
+            scheme: to_string(&parts.m_Scheme),
+            location: to_string(&parts.m_Location),
+            hostname: to_string(&parts.m_Hostname),
+            port: u16::try_from(parts.m_Port).ok(),
+            path,
+            query,
+            fragment,
+        })
+    }
+}
+
+impl FromStr for Uri {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Uri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.scheme.is_empty() {
+            write!(f, "{}://", self.scheme)?;
+        }
+        write!(f, "{}{}", self.location, self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{query}")?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{fragment}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Percent-encodes `s` with [`dmURI::Encode`].
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// fn search_url(term: &str) -> String {
+///     format!("https://example.com/search?q={}", dmuri::encode(term))
+/// }
+/// ```
+pub fn encode(s: &str) -> String {
+    // The engine works on C strings, so nul bytes are encoded here
+    s.split('\0')
+        .map(|segment| {
+            let src = CString::new(segment).unwrap();
+            // Every byte encodes to at most three characters
+            let mut dst = vec![0 as c_char; segment.len() * 3 + 1];
+            let mut written = 0;
+            unsafe {
+                dmURI::Encode(
+                    src.as_ptr(),
+                    dst.as_mut_ptr(),
+                    dst.len() as u32,
+                    &mut written,
+                );
+            }
+            to_string(&dst)
+        })
+        .collect::<Vec<_>>()
+        .join("%00")
+}
+
+/// Decodes percent-encoded `s` with [`dmURI::Decode`].
+///
+/// Sequences that don't decode to valid UTF-8 are replaced with [`std::char::REPLACEMENT_CHARACTER`].
+pub fn decode(s: &str) -> String {
+    fn decode_segment(segment: &str) -> String {
+        let src = CString::new(segment).unwrap();
+        // Decoding never makes a string longer
+        let mut dst = vec![0 as c_char; segment.len() + 1];
+        unsafe { dmURI::Decode(src.as_ptr(), dst.as_mut_ptr()) };
+        let bytes = unsafe { CStr::from_ptr(dst.as_ptr()) }.to_bytes();
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+
+    // Nul bytes, escaped or not, would end the engine's string early
+    s.split('\0')
+        .flat_map(|part| part.split("%00"))
+        .map(decode_segment)
+        .collect::<Vec<_>>()
+        .join("\0")
+}
+
+/// Pushes `uri` onto the stack as a table with the fields of [`Uri`].
+///
+/// `port`, `query` and `fragment` are left out when not present.
+pub fn push_uri(l: lua::State, uri: &Uri) {
+    lua::new_table(l);
+    for (name, value) in [
+        ("scheme", Some(&uri.scheme)),
+        ("location", Some(&uri.location)),
+        ("hostname", Some(&uri.hostname)),
+        ("path", Some(&uri.path)),
+        ("query", uri.query.as_ref()),
+        ("fragment", uri.fragment.as_ref()),
+    ] {
+        if let Some(value) = value {
+            lua::push_string(l, value);
+            lua::set_field(l, -2, name);
+        }
+    }
+    if let Some(port) = uri.port {
+        lua::push_integer(l, port as isize);
+        lua::set_field(l, -2, "port");
+    }
+}
+
+/// Checks if there is a Lua string at `i` and parses it into a [`Uri`].
+///
+/// Raises a Lua error if the string isn't a valid URI.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// fn open_link(l: lua::State) -> i32 {
+///     let uri = dmuri::check_uri(l, 1);
+///     dmuri::push_uri(l, &uri);
+///
+///     1
+/// }
+/// ```
+pub fn check_uri(l: lua::State, i: i32) -> Uri {
+    let uri = lua::check_string(l, i);
+    match Uri::parse(&uri) {
+        Ok(uri) => uri,
+        Err(err) => {
+            lua::error!(l, "bad argument #{i} ({err})");
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Conversions to and from [`glam`](::glam) types.
+
+use super::{Matrix3, Matrix4, Point3, Quat, Vector3, Vector4};
+
+impl From<::glam::Vec3> for Vector3 {
+    fn from(v: ::glam::Vec3) -> Self {
+        Self::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<Vector3> for ::glam::Vec3 {
+    fn from(v: Vector3) -> Self {
+        Self::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<::glam::Vec3A> for Vector3 {
+    fn from(v: ::glam::Vec3A) -> Self {
+        Self::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<Vector3> for ::glam::Vec3A {
+    fn from(v: Vector3) -> Self {
+        Self::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<::glam::Vec3> for Point3 {
+    fn from(v: ::glam::Vec3) -> Self {
+        Self::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<Point3> for ::glam::Vec3 {
+    fn from(p: Point3) -> Self {
+        Self::new(p.x, p.y, p.z)
+    }
+}
+
+impl From<::glam::Vec4> for Vector4 {
+    fn from(v: ::glam::Vec4) -> Self {
+        Self::new(v.x, v.y, v.z, v.w)
+    }
+}
+
+impl From<Vector4> for ::glam::Vec4 {
+    fn from(v: Vector4) -> Self {
+        Self::new(v.x, v.y, v.z, v.w)
+    }
+}
+
+impl From<::glam::Quat> for Quat {
+    fn from(q: ::glam::Quat) -> Self {
+        Self::new(q.x, q.y, q.z, q.w)
+    }
+}
+
+impl From<Quat> for ::glam::Quat {
+    fn from(q: Quat) -> Self {
+        Self::from_xyzw(q.x, q.y, q.z, q.w)
+    }
+}
+
+impl From<::glam::Mat3> for Matrix3 {
+    fn from(m: ::glam::Mat3) -> Self {
+        Self::from_cols(m.x_axis.into(), m.y_axis.into(), m.z_axis.into())
+    }
+}
+
+impl From<Matrix3> for ::glam::Mat3 {
+    fn from(m: Matrix3) -> Self {
+        Self::from_cols(m.c0.into(), m.c1.into(), m.c2.into())
+    }
+}
+
+impl From<::glam::Mat4> for Matrix4 {
+    fn from(m: ::glam::Mat4) -> Self {
+        Self::from_cols(
+            m.x_axis.into(),
+            m.y_axis.into(),
+            m.z_axis.into(),
+            m.w_axis.into(),
+        )
+    }
+}
+
+impl From<Matrix4> for ::glam::Mat4 {
+    fn from(m: Matrix4) -> Self {
+        Self::from_cols(m.c0.into(), m.c1.into(), m.c2.into(), m.c3.into())
+    }
+}
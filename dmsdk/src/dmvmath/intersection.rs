@@ -0,0 +1,281 @@
+//! Frustum culling with the engine's intersection tests.
+//!
+//! Frustums can be made without the near and far planes, in which case the tests ignore how far things are.
+//!
+//! # Examples
+//! ```
+//! use dmsdk::dmvmath::{intersection::*, *};
+//!
+//! fn draw_visible(view_proj: Matrix4, bounds: &[Sphere], visible: &mut BitSet) {
+//!     let frustum = Frustum::from_matrix(view_proj, 6);
+//!     frustum.cull_into(bounds, visible);
+//!     for i in visible.ones() {
+//!         println!("drawing instance {i}");
+//!     }
+//! }
+//! ```
+
+use super::{Matrix4, Point3, Vector3, Vector4};
+use dmsdk_ffi::{dmIntersection, dmVMath};
+
+/// Plane made of the points `p` where `normal.dot(p) + d` is zero.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Plane {
+    pub normal: Vector3,
+    pub d: f32,
+}
+
+impl Plane {
+    /// Creates a plane from its normal and offset.
+    pub const fn new(normal: Vector3, d: f32) -> Self {
+        Self { normal, d }
+    }
+
+    /// Creates the plane going through `point` and facing `normal`.
+    pub fn from_point_normal(point: Point3, normal: Vector3) -> Self {
+        Self::new(normal, -normal.dot(point.into()))
+    }
+
+    /// Returns the signed distance from the plane to `point`, which is positive on the side the normal faces.
+    ///
+    /// This is only a true distance if the normal has a length of 1.
+    pub fn distance(self, point: Point3) -> f32 {
+        unsafe { dmIntersection::DistanceToPlane(self.into(), point.into()) }
+    }
+}
+
+impl From<dmIntersection::Plane> for Plane {
+    fn from(plane: dmIntersection::Plane) -> Self {
+        let v = Vector4::from(plane);
+        Self::new(v.truncate(), v.w)
+    }
+}
+
+impl From<Plane> for dmIntersection::Plane {
+    fn from(plane: Plane) -> Self {
+        plane.normal.extend(plane.d).into()
+    }
+}
+
+/// Sphere, for testing against a [`Frustum`].
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Sphere {
+    pub center: Point3,
+    pub radius: f32,
+}
+
+impl Sphere {
+    /// Creates a sphere.
+    pub const fn new(center: Point3, radius: f32) -> Self {
+        Self { center, radius }
+    }
+}
+
+/// Oriented box, made of an axis-aligned box in local space and the transform that places it in the world.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Obb {
+    pub world: Matrix4,
+    pub min: Vector3,
+    pub max: Vector3,
+}
+
+impl Obb {
+    /// Creates an oriented box from the corners of a local box and its world transform.
+    pub const fn new(world: Matrix4, min: Vector3, max: Vector3) -> Self {
+        Self { world, min, max }
+    }
+}
+
+/// Region of space inside four or six planes, usually what a camera can see.
+#[derive(Debug, Clone, Copy)]
+pub struct Frustum {
+    raw: dmIntersection::Frustum,
+}
+
+impl Frustum {
+    /// Creates the frustum of a projection matrix, or of a projection multiplied with a view matrix to get it in
+    /// world space.
+    ///
+    /// `num_planes` is 6 for a complete frustum, or 4 to leave out the near and far planes.
+    /// The planes are normalized, so their distances can be compared with a sphere's radius.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_planes` is neither 4 nor 6.
+    pub fn from_matrix(m: Matrix4, num_planes: usize) -> Self {
+        assert!(
+            num_planes == 4 || num_planes == 6,
+            "a frustum has 4 or 6 planes, not {num_planes}"
+        );
+        let mut raw = dmIntersection::Frustum {
+            m_Planes: [Plane::default().into(); 6],
+            m_NumPlanes: num_planes as i32,
+        };
+        unsafe {
+            dmIntersection::CreateFrustumFromMatrix(&m.into(), true, num_planes as i32, &mut raw)
+        };
+        Self { raw }
+    }
+
+    /// Creates a frustum from its planes: left, right, bottom, top, near and far, with normals facing inwards.
+    pub fn from_planes(planes: [Plane; 6]) -> Self {
+        Self {
+            raw: dmIntersection::Frustum {
+                m_Planes: planes.map(Into::into),
+                m_NumPlanes: 6,
+            },
+        }
+    }
+
+    /// Returns the planes in the same order as [`Frustum::from_planes()`].
+    ///
+    /// Only the first [`Frustum::num_planes()`] are used, the others are zero.
+    pub fn planes(&self) -> [Plane; 6] {
+        self.raw.m_Planes.map(Into::into)
+    }
+
+    /// Returns the number of planes, 4 without the near and far planes or 6 with them.
+    pub fn num_planes(&self) -> usize {
+        self.raw.m_NumPlanes as usize
+    }
+
+    /// Returns `true` if `point` is inside the frustum.
+    pub fn test_point(&self, point: Point3) -> bool {
+        unsafe { dmIntersection::TestFrustumPoint(&self.raw, &point.into()) }
+    }
+
+    /// Returns `true` if `sphere` is at least partly inside the frustum.
+    pub fn test_sphere(&self, sphere: &Sphere) -> bool {
+        unsafe {
+            dmIntersection::TestFrustumSphere(&self.raw, &sphere.center.into(), sphere.radius)
+        }
+    }
+
+    /// Same as [`Frustum::test_sphere()`], with the radius already squared.
+    pub fn test_sphere_sq(&self, center: Point3, radius_sq: f32) -> bool {
+        unsafe { dmIntersection::TestFrustumSphereSq(&self.raw, &center.into(), radius_sq) }
+    }
+
+    /// Returns `true` if `obb` is at least partly inside the frustum.
+    ///
+    /// Boxes close to a corner of the frustum may be reported as inside even though they're not.
+    pub fn test_obb(&self, obb: &Obb) -> bool {
+        let world: dmVMath::Matrix4 = obb.world.into();
+        // Taken by mutable reference, though the engine only reads them
+        let mut min = obb.min.into();
+        let mut max = obb.max.into();
+        unsafe { dmIntersection::TestFrustumOBB(&self.raw, &world, &mut min, &mut max) }
+    }
+
+    /// Tests every item of `bounds` and returns which ones are inside the frustum.
+    pub fn cull<B: Bounds>(&self, bounds: &[B]) -> BitSet {
+        let mut visible = BitSet::new();
+        self.cull_into(bounds, &mut visible);
+        visible
+    }
+
+    /// Same as [`Frustum::cull()`], reusing the memory of `visible`, which is cleared first.
+    pub fn cull_into<B: Bounds>(&self, bounds: &[B], visible: &mut BitSet) {
+        visible.reset(bounds.len());
+        for (word, chunk) in visible.words.iter_mut().zip(bounds.chunks(64)) {
+            for (bit, item) in chunk.iter().enumerate() {
+                if item.test_frustum(self) {
+                    *word |= 1 << bit;
+                }
+            }
+        }
+    }
+}
+
+/// Something that can be tested against a [`Frustum`], used by [`Frustum::cull()`].
+pub trait Bounds {
+    /// Returns `true` if `self` is at least partly inside `frustum`.
+    fn test_frustum(&self, frustum: &Frustum) -> bool;
+}
+
+impl Bounds for Point3 {
+    fn test_frustum(&self, frustum: &Frustum) -> bool {
+        frustum.test_point(*self)
+    }
+}
+
+impl Bounds for Sphere {
+    fn test_frustum(&self, frustum: &Frustum) -> bool {
+        frustum.test_sphere(self)
+    }
+}
+
+impl Bounds for Obb {
+    fn test_frustum(&self, frustum: &Frustum) -> bool {
+        frustum.test_obb(self)
+    }
+}
+
+/// Fixed-size set of bits, returned by [`Frustum::cull()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitSet {
+    /// Creates an empty set.
+    pub const fn new() -> Self {
+        Self {
+            words: Vec::new(),
+            len: 0,
+        }
+    }
+
+    fn reset(&mut self, len: usize) {
+        self.words.clear();
+        self.words.resize(len.div_ceil(64), 0);
+        self.len = len;
+    }
+
+    /// Returns the number of bits.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no bits.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns bit `i`, or `false` if it's out of bounds.
+    pub fn get(&self, i: usize) -> bool {
+        i < self.len && self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    /// Returns the number of bits that are set.
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns the indices of the bits that are set, in order.
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+
+    /// Returns the bits packed into words, bit `i` being bit `i % 64` of word `i / 64`.
+    pub fn as_words(&self) -> &[u64] {
+        &self.words
+    }
+}
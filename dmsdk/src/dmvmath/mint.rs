@@ -0,0 +1,100 @@
+//! Conversions to and from [`mint`](::mint) types.
+
+use super::{Matrix3, Matrix4, Point3, Quat, Vector3, Vector4};
+
+impl From<::mint::Vector3<f32>> for Vector3 {
+    fn from(v: ::mint::Vector3<f32>) -> Self {
+        Self::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<Vector3> for ::mint::Vector3<f32> {
+    fn from(v: Vector3) -> Self {
+        Self {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+        }
+    }
+}
+
+impl From<::mint::Point3<f32>> for Point3 {
+    fn from(p: ::mint::Point3<f32>) -> Self {
+        Self::new(p.x, p.y, p.z)
+    }
+}
+
+impl From<Point3> for ::mint::Point3<f32> {
+    fn from(p: Point3) -> Self {
+        Self {
+            x: p.x,
+            y: p.y,
+            z: p.z,
+        }
+    }
+}
+
+impl From<::mint::Vector4<f32>> for Vector4 {
+    fn from(v: ::mint::Vector4<f32>) -> Self {
+        Self::new(v.x, v.y, v.z, v.w)
+    }
+}
+
+impl From<Vector4> for ::mint::Vector4<f32> {
+    fn from(v: Vector4) -> Self {
+        Self {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+            w: v.w,
+        }
+    }
+}
+
+impl From<::mint::Quaternion<f32>> for Quat {
+    fn from(q: ::mint::Quaternion<f32>) -> Self {
+        Self::new(q.v.x, q.v.y, q.v.z, q.s)
+    }
+}
+
+impl From<Quat> for ::mint::Quaternion<f32> {
+    fn from(q: Quat) -> Self {
+        Self {
+            v: Vector3::new(q.x, q.y, q.z).into(),
+            s: q.w,
+        }
+    }
+}
+
+impl From<::mint::ColumnMatrix3<f32>> for Matrix3 {
+    fn from(m: ::mint::ColumnMatrix3<f32>) -> Self {
+        Self::from_cols(m.x.into(), m.y.into(), m.z.into())
+    }
+}
+
+impl From<Matrix3> for ::mint::ColumnMatrix3<f32> {
+    fn from(m: Matrix3) -> Self {
+        Self {
+            x: m.c0.into(),
+            y: m.c1.into(),
+            z: m.c2.into(),
+        }
+    }
+}
+
+impl From<::mint::ColumnMatrix4<f32>> for Matrix4 {
+    fn from(m: ::mint::ColumnMatrix4<f32>) -> Self {
+        Self::from_cols(m.x.into(), m.y.into(), m.z.into(), m.w.into())
+    }
+}
+
+impl From<Matrix4> for ::mint::ColumnMatrix4<f32> {
+    fn from(m: Matrix4) -> Self {
+        Self {
+            x: m.c0.into(),
+            y: m.c1.into(),
+            z: m.c2.into(),
+            w: m.c3.into(),
+        }
+    }
+}
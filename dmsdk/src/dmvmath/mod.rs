@@ -0,0 +1,1166 @@
+//! Vector math types mirroring Defold's `vmath` module.
+//!
+//! Matrices are column-major and transform column vectors, so `a * b` applies `b` first.
+//! Angles are in radians, except for [`Quat::from_euler()`], which takes degrees like `vmath.euler_to_quat()`.
+//!
+//! The `glam`, `mint` and `nalgebra` features add [`From`] conversions between these types and the
+//! equivalent `f32` types of those crates, so they can be passed straight to functions such as
+//! [`dmscript::push_vector3()`](crate::dmscript::push_vector3) and
+//! [`Instance::set_position()`](crate::dmgameobject::Instance::set_position).
+//!
+//! ```
+//! # #[cfg(feature = "glam")] {
+//! use dmsdk::*;
+//!
+//! // Called from Lua as `my_extension.reflect(direction, normal)`
+//! fn reflect(l: lua::State) -> i32 {
+//!     let direction: glam::Vec3 = dmscript::check_vector3(l, 1).into();
+//!     let normal: glam::Vec3 = dmscript::check_vector3(l, 2).into();
+//!     dmscript::push_vector3(l, direction - 2.0 * direction.dot(normal) * normal);
+//!     1
+//! }
+//! # }
+//! ```
+//!
+//! # Examples
+//! ```
+//! use dmsdk::dmvmath::*;
+//!
+//! let rotation = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2);
+//! let v = rotation.rotate(Vector3::new(1.0, 0.0, 0.0));
+//! assert!((v - Vector3::new(0.0, 1.0, 0.0)).length() < 1e-6);
+//!
+//! let world = Matrix4::from_translation(Vector3::new(0.0, 0.0, 5.0)) * Matrix4::from_quat(rotation);
+//! let p = world.transform_point(Point3::new(1.0, 0.0, 0.0));
+//! assert!((p - Point3::new(0.0, 1.0, 5.0)).length() < 1e-6);
+//! ```
+
+use dmsdk_ffi::{dmTransform, dmVMath};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+pub mod intersection;
+
+#[cfg(feature = "glam")]
+mod glam;
+#[cfg(feature = "mint")]
+mod mint;
+#[cfg(feature = "nalgebra")]
+mod nalgebra;
+
+/// Point in 3D space.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Point3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// Vector in 3D space.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Vector3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// Vector in 4D space.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Vector4 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+/// Quaternion.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Quat {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+/// 3x3 matrix, stored as columns.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Matrix3 {
+    pub c0: Vector3,
+    pub c1: Vector3,
+    pub c2: Vector3,
+}
+
+/// 4x4 matrix, stored as columns.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Matrix4 {
+    pub c0: Vector4,
+    pub c1: Vector4,
+    pub c2: Vector4,
+    pub c3: Vector4,
+}
+
+/// Translation, rotation and non-uniform scale, applied in the order scale, rotation, translation.
+///
+/// This is how the engine stores the transform of a game object.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub translation: Vector3,
+    pub rotation: Quat,
+    pub scale: Vector3,
+}
+
+// Arithmetic shared by vectors and quaternions //
+macro_rules! elementwise {
+    ($ty:ident { $($field:ident),+ }) => {
+        impl $ty {
+            /// Returns a value with every component set to `v`.
+            pub const fn splat(v: f32) -> Self {
+                Self { $($field: v),+ }
+            }
+
+            /// Returns the dot product of `self` and `other`.
+            pub fn dot(self, other: Self) -> f32 {
+                0.0 $(+ self.$field * other.$field)+
+            }
+
+            /// Returns the squared length, which is cheaper to compute than [`length()`](Self::length).
+            pub fn length_sqr(self) -> f32 {
+                self.dot(self)
+            }
+
+            /// Returns the length.
+            pub fn length(self) -> f32 {
+                self.length_sqr().sqrt()
+            }
+
+            /// Returns `self` scaled to a length of 1.
+            ///
+            /// Like `vmath.normalize()`, a zero length gives NaN components.
+            pub fn normalize(self) -> Self {
+                self / self.length()
+            }
+
+            /// Interpolates linearly from `self` at `t` = 0 to `other` at `t` = 1.
+            pub fn lerp(self, other: Self, t: f32) -> Self {
+                self + (other - self) * t
+            }
+
+            /// Multiplies each component of `self` with the same component of `other`.
+            pub fn mul_per_elem(self, other: Self) -> Self {
+                Self { $($field: self.$field * other.$field),+ }
+            }
+        }
+
+        impl Add for $ty {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl Sub for $ty {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl Mul<f32> for $ty {
+            type Output = Self;
+
+            fn mul(self, rhs: f32) -> Self {
+                Self { $($field: self.$field * rhs),+ }
+            }
+        }
+
+        impl Mul<$ty> for f32 {
+            type Output = $ty;
+
+            fn mul(self, rhs: $ty) -> $ty {
+                rhs * self
+            }
+        }
+
+        impl Div<f32> for $ty {
+            type Output = Self;
+
+            fn div(self, rhs: f32) -> Self {
+                Self { $($field: self.$field / rhs),+ }
+            }
+        }
+
+        impl Neg for $ty {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),+ }
+            }
+        }
+
+        impl AddAssign for $ty {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl SubAssign for $ty {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl MulAssign<f32> for $ty {
+            fn mul_assign(&mut self, rhs: f32) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl DivAssign<f32> for $ty {
+            fn div_assign(&mut self, rhs: f32) {
+                *self = *self / rhs;
+            }
+        }
+    };
+}
+
+elementwise!(Vector3 { x, y, z });
+elementwise!(Vector4 { x, y, z, w });
+elementwise!(Quat { x, y, z, w });
+
+// Same tolerance as the engine's Vectormath library, past which slerp falls back to lerp
+const SLERP_TOLERANCE: f32 = 0.999;
+
+fn slerp_scales(cos_angle: f32, t: f32) -> (f32, f32) {
+    if cos_angle < SLERP_TOLERANCE {
+        let angle = cos_angle.acos();
+        let recip_sin = 1.0 / angle.sin();
+        (
+            ((1.0 - t) * angle).sin() * recip_sin,
+            (t * angle).sin() * recip_sin,
+        )
+    } else {
+        (1.0 - t, t)
+    }
+}
+
+// Vector3 //
+impl Vector3 {
+    /// All zeros.
+    pub const ZERO: Self = Self::splat(0.0);
+    /// All ones.
+    pub const ONE: Self = Self::splat(1.0);
+    /// Unit vector along the X axis.
+    pub const X: Self = Self::new(1.0, 0.0, 0.0);
+    /// Unit vector along the Y axis.
+    pub const Y: Self = Self::new(0.0, 1.0, 0.0);
+    /// Unit vector along the Z axis.
+    pub const Z: Self = Self::new(0.0, 0.0, 1.0);
+
+    /// Creates a vector.
+    pub const fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Returns the cross product of `self` and `other`.
+    ///
+    /// # Examples
+    /// ```
+    /// use dmsdk::dmvmath::Vector3;
+    ///
+    /// assert_eq!(Vector3::X.cross(Vector3::Y), Vector3::Z);
+    /// ```
+    pub fn cross(self, other: Self) -> Self {
+        Self {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    /// Interpolates along the arc between the unit vectors `self` and `other`, like `vmath.slerp()`.
+    pub fn slerp(self, other: Self, t: f32) -> Self {
+        let (scale0, scale1) = slerp_scales(self.dot(other), t);
+        self * scale0 + other * scale1
+    }
+
+    /// Returns a [`Vector4`] with `self` as its first three components.
+    pub const fn extend(self, w: f32) -> Vector4 {
+        Vector4::new(self.x, self.y, self.z, w)
+    }
+}
+
+// Vector4 //
+impl Vector4 {
+    /// All zeros.
+    pub const ZERO: Self = Self::splat(0.0);
+    /// All ones.
+    pub const ONE: Self = Self::splat(1.0);
+
+    /// Creates a vector.
+    pub const fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+
+    /// Interpolates along the arc between the unit vectors `self` and `other`, like `vmath.slerp()`.
+    pub fn slerp(self, other: Self, t: f32) -> Self {
+        let (scale0, scale1) = slerp_scales(self.dot(other), t);
+        self * scale0 + other * scale1
+    }
+
+    /// Returns the first three components.
+    pub const fn truncate(self) -> Vector3 {
+        Vector3::new(self.x, self.y, self.z)
+    }
+}
+
+// Point3 //
+impl Point3 {
+    /// The origin.
+    pub const ORIGIN: Self = Self::new(0.0, 0.0, 0.0);
+
+    /// Creates a point.
+    pub const fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Returns the distance between `self` and `other`.
+    pub fn distance(self, other: Self) -> f32 {
+        (other - self).length()
+    }
+
+    /// Returns the squared distance between `self` and `other`.
+    pub fn distance_sqr(self, other: Self) -> f32 {
+        (other - self).length_sqr()
+    }
+
+    /// Interpolates linearly from `self` at `t` = 0 to `other` at `t` = 1.
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Add<Vector3> for Point3 {
+    type Output = Self;
+
+    fn add(self, rhs: Vector3) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub<Vector3> for Point3 {
+    type Output = Self;
+
+    fn sub(self, rhs: Vector3) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Sub for Point3 {
+    type Output = Vector3;
+
+    fn sub(self, rhs: Self) -> Vector3 {
+        Vector3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl AddAssign<Vector3> for Point3 {
+    fn add_assign(&mut self, rhs: Vector3) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign<Vector3> for Point3 {
+    fn sub_assign(&mut self, rhs: Vector3) {
+        *self = *self - rhs;
+    }
+}
+
+// Quat //
+impl Quat {
+    /// No rotation.
+    pub const IDENTITY: Self = Self::new(0.0, 0.0, 0.0, 1.0);
+
+    /// Creates a quaternion from its components.
+    pub const fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+
+    /// Rotation of `angle` radians around the unit vector `axis`, like `vmath.quat_axis_angle()`.
+    pub fn from_axis_angle(axis: Vector3, angle: f32) -> Self {
+        let (s, c) = (angle * 0.5).sin_cos();
+        let v = axis * s;
+        Self::new(v.x, v.y, v.z, c)
+    }
+
+    /// Rotation of `angle` radians around the X axis, like `vmath.quat_rotation_x()`.
+    pub fn from_rotation_x(angle: f32) -> Self {
+        Self::from_axis_angle(Vector3::X, angle)
+    }
+
+    /// Rotation of `angle` radians around the Y axis, like `vmath.quat_rotation_y()`.
+    pub fn from_rotation_y(angle: f32) -> Self {
+        Self::from_axis_angle(Vector3::Y, angle)
+    }
+
+    /// Rotation of `angle` radians around the Z axis, like `vmath.quat_rotation_z()`.
+    pub fn from_rotation_z(angle: f32) -> Self {
+        Self::from_axis_angle(Vector3::Z, angle)
+    }
+
+    /// Rotation from Euler angles in degrees, like `vmath.euler_to_quat()`.
+    ///
+    /// The rotation around X is applied first, then Z, then Y.
+    ///
+    /// # Examples
+    /// ```
+    /// use dmsdk::dmvmath::{Quat, Vector3};
+    ///
+    /// let q = Quat::from_euler(0.0, 0.0, 90.0);
+    /// assert!((q - Quat::from_rotation_z(std::f32::consts::FRAC_PI_2)).length() < 1e-6);
+    /// ```
+    pub fn from_euler(x: f32, y: f32, z: f32) -> Self {
+        Self::from_rotation_y(y.to_radians())
+            * Self::from_rotation_z(z.to_radians())
+            * Self::from_rotation_x(x.to_radians())
+    }
+
+    /// Shortest rotation from the unit vector `from` to the unit vector `to`, like `vmath.quat_from_to()`.
+    pub fn from_to(from: Vector3, to: Vector3) -> Self {
+        let cos_half_angle_x2 = (2.0 * (1.0 + from.dot(to))).sqrt();
+        let v = from.cross(to) / cos_half_angle_x2;
+        Self::new(v.x, v.y, v.z, cos_half_angle_x2 * 0.5)
+    }
+
+    /// Returns the conjugate, which for a unit quaternion is the inverse rotation, like `vmath.conj()`.
+    pub const fn conjugate(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    /// Rotates `v` by this unit quaternion, like `vmath.rotate()`.
+    pub fn rotate(self, v: Vector3) -> Vector3 {
+        let q = self;
+        let tmp_x = q.w * v.x + q.y * v.z - q.z * v.y;
+        let tmp_y = q.w * v.y + q.z * v.x - q.x * v.z;
+        let tmp_z = q.w * v.z + q.x * v.y - q.y * v.x;
+        let tmp_w = q.x * v.x + q.y * v.y + q.z * v.z;
+        Vector3::new(
+            tmp_w * q.x + tmp_x * q.w - tmp_y * q.z + tmp_z * q.y,
+            tmp_w * q.y + tmp_y * q.w - tmp_z * q.x + tmp_x * q.z,
+            tmp_w * q.z + tmp_z * q.w - tmp_x * q.y + tmp_y * q.x,
+        )
+    }
+
+    /// Interpolates along the shortest arc between the unit quaternions `self` and `other`, like `vmath.slerp()`.
+    pub fn slerp(self, other: Self, t: f32) -> Self {
+        let cos_angle = self.dot(other);
+        let (start, cos_angle) = if cos_angle < 0.0 {
+            (-self, -cos_angle)
+        } else {
+            (self, cos_angle)
+        };
+        let (scale0, scale1) = slerp_scales(cos_angle, t);
+        start * scale0 + other * scale1
+    }
+}
+
+impl Mul for Quat {
+    type Output = Self;
+
+    /// Combines two rotations, `rhs` being applied first.
+    fn mul(self, rhs: Self) -> Self {
+        let (a, b) = (self, rhs);
+        Self::new(
+            a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+            a.w * b.y + a.y * b.w + a.z * b.x - a.x * b.z,
+            a.w * b.z + a.z * b.w + a.x * b.y - a.y * b.x,
+            a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z,
+        )
+    }
+}
+
+impl MulAssign for Quat {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Mul<Vector3> for Quat {
+    type Output = Vector3;
+
+    /// Same as [`Quat::rotate()`].
+    fn mul(self, rhs: Vector3) -> Vector3 {
+        self.rotate(rhs)
+    }
+}
+
+// Matrix3 //
+impl Matrix3 {
+    /// The identity matrix.
+    pub const IDENTITY: Self = Self::from_cols(Vector3::X, Vector3::Y, Vector3::Z);
+
+    /// Creates a matrix from its columns.
+    pub const fn from_cols(c0: Vector3, c1: Vector3, c2: Vector3) -> Self {
+        Self { c0, c1, c2 }
+    }
+
+    /// Rotation matrix of the unit quaternion `q`.
+    pub fn from_quat(q: Quat) -> Self {
+        let (x2, y2, z2) = (q.x + q.x, q.y + q.y, q.z + q.z);
+        let (xx, xy, xz) = (q.x * x2, q.x * y2, q.x * z2);
+        let (yy, yz, zz) = (q.y * y2, q.y * z2, q.z * z2);
+        let (wx, wy, wz) = (q.w * x2, q.w * y2, q.w * z2);
+        Self::from_cols(
+            Vector3::new(1.0 - yy - zz, xy + wz, xz - wy),
+            Vector3::new(xy - wz, 1.0 - xx - zz, yz + wx),
+            Vector3::new(xz + wy, yz - wx, 1.0 - xx - yy),
+        )
+    }
+
+    /// Rotation of `angle` radians around the unit vector `axis`.
+    pub fn from_axis_angle(axis: Vector3, angle: f32) -> Self {
+        let (s, c) = angle.sin_cos();
+        let (x, y, z) = (axis.x, axis.y, axis.z);
+        let one_minus_c = 1.0 - c;
+        Self::from_cols(
+            Vector3::new(
+                x * x * one_minus_c + c,
+                x * y * one_minus_c + z * s,
+                z * x * one_minus_c - y * s,
+            ),
+            Vector3::new(
+                x * y * one_minus_c - z * s,
+                y * y * one_minus_c + c,
+                y * z * one_minus_c + x * s,
+            ),
+            Vector3::new(
+                z * x * one_minus_c + y * s,
+                y * z * one_minus_c - x * s,
+                z * z * one_minus_c + c,
+            ),
+        )
+    }
+
+    /// Scale matrix.
+    pub const fn from_scale(scale: Vector3) -> Self {
+        Self::from_cols(
+            Vector3::new(scale.x, 0.0, 0.0),
+            Vector3::new(0.0, scale.y, 0.0),
+            Vector3::new(0.0, 0.0, scale.z),
+        )
+    }
+
+    /// Returns the transpose.
+    pub const fn transpose(self) -> Self {
+        let Self { c0, c1, c2 } = self;
+        Self::from_cols(
+            Vector3::new(c0.x, c1.x, c2.x),
+            Vector3::new(c0.y, c1.y, c2.y),
+            Vector3::new(c0.z, c1.z, c2.z),
+        )
+    }
+
+    /// Returns the determinant.
+    pub fn determinant(self) -> f32 {
+        self.c2.dot(self.c0.cross(self.c1))
+    }
+
+    /// Returns the inverse. A singular matrix gives infinite or NaN components.
+    pub fn inverse(self) -> Self {
+        let tmp0 = self.c1.cross(self.c2);
+        let tmp1 = self.c2.cross(self.c0);
+        let tmp2 = self.c0.cross(self.c1);
+        let det_inv = 1.0 / self.c2.dot(tmp2);
+        Self::from_cols(
+            Vector3::new(tmp0.x, tmp1.x, tmp2.x) * det_inv,
+            Vector3::new(tmp0.y, tmp1.y, tmp2.y) * det_inv,
+            Vector3::new(tmp0.z, tmp1.z, tmp2.z) * det_inv,
+        )
+    }
+}
+
+impl Mul<Vector3> for Matrix3 {
+    type Output = Vector3;
+
+    fn mul(self, rhs: Vector3) -> Vector3 {
+        self.c0 * rhs.x + self.c1 * rhs.y + self.c2 * rhs.z
+    }
+}
+
+impl Mul for Matrix3 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::from_cols(self * rhs.c0, self * rhs.c1, self * rhs.c2)
+    }
+}
+
+impl MulAssign for Matrix3 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+// Matrix4 //
+impl Matrix4 {
+    /// The identity matrix.
+    pub const IDENTITY: Self = Self::from_cols(
+        Vector4::new(1.0, 0.0, 0.0, 0.0),
+        Vector4::new(0.0, 1.0, 0.0, 0.0),
+        Vector4::new(0.0, 0.0, 1.0, 0.0),
+        Vector4::new(0.0, 0.0, 0.0, 1.0),
+    );
+
+    /// Creates a matrix from its columns.
+    pub const fn from_cols(c0: Vector4, c1: Vector4, c2: Vector4, c3: Vector4) -> Self {
+        Self { c0, c1, c2, c3 }
+    }
+
+    /// Creates an affine matrix from its upper 3x3 part and a translation.
+    pub const fn from_matrix3_translation(m: Matrix3, translation: Vector3) -> Self {
+        Self::from_cols(
+            m.c0.extend(0.0),
+            m.c1.extend(0.0),
+            m.c2.extend(0.0),
+            translation.extend(1.0),
+        )
+    }
+
+    /// Translation matrix, like `vmath.matrix4_translation()`.
+    pub const fn from_translation(translation: Vector3) -> Self {
+        Self::from_matrix3_translation(Matrix3::IDENTITY, translation)
+    }
+
+    /// Scale matrix, like `vmath.matrix4_scale()`.
+    pub const fn from_scale(scale: Vector3) -> Self {
+        Self::from_matrix3_translation(Matrix3::from_scale(scale), Vector3::ZERO)
+    }
+
+    /// Rotation matrix of the unit quaternion `q`, like `vmath.matrix4_from_quat()`.
+    pub fn from_quat(q: Quat) -> Self {
+        Self::from_matrix3_translation(Matrix3::from_quat(q), Vector3::ZERO)
+    }
+
+    /// Rotation of `angle` radians around the unit vector `axis`, like `vmath.matrix4_axis_angle()`.
+    pub fn from_axis_angle(axis: Vector3, angle: f32) -> Self {
+        Self::from_matrix3_translation(Matrix3::from_axis_angle(axis, angle), Vector3::ZERO)
+    }
+
+    /// Rotation of `angle` radians around the X axis, like `vmath.matrix4_rotation_x()`.
+    pub fn from_rotation_x(angle: f32) -> Self {
+        Self::from_axis_angle(Vector3::X, angle)
+    }
+
+    /// Rotation of `angle` radians around the Y axis, like `vmath.matrix4_rotation_y()`.
+    pub fn from_rotation_y(angle: f32) -> Self {
+        Self::from_axis_angle(Vector3::Y, angle)
+    }
+
+    /// Rotation of `angle` radians around the Z axis, like `vmath.matrix4_rotation_z()`.
+    pub fn from_rotation_z(angle: f32) -> Self {
+        Self::from_axis_angle(Vector3::Z, angle)
+    }
+
+    /// Scales by `scale`, then rotates by `rotation`, then translates by `translation`.
+    pub fn from_scale_rotation_translation(
+        scale: Vector3,
+        rotation: Quat,
+        translation: Vector3,
+    ) -> Self {
+        let r = Matrix3::from_quat(rotation);
+        Self::from_matrix3_translation(
+            Matrix3::from_cols(r.c0 * scale.x, r.c1 * scale.y, r.c2 * scale.z),
+            translation,
+        )
+    }
+
+    /// View matrix of a camera at `eye` looking at `target`, like `vmath.matrix4_look_at()`.
+    ///
+    /// # Examples
+    /// ```
+    /// use dmsdk::dmvmath::*;
+    ///
+    /// let view = Matrix4::look_at(Point3::new(0.0, 0.0, 10.0), Point3::ORIGIN, Vector3::Y);
+    /// let p = view.transform_point(Point3::ORIGIN);
+    /// assert!((p - Point3::new(0.0, 0.0, -10.0)).length() < 1e-6);
+    /// ```
+    pub fn look_at(eye: Point3, target: Point3, up: Vector3) -> Self {
+        let z = (eye - target).normalize();
+        let x = up.normalize().cross(z).normalize();
+        let y = z.cross(x);
+        Self::from_matrix3_translation(Matrix3::from_cols(x, y, z), eye.into()).ortho_inverse()
+    }
+
+    /// Perspective projection, like `vmath.matrix4_perspective()`.
+    ///
+    /// `fov` is the vertical field of view in radians and `aspect` is width divided by height.
+    pub fn perspective(fov: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let f = (std::f32::consts::FRAC_PI_2 - 0.5 * fov).tan();
+        let range_inv = 1.0 / (near - far);
+        Self::from_cols(
+            Vector4::new(f / aspect, 0.0, 0.0, 0.0),
+            Vector4::new(0.0, f, 0.0, 0.0),
+            Vector4::new(0.0, 0.0, (near + far) * range_inv, -1.0),
+            Vector4::new(0.0, 0.0, near * far * range_inv * 2.0, 0.0),
+        )
+    }
+
+    /// Perspective projection of the frustum with the given planes, like `vmath.matrix4_frustum()`.
+    pub fn frustum(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Self {
+        let inv_rl = 1.0 / (right - left);
+        let inv_tb = 1.0 / (top - bottom);
+        let inv_nf = 1.0 / (near - far);
+        let near2 = near + near;
+        Self::from_cols(
+            Vector4::new(near2 * inv_rl, 0.0, 0.0, 0.0),
+            Vector4::new(0.0, near2 * inv_tb, 0.0, 0.0),
+            Vector4::new(
+                (right + left) * inv_rl,
+                (top + bottom) * inv_tb,
+                (near + far) * inv_nf,
+                -1.0,
+            ),
+            Vector4::new(0.0, 0.0, near2 * inv_nf * far, 0.0),
+        )
+    }
+
+    /// Orthographic projection, like `vmath.matrix4_orthographic()`.
+    pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Self {
+        let inv_rl = 1.0 / (right - left);
+        let inv_tb = 1.0 / (top - bottom);
+        let inv_nf = 1.0 / (near - far);
+        Self::from_cols(
+            Vector4::new(inv_rl * 2.0, 0.0, 0.0, 0.0),
+            Vector4::new(0.0, inv_tb * 2.0, 0.0, 0.0),
+            Vector4::new(0.0, 0.0, inv_nf * 2.0, 0.0),
+            Vector4::new(
+                -(right + left) * inv_rl,
+                -(top + bottom) * inv_tb,
+                (near + far) * inv_nf,
+                1.0,
+            ),
+        )
+    }
+
+    /// Returns the upper 3x3 part.
+    pub const fn matrix3(self) -> Matrix3 {
+        Matrix3::from_cols(self.c0.truncate(), self.c1.truncate(), self.c2.truncate())
+    }
+
+    /// Returns the translation part.
+    pub const fn translation(self) -> Vector3 {
+        self.c3.truncate()
+    }
+
+    /// Returns the transpose.
+    pub const fn transpose(self) -> Self {
+        let Self { c0, c1, c2, c3 } = self;
+        Self::from_cols(
+            Vector4::new(c0.x, c1.x, c2.x, c3.x),
+            Vector4::new(c0.y, c1.y, c2.y, c3.y),
+            Vector4::new(c0.z, c1.z, c2.z, c3.z),
+            Vector4::new(c0.w, c1.w, c2.w, c3.w),
+        )
+    }
+
+    /// Returns the inverse, like `vmath.inv()`. A singular matrix gives infinite or NaN components.
+    ///
+    /// # Examples
+    /// ```
+    /// use dmsdk::dmvmath::*;
+    ///
+    /// let m = Matrix4::perspective(1.0, 16.0 / 9.0, 0.1, 100.0) * Matrix4::from_translation(Vector3::new(1.0, 2.0, 3.0));
+    /// let v = Vector4::new(4.0, 5.0, 6.0, 1.0);
+    /// assert!((m.inverse() * (m * v) - v).length() < 1e-4);
+    /// ```
+    pub fn inverse(self) -> Self {
+        let [a, b, c, d] = [self.c0, self.c1, self.c2, self.c3];
+
+        // 2x2 minors of the top two and bottom two rows
+        let s0 = a.x * b.y - b.x * a.y;
+        let s1 = a.x * c.y - c.x * a.y;
+        let s2 = a.x * d.y - d.x * a.y;
+        let s3 = b.x * c.y - c.x * b.y;
+        let s4 = b.x * d.y - d.x * b.y;
+        let s5 = c.x * d.y - d.x * c.y;
+        let c5 = c.z * d.w - d.z * c.w;
+        let c4 = b.z * d.w - d.z * b.w;
+        let c3 = b.z * c.w - c.z * b.w;
+        let c2 = a.z * d.w - d.z * a.w;
+        let c1 = a.z * c.w - c.z * a.w;
+        let c0 = a.z * b.w - b.z * a.w;
+
+        let det_inv = 1.0 / (s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0);
+
+        Self::from_cols(
+            Vector4::new(
+                b.y * c5 - c.y * c4 + d.y * c3,
+                -a.y * c5 + c.y * c2 - d.y * c1,
+                a.y * c4 - b.y * c2 + d.y * c0,
+                -a.y * c3 + b.y * c1 - c.y * c0,
+            ),
+            Vector4::new(
+                -b.x * c5 + c.x * c4 - d.x * c3,
+                a.x * c5 - c.x * c2 + d.x * c1,
+                -a.x * c4 + b.x * c2 - d.x * c0,
+                a.x * c3 - b.x * c1 + c.x * c0,
+            ),
+            Vector4::new(
+                b.w * s5 - c.w * s4 + d.w * s3,
+                -a.w * s5 + c.w * s2 - d.w * s1,
+                a.w * s4 - b.w * s2 + d.w * s0,
+                -a.w * s3 + b.w * s1 - c.w * s0,
+            ),
+            Vector4::new(
+                -b.z * s5 + c.z * s4 - d.z * s3,
+                a.z * s5 - c.z * s2 + d.z * s1,
+                -a.z * s4 + b.z * s2 - d.z * s0,
+                a.z * s3 - b.z * s1 + c.z * s0,
+            ),
+        ) * det_inv
+    }
+
+    /// Returns the inverse of a matrix made only of rotation and translation, like `vmath.ortho_inv()`.
+    ///
+    /// This is cheaper than [`inverse()`](Self::inverse), but wrong for any other matrix.
+    pub fn ortho_inverse(self) -> Self {
+        let r = self.matrix3().transpose();
+        Self::from_matrix3_translation(r, -(r * self.translation()))
+    }
+
+    /// Transforms `p`, including the translation.
+    pub fn transform_point(self, p: Point3) -> Point3 {
+        (self * Vector3::from(p).extend(1.0)).truncate().into()
+    }
+
+    /// Transforms `v`, ignoring the translation.
+    pub fn transform_vector(self, v: Vector3) -> Vector3 {
+        (self * v.extend(0.0)).truncate()
+    }
+}
+
+impl Mul<f32> for Matrix4 {
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self {
+        Self::from_cols(self.c0 * rhs, self.c1 * rhs, self.c2 * rhs, self.c3 * rhs)
+    }
+}
+
+impl Mul<Vector4> for Matrix4 {
+    type Output = Vector4;
+
+    fn mul(self, rhs: Vector4) -> Vector4 {
+        self.c0 * rhs.x + self.c1 * rhs.y + self.c2 * rhs.z + self.c3 * rhs.w
+    }
+}
+
+impl Mul for Matrix4 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::from_cols(self * rhs.c0, self * rhs.c1, self * rhs.c2, self * rhs.c3)
+    }
+}
+
+impl MulAssign for Matrix4 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+// Transform //
+impl Transform {
+    /// No translation, rotation or scale.
+    pub const IDENTITY: Self = Self::new(Vector3::ZERO, Quat::IDENTITY, Vector3::ONE);
+
+    /// Creates a transform.
+    pub const fn new(translation: Vector3, rotation: Quat, scale: Vector3) -> Self {
+        Self {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    /// Applies the transform to `p`.
+    pub fn apply(self, p: Point3) -> Point3 {
+        Point3::from(self.translation)
+            + self
+                .rotation
+                .rotate(Vector3::from(p).mul_per_elem(self.scale))
+    }
+
+    /// Returns the inverse transform.
+    ///
+    /// Like the engine's, this is only exact for uniform scale.
+    pub fn inverse(self) -> Self {
+        let scale = Vector3::new(1.0 / self.scale.x, 1.0 / self.scale.y, 1.0 / self.scale.z);
+        let rotation = self.rotation.conjugate();
+        let translation = rotation.rotate(-self.translation).mul_per_elem(scale);
+        Self::new(translation, rotation, scale)
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Mul for Transform {
+    type Output = Self;
+
+    /// Combines two transforms, `rhs` being applied first, the same way the engine combines a child
+    /// with its parent.
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.translation
+                + self
+                    .rotation
+                    .rotate(rhs.translation.mul_per_elem(self.scale)),
+            self.rotation * rhs.rotation,
+            self.scale.mul_per_elem(rhs.scale),
+        )
+    }
+}
+
+impl From<Transform> for Matrix4 {
+    fn from(t: Transform) -> Self {
+        Self::from_scale_rotation_translation(t.scale, t.rotation, t.translation)
+    }
+}
+
+// Vector3 <-> Point3 //
+impl From<Vector3> for Point3 {
+    fn from(v: Vector3) -> Self {
+        Self {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+        }
+    }
+}
+
+impl From<Point3> for Vector3 {
+    fn from(p: Point3) -> Self {
+        Self {
+            x: p.x,
+            y: p.y,
+            z: p.z,
+        }
+    }
+}
+
+// Arrays <-> Rust //
+impl From<[f32; 3]> for Vector3 {
+    fn from([x, y, z]: [f32; 3]) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl From<Vector3> for [f32; 3] {
+    fn from(v: Vector3) -> Self {
+        [v.x, v.y, v.z]
+    }
+}
+
+impl From<[f32; 3]> for Point3 {
+    fn from([x, y, z]: [f32; 3]) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl From<Point3> for [f32; 3] {
+    fn from(p: Point3) -> Self {
+        [p.x, p.y, p.z]
+    }
+}
+
+impl From<[f32; 4]> for Vector4 {
+    fn from([x, y, z, w]: [f32; 4]) -> Self {
+        Self { x, y, z, w }
+    }
+}
+
+impl From<Vector4> for [f32; 4] {
+    fn from(v: Vector4) -> Self {
+        [v.x, v.y, v.z, v.w]
+    }
+}
+
+// Defold <-> Rust //
+impl From<dmVMath::Point3> for Point3 {
+    fn from(p: dmVMath::Point3) -> Self {
+        Self {
+            x: p.mX,
+            y: p.mY,
+            z: p.mZ,
+        }
+    }
+}
+
+#[cfg(target_env = "gnu")]
+impl From<Point3> for dmVMath::Point3 {
+    fn from(p: Point3) -> Self {
+        Self {
+            mX: p.x,
+            mY: p.y,
+            mZ: p.z,
+        }
+    }
+}
+
+#[cfg(not(target_env = "gnu"))]
+impl From<Point3> for dmVMath::Point3 {
+    fn from(p: Point3) -> Self {
+        Self {
+            mX: p.x,
+            mY: p.y,
+            mZ: p.z,
+            d: 0.0,
+        }
+    }
+}
+
+impl From<dmVMath::Vector3> for Vector3 {
+    fn from(v: dmVMath::Vector3) -> Self {
+        Self {
+            x: v.mX,
+            y: v.mY,
+            z: v.mZ,
+        }
+    }
+}
+
+#[cfg(target_env = "gnu")]
+impl From<Vector3> for dmVMath::Vector3 {
+    fn from(v: Vector3) -> Self {
+        Self {
+            mX: v.x,
+            mY: v.y,
+            mZ: v.z,
+        }
+    }
+}
+
+#[cfg(not(target_env = "gnu"))]
+impl From<Vector3> for dmVMath::Vector3 {
+    fn from(v: Vector3) -> Self {
+        Self {
+            mX: v.x,
+            mY: v.y,
+            mZ: v.z,
+            d: 0.0,
+        }
+    }
+}
+
+impl From<dmVMath::Vector4> for Vector4 {
+    fn from(v: dmVMath::Vector4) -> Self {
+        Self {
+            x: v.mX,
+            y: v.mY,
+            z: v.mZ,
+            w: v.mW,
+        }
+    }
+}
+
+impl From<Vector4> for dmVMath::Vector4 {
+    fn from(v: Vector4) -> Self {
+        Self {
+            mX: v.x,
+            mY: v.y,
+            mZ: v.z,
+            mW: v.w,
+        }
+    }
+}
+
+impl From<dmVMath::Quat> for Quat {
+    fn from(q: dmVMath::Quat) -> Self {
+        Self {
+            x: q.mX,
+            y: q.mY,
+            z: q.mZ,
+            w: q.mW,
+        }
+    }
+}
+
+impl From<Quat> for dmVMath::Quat {
+    fn from(q: Quat) -> Self {
+        Self {
+            mX: q.x,
+            mY: q.y,
+            mZ: q.z,
+            mW: q.w,
+        }
+    }
+}
+
+impl From<dmVMath::Matrix3> for Matrix3 {
+    fn from(m: dmVMath::Matrix3) -> Self {
+        Self::from_cols(m.mCol0.into(), m.mCol1.into(), m.mCol2.into())
+    }
+}
+
+impl From<Matrix3> for dmVMath::Matrix3 {
+    fn from(m: Matrix3) -> Self {
+        Self {
+            mCol0: m.c0.into(),
+            mCol1: m.c1.into(),
+            mCol2: m.c2.into(),
+        }
+    }
+}
+
+impl From<dmVMath::Matrix4> for Matrix4 {
+    fn from(m: dmVMath::Matrix4) -> Self {
+        Self::from_cols(
+            m.mCol0.into(),
+            m.mCol1.into(),
+            m.mCol2.into(),
+            m.mCol3.into(),
+        )
+    }
+}
+
+impl From<Matrix4> for dmVMath::Matrix4 {
+    fn from(m: Matrix4) -> Self {
+        Self {
+            mCol0: m.c0.into(),
+            mCol1: m.c1.into(),
+            mCol2: m.c2.into(),
+            mCol3: m.c3.into(),
+        }
+    }
+}
+
+impl From<dmTransform::Transform> for Transform {
+    fn from(t: dmTransform::Transform) -> Self {
+        Self::new(
+            t.m_Translation.into(),
+            t.m_Rotation.into(),
+            t.m_Scale.into(),
+        )
+    }
+}
+
+impl From<Transform> for dmTransform::Transform {
+    fn from(t: Transform) -> Self {
+        Self {
+            m_Rotation: t.rotation.into(),
+            m_Translation: t.translation.into(),
+            m_Scale: t.scale.into(),
+        }
+    }
+}
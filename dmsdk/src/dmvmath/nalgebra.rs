@@ -0,0 +1,85 @@
+//! Conversions to and from [`nalgebra`](::nalgebra) types.
+
+use super::{Matrix3, Matrix4, Point3, Quat, Vector3, Vector4};
+
+impl From<::nalgebra::Vector3<f32>> for Vector3 {
+    fn from(v: ::nalgebra::Vector3<f32>) -> Self {
+        Self::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<Vector3> for ::nalgebra::Vector3<f32> {
+    fn from(v: Vector3) -> Self {
+        Self::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<::nalgebra::Point3<f32>> for Point3 {
+    fn from(p: ::nalgebra::Point3<f32>) -> Self {
+        Self::new(p.x, p.y, p.z)
+    }
+}
+
+impl From<Point3> for ::nalgebra::Point3<f32> {
+    fn from(p: Point3) -> Self {
+        Self::new(p.x, p.y, p.z)
+    }
+}
+
+impl From<::nalgebra::Vector4<f32>> for Vector4 {
+    fn from(v: ::nalgebra::Vector4<f32>) -> Self {
+        Self::new(v.x, v.y, v.z, v.w)
+    }
+}
+
+impl From<Vector4> for ::nalgebra::Vector4<f32> {
+    fn from(v: Vector4) -> Self {
+        Self::new(v.x, v.y, v.z, v.w)
+    }
+}
+
+impl From<::nalgebra::Quaternion<f32>> for Quat {
+    fn from(q: ::nalgebra::Quaternion<f32>) -> Self {
+        Self::new(q.i, q.j, q.k, q.w)
+    }
+}
+
+impl From<Quat> for ::nalgebra::Quaternion<f32> {
+    fn from(q: Quat) -> Self {
+        Self::new(q.w, q.x, q.y, q.z)
+    }
+}
+
+/// Going the other way needs [`UnitQuaternion::new_normalize()`](::nalgebra::UnitQuaternion::new_normalize)
+/// or [`Unit::new_unchecked()`](::nalgebra::Unit::new_unchecked), since a [`Quat`] may not be normalized.
+impl From<::nalgebra::UnitQuaternion<f32>> for Quat {
+    fn from(q: ::nalgebra::UnitQuaternion<f32>) -> Self {
+        q.into_inner().into()
+    }
+}
+
+impl From<::nalgebra::Matrix3<f32>> for Matrix3 {
+    fn from(m: ::nalgebra::Matrix3<f32>) -> Self {
+        let [c0, c1, c2]: [[f32; 3]; 3] = m.into();
+        Self::from_cols(c0.into(), c1.into(), c2.into())
+    }
+}
+
+impl From<Matrix3> for ::nalgebra::Matrix3<f32> {
+    fn from(m: Matrix3) -> Self {
+        Self::from([m.c0.into(), m.c1.into(), m.c2.into()])
+    }
+}
+
+impl From<::nalgebra::Matrix4<f32>> for Matrix4 {
+    fn from(m: ::nalgebra::Matrix4<f32>) -> Self {
+        let [c0, c1, c2, c3]: [[f32; 4]; 4] = m.into();
+        Self::from_cols(c0.into(), c1.into(), c2.into(), c3.into())
+    }
+}
+
+impl From<Matrix4> for ::nalgebra::Matrix4<f32> {
+    fn from(m: Matrix4) -> Self {
+        Self::from([m.c0.into(), m.c1.into(), m.c2.into(), m.c3.into()])
+    }
+}
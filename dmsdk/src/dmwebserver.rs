@@ -1,6 +1,420 @@
 //! Web server module.
+//!
+//! Handlers are added to the engine's built-in web server, the same one used for `/ping`, `/redirect` and the
+//! other engine service endpoints, so they're reachable at the engine service port.
 
 use dmsdk_ffi::dmWebServer;
+use libc::c_void;
+use std::{
+    ffi::{CStr, CString},
+    fmt,
+    io::{self, Read, Write},
+    panic::{catch_unwind, AssertUnwindSafe},
+};
 
-/// Web server pointer.
-pub type Server = dmWebServer::HServer;
+#[doc(hidden)]
+pub type RawServer = dmWebServer::HServer;
+#[doc(hidden)]
+pub type RawRequest = *mut dmWebServer::Request;
+
+/// Web server error.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    SocketError,
+    InvalidRequest,
+    InvalidArgument,
+    HandlerAlreadyRegistered,
+    HandlerNotRegistered,
+    InternalError,
+    Unknown,
+}
+
+impl From<i32> for Error {
+    fn from(x: i32) -> Self {
+        match x {
+            dmWebServer::Result_RESULT_SOCKET_ERROR => Self::SocketError,
+            dmWebServer::Result_RESULT_INVALID_REQUEST => Self::InvalidRequest,
+            dmWebServer::Result_RESULT_ERROR_INVAL => Self::InvalidArgument,
+            dmWebServer::Result_RESULT_HANDLER_ALREADY_REGISTRED => Self::HandlerAlreadyRegistered,
+            dmWebServer::Result_RESULT_HANDLER_NOT_REGISTRED => Self::HandlerNotRegistered,
+            dmWebServer::Result_RESULT_INTERNAL_ERROR => Self::InternalError,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::SocketError => "socket error",
+            Self::InvalidRequest => "invalid request",
+            Self::InvalidArgument => "invalid argument",
+            Self::HandlerAlreadyRegistered => "a handler is already registered for this path",
+            Self::HandlerNotRegistered => "no handler is registered for this path",
+            Self::InternalError => "internal web server error",
+            Self::Unknown => "unknown web server error",
+        })
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        io::Error::other(err)
+    }
+}
+
+/// [`Result`](core::result::Result) alias with an error type of [`Error`].
+pub type Result<T> = core::result::Result<T, Error>;
+
+fn check(result: dmWebServer::Result) -> Result<()> {
+    if result == dmWebServer::Result_RESULT_OK {
+        Ok(())
+    } else {
+        Err(result.into())
+    }
+}
+
+/// Web server handle.
+///
+/// You probably want [`AppParams::web_server()`](crate::dmextension::AppParams::web_server()) to get one.
+#[derive(Debug, Clone, Copy)]
+pub struct Server {
+    ptr: RawServer,
+}
+
+impl From<RawServer> for Server {
+    fn from(ptr: RawServer) -> Self {
+        Self { ptr }
+    }
+}
+
+impl From<Server> for RawServer {
+    fn from(server: Server) -> Self {
+        server.ptr
+    }
+}
+
+impl Server {
+    /// Adds a handler for every request whose path starts with `prefix`.
+    ///
+    /// The handler is called on the main thread and stays registered until the returned [`Handler`] is dropped.
+    /// A panicking handler responds with status 500. A [`Response::stream()`] body that panics ends the response
+    /// where it stopped.
+    ///
+    /// # Examples
+    /// ```
+    /// use dmsdk::*;
+    /// use dmwebserver::{Handler, Response};
+    ///
+    /// fn add_debug_endpoint(params: dmextension::AppParams) -> dmwebserver::Result<Handler> {
+    ///     let mut hits = 0;
+    ///     params.web_server().add_handler("/debug/hits", move |request| {
+    ///         hits += 1;
+    ///         match request.method() {
+    ///             "GET" => Response::text(format!("{hits}")),
+    ///             _ => Response::new(405),
+    ///         }
+    ///     })
+    /// }
+    /// ```
+    pub fn add_handler<F>(&self, prefix: &str, handler: F) -> Result<Handler>
+    where
+        F: FnMut(&mut Request) -> Response + 'static,
+    {
+        let prefix = CString::new(prefix).map_err(|_| Error::InvalidArgument)?;
+        let user_data = Box::into_raw(Box::new(handler)) as *mut c_void;
+        let params = dmWebServer::HandlerParams {
+            m_Userdata: user_data,
+            m_Handler: Some(dispatch::<F>),
+        };
+
+        let result = check(unsafe { dmWebServer::AddHandler(self.ptr, prefix.as_ptr(), &params) });
+        let handler = Handler {
+            server: self.ptr,
+            prefix,
+            user_data,
+            drop_fn: drop_handler::<F>,
+            registered: result.is_ok(),
+        };
+        result.map(|_| handler)
+    }
+}
+
+unsafe extern "C" fn dispatch<F>(user_data: *mut c_void, request: RawRequest)
+where
+    F: FnMut(&mut Request) -> Response + 'static,
+{
+    let handler = &mut *(user_data as *mut F);
+    let mut request = Request::new(request);
+
+    let response = catch_unwind(AssertUnwindSafe(|| handler(&mut request)))
+        .unwrap_or_else(|_| Response::new(500).body("Internal server error"));
+    // There's nobody left to report a failed send to, and a panicking stream body must not unwind into the engine.
+    // The status and headers are already sent by then, so the client only sees a truncated body.
+    let _ = catch_unwind(AssertUnwindSafe(|| response.send(&mut request)));
+}
+
+unsafe fn drop_handler<F>(user_data: *mut c_void) {
+    drop(Box::from_raw(user_data as *mut F));
+}
+
+/// Handler added with [`Server::add_handler()`]. The handler is removed when this is dropped.
+#[must_use = "the handler is removed as soon as this is dropped"]
+pub struct Handler {
+    server: RawServer,
+    prefix: CString,
+    user_data: *mut c_void,
+    drop_fn: unsafe fn(*mut c_void),
+    registered: bool,
+}
+
+impl Handler {
+    /// Returns the path prefix this handler was added for.
+    pub fn prefix(&self) -> &str {
+        self.prefix.to_str().unwrap_or_default()
+    }
+}
+
+impl fmt::Debug for Handler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Handler").field(&self.prefix).finish()
+    }
+}
+
+impl Drop for Handler {
+    fn drop(&mut self) {
+        unsafe {
+            if self.registered {
+                dmWebServer::RemoveHandler(self.server, self.prefix.as_ptr());
+            }
+            (self.drop_fn)(self.user_data);
+        }
+    }
+}
+
+/// Incoming request passed to a handler.
+///
+/// The request body can be read with the [`Read`] implementation.
+pub struct Request {
+    ptr: RawRequest,
+    method: String,
+    path: String,
+}
+
+impl Request {
+    unsafe fn new(ptr: RawRequest) -> Self {
+        let to_string = |s: *const libc::c_char| {
+            if s.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(s).to_string_lossy().into_owned()
+            }
+        };
+
+        Self {
+            method: to_string((*ptr).m_Method),
+            path: to_string((*ptr).m_Resource),
+            ptr,
+        }
+    }
+
+    /// Returns the request method, e.g. `"GET"`.
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// Returns the requested path, including the handler's prefix and any query string.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the value of the `Content-Length` header, or 0 if there wasn't one.
+    pub fn content_length(&self) -> u32 {
+        unsafe { (*self.ptr).m_ContentLength }
+    }
+
+    /// Returns the value of the given header, if the request has it.
+    pub fn header(&self, name: &str) -> Option<String> {
+        let name = CString::new(name).ok()?;
+        let value = unsafe { dmWebServer::GetHeader(self.ptr, name.as_ptr()) };
+        if value.is_null() {
+            None
+        } else {
+            Some(
+                unsafe { CStr::from_ptr(value) }
+                    .to_string_lossy()
+                    .into_owned(),
+            )
+        }
+    }
+
+    /// Reads the whole request body.
+    pub fn read_body(&mut self) -> io::Result<Vec<u8>> {
+        let mut body = Vec::with_capacity(self.content_length() as usize);
+        self.read_to_end(&mut body)?;
+        Ok(body)
+    }
+}
+
+impl Read for Request {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut received = 0;
+        let len = buf.len().min(u32::MAX as usize) as u32;
+        check(unsafe {
+            dmWebServer::Receive(
+                self.ptr,
+                buf.as_mut_ptr() as *mut c_void,
+                len,
+                &mut received,
+            )
+        })?;
+        Ok(received as usize)
+    }
+}
+
+impl fmt::Debug for Request {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Request")
+            .field("method", &self.method)
+            .field("path", &self.path)
+            .field("content_length", &self.content_length())
+            .finish()
+    }
+}
+
+/// Writer for a streamed response body, see [`Response::stream()`].
+pub struct BodyWriter<'a> {
+    request: &'a mut Request,
+}
+
+impl Write for BodyWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(u32::MAX as usize);
+        check(unsafe {
+            dmWebServer::Send(self.request.ptr, buf.as_ptr() as *const c_void, len as u32)
+        })?;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+type StreamFn = Box<dyn FnOnce(&mut BodyWriter) -> io::Result<()>>;
+
+enum Body {
+    Bytes(Vec<u8>),
+    Stream(StreamFn),
+}
+
+/// Response returned by a handler.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+/// use dmwebserver::Response;
+/// use std::io::Write;
+///
+/// fn json() -> Response {
+///     Response::new(200)
+///         .header("Content-Type", "application/json")
+///         .body(r#"{"ok":true}"#)
+/// }
+///
+/// fn streamed() -> Response {
+///     Response::new(200).stream(|writer| {
+///         for i in 0..100 {
+///             writeln!(writer, "line {i}")?;
+///         }
+///         Ok(())
+///     })
+/// }
+/// ```
+pub struct Response {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Body,
+}
+
+impl Response {
+    /// Creates an empty response with the given status code.
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Body::Bytes(Vec::new()),
+        }
+    }
+
+    /// Creates a `200 OK` response with a plain text body.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::new(200)
+            .header("Content-Type", "text/plain; charset=utf-8")
+            .body(text.into())
+    }
+
+    /// Creates a `404 Not Found` response.
+    pub fn not_found() -> Self {
+        Self::new(404).body("Not found")
+    }
+
+    /// Returns the status code of this response.
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// Adds a header to this response.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the body of this response.
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = Body::Bytes(body.into());
+        self
+    }
+
+    /// Sets the body of this response to whatever `f` writes, sent to the client as it's written.
+    pub fn stream<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut BodyWriter) -> io::Result<()> + 'static,
+    {
+        self.body = Body::Stream(Box::new(f));
+        self
+    }
+
+    fn send(self, request: &mut Request) -> io::Result<()> {
+        check(unsafe { dmWebServer::SetStatusCode(request.ptr, self.status.into()) })?;
+
+        for (name, value) in &self.headers {
+            let (Ok(name), Ok(value)) = (CString::new(name.as_str()), CString::new(value.as_str()))
+            else {
+                continue;
+            };
+            check(unsafe {
+                dmWebServer::SendAttribute(request.ptr, name.as_ptr(), value.as_ptr())
+            })?;
+        }
+
+        let mut writer = BodyWriter { request };
+        match self.body {
+            Body::Bytes(bytes) if bytes.is_empty() => Ok(()),
+            Body::Bytes(bytes) => writer.write_all(&bytes),
+            Body::Stream(f) => f(&mut writer),
+        }
+    }
+}
+
+impl fmt::Debug for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
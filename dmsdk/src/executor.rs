@@ -0,0 +1,368 @@
+//! Single-threaded async executor polled once per frame. Requires the `async` feature.
+//!
+//! Futures started with [`spawn()`] run on the main thread, so they can use Lua and game objects freely.
+//! Every frame, right after the jobs queued with [`mainthread::run_on_main()`](crate::mainthread::run_on_main),
+//! each task that was woken since the last frame is polled once.
+//!
+//! # Examples
+//! ```
+//! use dmsdk::*;
+//! use std::time::Duration;
+//!
+//! fn start_countdown() {
+//!     executor::spawn(async {
+//!         for i in (1..=3).rev() {
+//!             println!("{i}...");
+//!             executor::sleep(Duration::from_secs(1)).await;
+//!         }
+//!         println!("Go!");
+//!     });
+//! }
+//! ```
+
+use crate::{
+    dmlog,
+    dmscript::LuaCallback,
+    dmsocket::{self, AsRawSocket, Selector, SelectorKind},
+    dmtime, lua,
+};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fmt,
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    task::{Context, Poll, Wake, Waker},
+    time::Duration,
+};
+
+struct TaskWaker {
+    id: usize,
+    queued: AtomicBool,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        if !self.queued.swap(true, Ordering::AcqRel) {
+            READY
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(self.id);
+        }
+    }
+}
+
+struct Task {
+    future: Pin<Box<dyn Future<Output = ()>>>,
+    waker: Arc<TaskWaker>,
+}
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+// Wakers can be sent to other threads, so woken tasks are collected here
+static READY: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+thread_local! {
+    static TASKS: RefCell<HashMap<usize, Task>> = RefCell::new(HashMap::new());
+    static NEXT_FRAME: RefCell<Vec<Waker>> = const { RefCell::new(Vec::new()) };
+    static FRAME: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Starts running `future` on the main thread and returns a handle to await its output.
+///
+/// The task keeps running if the [`JoinHandle`] is dropped. Must be called on the main thread.
+pub fn spawn<F: Future + 'static>(future: F) -> JoinHandle<F::Output> {
+    let shared = Rc::new(RefCell::new(JoinState {
+        output: None,
+        waker: None,
+    }));
+
+    let state = Rc::clone(&shared);
+    let task = Task {
+        future: Box::pin(async move {
+            let output = future.await;
+            let mut state = state.borrow_mut();
+            state.output = Some(output);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        }),
+        waker: Arc::new(TaskWaker {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            queued: AtomicBool::new(false),
+        }),
+    };
+
+    let waker = Arc::clone(&task.waker);
+    TASKS.with(|tasks| tasks.borrow_mut().insert(waker.id, task));
+    waker.wake_by_ref();
+
+    JoinHandle { shared }
+}
+
+/// Starts running `future` and calls `callback` with the values `push` pushes once it completes.
+///
+/// `push` returns how many values it pushed, see [`LuaCallback::call()`].
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+/// use std::time::Duration;
+///
+/// // Called from Lua as `my_extension.delay(seconds, function(self, seconds) ... end)`
+/// fn delay(l: lua::State) -> i32 {
+///     let seconds = lua::check_int(l, 1);
+///     let callback = dmscript::LuaCallback::new(l, 2);
+///
+///     executor::spawn_lua(
+///         callback,
+///         async move {
+///             executor::sleep(Duration::from_secs(seconds as u64)).await;
+///             seconds
+///         },
+///         |l, seconds| {
+///             lua::push_integer(l, seconds);
+///             1
+///         },
+///     );
+///
+///     0
+/// }
+/// ```
+pub fn spawn_lua<F, P>(callback: LuaCallback, future: F, push: P)
+where
+    F: Future + 'static,
+    P: FnOnce(lua::State, F::Output) -> i32 + 'static,
+{
+    spawn(async move {
+        let output = future.await;
+        callback.call(|l| push(l, output));
+    });
+}
+
+/// Polls every task that was woken since the last call, and returns how many were polled.
+///
+/// This is called automatically once per frame by extensions declared with
+/// [`declare_extension!`](crate::declare_extension), so it only needs to be called by hand without one.
+/// Must be called on the main thread.
+pub fn poll() -> usize {
+    FRAME.with(|frame| frame.set(frame.get() + 1));
+    for waker in NEXT_FRAME.with(|wakers| std::mem::take(&mut *wakers.borrow_mut())) {
+        waker.wake();
+    }
+
+    // Tasks woken while polling wait for the next frame
+    let ready = std::mem::take(&mut *READY.lock().unwrap_or_else(PoisonError::into_inner));
+    let mut count = 0;
+    for id in ready {
+        // Tasks are taken out while polled, so they can spawn others
+        let Some(mut task) = TASKS.with(|tasks| tasks.borrow_mut().remove(&id)) else {
+            continue;
+        };
+        task.waker.queued.store(false, Ordering::Release);
+
+        let waker = Waker::from(Arc::clone(&task.waker));
+        let mut cx = Context::from_waker(&waker);
+        count += 1;
+        match panic::catch_unwind(AssertUnwindSafe(|| task.future.as_mut().poll(&mut cx))) {
+            Ok(Poll::Pending) => {
+                TASKS.with(|tasks| tasks.borrow_mut().insert(id, task));
+            }
+            Ok(Poll::Ready(())) => {}
+            Err(_) => {
+                dmlog::error!("async task panicked");
+            }
+        }
+    }
+
+    count
+}
+
+struct JoinState<T> {
+    output: Option<T>,
+    waker: Option<Waker>,
+}
+
+/// Awaits the output of a task started with [`spawn()`].
+pub struct JoinHandle<T> {
+    shared: Rc<RefCell<JoinState<T>>>,
+}
+
+impl<T> JoinHandle<T> {
+    /// Returns `true` if the task has finished and its output hasn't been taken yet.
+    pub fn is_finished(&self) -> bool {
+        self.shared.borrow().output.is_some()
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.shared.borrow_mut();
+        match state.output.take() {
+            Some(output) => Poll::Ready(output),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for JoinHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinHandle")
+            .field("finished", &self.is_finished())
+            .finish()
+    }
+}
+
+fn wake_next_frame(cx: &Context<'_>) {
+    NEXT_FRAME.with(|wakers| wakers.borrow_mut().push(cx.waker().clone()));
+}
+
+/// Future returned by [`next_frame()`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless awaited"]
+pub struct NextFrame {
+    frame: Option<u64>,
+}
+
+impl Future for NextFrame {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let current = FRAME.with(Cell::get);
+        match self.frame {
+            Some(frame) if current > frame => Poll::Ready(()),
+            _ => {
+                self.frame.get_or_insert(current);
+                wake_next_frame(cx);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Completes on the next frame.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// async fn fade_in(mut set_alpha: impl FnMut(f32)) {
+///     for i in 0..=30 {
+///         set_alpha(i as f32 / 30.0);
+///         executor::next_frame().await;
+///     }
+/// }
+/// ```
+pub fn next_frame() -> NextFrame {
+    NextFrame { frame: None }
+}
+
+/// Future returned by [`sleep()`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless awaited"]
+pub struct Sleep {
+    deadline: u64,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if dmtime::get_time() >= self.deadline {
+            Poll::Ready(())
+        } else {
+            wake_next_frame(cx);
+            Poll::Pending
+        }
+    }
+}
+
+/// Completes on the first frame after `duration` has passed, measured with [`dmtime::get_time()`].
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep {
+        deadline: dmtime::get_time().saturating_add(duration.as_micros() as u64),
+    }
+}
+
+/// Future returned by [`readable()`] and [`writable()`].
+#[must_use = "futures do nothing unless awaited"]
+pub struct Ready<'a, S> {
+    socket: &'a S,
+    kind: SelectorKind,
+}
+
+impl<S: AsRawSocket> Future for Ready<'_, S> {
+    type Output = dmsocket::Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut selector = Selector::new();
+        selector.set(self.kind, self.socket);
+        selector.set(SelectorKind::Except, self.socket);
+        selector.select(Some(Duration::ZERO))?;
+
+        // Errors are reported as ready, so the next read or write returns them
+        if selector.is_set(self.kind, self.socket)
+            || selector.is_set(SelectorKind::Except, self.socket)
+        {
+            Poll::Ready(Ok(()))
+        } else {
+            wake_next_frame(cx);
+            Poll::Pending
+        }
+    }
+}
+
+impl<S> fmt::Debug for Ready<'_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ready").field("kind", &self.kind).finish()
+    }
+}
+
+/// Completes once `socket` can be read from without blocking, checked once per frame.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+/// use std::io::Read;
+///
+/// async fn receive(mut stream: dmsocket::TcpStream) -> std::io::Result<Vec<u8>> {
+///     stream.set_nonblocking(true)?;
+///     let mut data = Vec::new();
+///     let mut buf = [0; 1024];
+///     loop {
+///         executor::readable(&stream).await?;
+///         match stream.read(&mut buf)? {
+///             0 => return Ok(data),
+///             n => data.extend_from_slice(&buf[..n]),
+///         }
+///     }
+/// }
+/// ```
+pub fn readable<S: AsRawSocket>(socket: &S) -> Ready<'_, S> {
+    Ready {
+        socket,
+        kind: SelectorKind::Read,
+    }
+}
+
+/// Completes once `socket` can be written to without blocking, or a non-blocking connect has finished.
+pub fn writable<S: AsRawSocket>(socket: &S) -> Ready<'_, S> {
+    Ready {
+        socket,
+        kind: SelectorKind::Write,
+    }
+}
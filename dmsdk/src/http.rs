@@ -0,0 +1,785 @@
+//! HTTP client built on the engine's sockets.
+//!
+//! Requests are sent either on the calling thread with [`Request::send()`], which returns a [`Response`] whose
+//! body can be streamed, or on the [`mainthread`] worker pool with [`Request::send_with()`] and
+//! [`Request::send_lua()`]. Completions of background requests run on the main thread, along with the other jobs
+//! queued with [`mainthread::run_on_main()`]. Since they share the pool with [`mainthread::spawn_blocking()`], at
+//! most [`mainthread::set_workers()`] requests run at once and the rest wait for a free worker.
+//!
+//! Response headers are parsed by the engine's HTTP parser. Requests can reuse keep-alive connections by going
+//! through a [`Pool`].
+//!
+//! # Examples
+//! ```
+//! use dmsdk::*;
+//! use std::time::Duration;
+//!
+//! fn fetch_score(player: &str) -> Result<String, http::Error> {
+//!     let response = http::Request::get(&format!("https://scores.example.com/player/{player}"))
+//!         .header("Accept", "text/plain")
+//!         .timeout(Duration::from_secs(5))
+//!         .send()?;
+//!
+//!     response.text()
+//! }
+//! ```
+
+use crate::{
+    dmconnectionpool::{self, Pool},
+    dmscript::LuaCallback,
+    dmsocket::{self, TcpStream, TlsStream},
+    dmuri::Uri,
+    lua, mainthread,
+};
+use dmsdk_ffi::dmHttpClient;
+use libc::{c_char, c_int, c_void};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::CStr,
+    fmt,
+    io::{self, Read, Write},
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// Timeout used for connecting through a [`Pool`] when the request has none.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+const MAX_HEADER_SIZE: usize = 64 * 1024;
+
+/// HTTP client error.
+#[derive(Debug)]
+pub enum Error {
+    /// The URL isn't an absolute `http` or `https` URL.
+    InvalidUrl,
+    /// A header name or value contains a line break, which would let it add headers of its own.
+    InvalidHeader,
+    /// The server sent something that isn't a valid HTTP response.
+    InvalidResponse,
+    /// Connecting, sending or receiving failed.
+    Io(io::Error),
+    /// Getting a connection from the pool failed.
+    Pool(dmconnectionpool::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUrl => f.write_str("invalid URL"),
+            Self::InvalidHeader => f.write_str("header contains a line break"),
+            Self::InvalidResponse => f.write_str("invalid HTTP response"),
+            Self::Io(err) => err.fmt(f),
+            Self::Pool(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Pool(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<dmsocket::Error> for Error {
+    fn from(err: dmsocket::Error) -> Self {
+        Self::Io(err.into())
+    }
+}
+
+impl From<dmconnectionpool::Error> for Error {
+    fn from(err: dmconnectionpool::Error) -> Self {
+        Self::Pool(err)
+    }
+}
+
+/// [`Result`](core::result::Result) alias with an error type of [`Error`].
+pub type Result<T> = core::result::Result<T, Error>;
+
+struct Url {
+    tls: bool,
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl Url {
+    fn parse(url: &str) -> Result<Self> {
+        let uri = Uri::parse(url).map_err(|_| Error::InvalidUrl)?;
+        let tls = match uri.scheme.to_ascii_lowercase().as_str() {
+            "http" => false,
+            "https" => true,
+            _ => return Err(Error::InvalidUrl),
+        };
+
+        let host = uri.hostname.trim_start_matches('[').trim_end_matches(']');
+        if host.is_empty() {
+            return Err(Error::InvalidUrl);
+        }
+
+        let mut path = match uri.path.as_str() {
+            "" => "/".to_owned(),
+            path => path.to_owned(),
+        };
+        if let Some(query) = &uri.query {
+            path.push('?');
+            path.push_str(query);
+        }
+
+        Ok(Self {
+            tls,
+            host: host.to_owned(),
+            port: uri.port.unwrap_or(if tls { 443 } else { 80 }),
+            path,
+        })
+    }
+
+    fn host_header(&self) -> String {
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+
+        match (self.tls, self.port) {
+            (false, 80) | (true, 443) => host,
+            (_, port) => format!("{host}:{port}"),
+        }
+    }
+}
+
+enum Transport {
+    Tcp(TcpStream),
+    Tls(TlsStream),
+    Pooled(dmconnectionpool::Connection),
+}
+
+impl Read for Transport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(stream) => stream.read(buf),
+            Self::Tls(stream) => stream.read(buf),
+            Self::Pooled(connection) => connection.read(buf),
+        }
+    }
+}
+
+impl Write for Transport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(stream) => stream.write(buf),
+            Self::Tls(stream) => stream.write(buf),
+            Self::Pooled(connection) => connection.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// HTTP request builder.
+#[derive(Debug, Clone)]
+pub struct Request {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    timeout: Option<Duration>,
+    pool: Option<Pool>,
+}
+
+impl Request {
+    /// Creates a request with the given method, e.g. `"PATCH"`, and an absolute `http` or `https` URL.
+    pub fn new(method: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            method: method.into(),
+            url: url.into(),
+            headers: Vec::new(),
+            body: Vec::new(),
+            timeout: None,
+            pool: None,
+        }
+    }
+
+    /// Creates a `GET` request.
+    pub fn get(url: impl Into<String>) -> Self {
+        Self::new("GET", url)
+    }
+
+    /// Creates a `POST` request.
+    pub fn post(url: impl Into<String>) -> Self {
+        Self::new("POST", url)
+    }
+
+    /// Creates a `PUT` request.
+    pub fn put(url: impl Into<String>) -> Self {
+        Self::new("PUT", url)
+    }
+
+    /// Creates a `DELETE` request.
+    pub fn delete(url: impl Into<String>) -> Self {
+        Self::new("DELETE", url)
+    }
+
+    /// Adds a header to this request.
+    ///
+    /// Sending fails with [`Error::InvalidHeader`] if `name` or `value` contains a line break.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the body of this request.
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Sets how long connecting and each read or write may take.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends this request over a kept-alive connection from `pool` when possible.
+    pub fn pool(mut self, pool: &Pool) -> Self {
+        self.pool = Some(pool.clone());
+        self
+    }
+
+    /// Sends this request on the calling thread and waits for the response headers.
+    pub fn send(self) -> Result<Response> {
+        let url = Url::parse(&self.url)?;
+        if self
+            .headers
+            .iter()
+            .any(|(name, value)| name.contains(['\r', '\n']) || value.contains(['\r', '\n']))
+        {
+            return Err(Error::InvalidHeader);
+        }
+        let pooled = self.pool.is_some();
+        let mut transport = self.connect(&url)?;
+
+        let mut head = format!(
+            "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: {}\r\n",
+            self.method,
+            url.path,
+            url.host_header(),
+            if pooled { "keep-alive" } else { "close" }
+        );
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        if !self.body.is_empty() || matches!(self.method.as_str(), "POST" | "PUT" | "PATCH") {
+            head.push_str(&format!("Content-Length: {}\r\n", self.body.len()));
+        }
+        head.push_str("\r\n");
+
+        transport.write_all(head.as_bytes())?;
+        transport.write_all(&self.body)?;
+
+        Response::read(transport, &self.method)
+    }
+
+    fn connect(&self, url: &Url) -> Result<Transport> {
+        if let Some(pool) = &self.pool {
+            let connection = pool.dial(
+                &url.host,
+                url.port,
+                url.tls,
+                self.timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT),
+            )?;
+            connection.set_read_timeout(self.timeout)?;
+            return Ok(Transport::Pooled(connection));
+        }
+
+        let stream = TcpStream::connect_host(&url.host, url.port)?;
+        stream.set_read_timeout(self.timeout)?;
+        stream.set_write_timeout(self.timeout)?;
+        if url.tls {
+            Ok(Transport::Tls(TlsStream::from_stream(
+                stream,
+                &url.host,
+                self.timeout,
+            )?))
+        } else {
+            Ok(Transport::Tcp(stream))
+        }
+    }
+
+    /// Sends this request on the [`mainthread`] worker pool and calls `on_complete` with the whole response on the
+    /// main thread.
+    ///
+    /// # Examples
+    /// ```
+    /// use dmsdk::*;
+    ///
+    /// fn submit_score(score: u32) -> http::RequestId {
+    ///     http::Request::post("https://scores.example.com/submit")
+    ///         .header("Content-Type", "application/json")
+    ///         .body(format!(r#"{{"score":{score}}}"#))
+    ///         .send_with(|result| match result {
+    ///             Ok(response) if response.status == 200 => println!("Score submitted"),
+    ///             Ok(response) => println!("Server responded with {}", response.status),
+    ///             Err(err) => println!("Couldn't submit score: {err}"),
+    ///         })
+    /// }
+    /// ```
+    pub fn send_with<F>(self, on_complete: F) -> RequestId
+    where
+        F: FnOnce(Result<CompletedResponse>) + Send + 'static,
+    {
+        let id = RequestId(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+        self.spawn().then(on_complete);
+
+        id
+    }
+
+    /// Sends this request on the [`mainthread`] worker pool and calls `callback` on the main thread with the same
+    /// arguments as `http.request()` callbacks: `self`, the request ID and a table with `status`, `headers` and
+    /// `response` fields, plus `error` if the request failed.
+    ///
+    /// Must be called on the main thread, like everything else that uses Lua.
+    ///
+    /// # Examples
+    /// ```
+    /// use dmsdk::*;
+    ///
+    /// // Called from Lua as `my_extension.get(url, function(self, id, response) ... end)`
+    /// fn get(l: lua::State) -> i32 {
+    ///     let url = lua::check_string(l, 1);
+    ///     let callback = dmscript::LuaCallback::new(l, 2);
+    ///     let id = http::Request::get(url).send_lua(callback);
+    ///     lua::push_integer(l, id.0 as isize);
+    ///
+    ///     1
+    /// }
+    /// ```
+    pub fn send_lua(self, callback: LuaCallback) -> RequestId {
+        let id = RequestId(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+        // Callbacks can't leave the main thread, so they wait there for the result
+        PENDING.with(|pending| pending.borrow_mut().insert(id, callback));
+        self.spawn().then(move |result| {
+            if let Some(callback) = PENDING.with(|pending| pending.borrow_mut().remove(&id)) {
+                callback.call(|l| {
+                    push_result(l, id, &result);
+                    2
+                });
+            }
+        });
+
+        id
+    }
+
+    /// Sends this request on the [`mainthread`](crate::mainthread) worker pool and completes with the whole
+    /// response. Requires the `async` feature.
+    ///
+    /// # Examples
+    /// ```
+    /// use dmsdk::*;
+    ///
+    /// async fn fetch_motd() -> Result<String, http::Error> {
+    ///     let response = http::Request::get("https://example.com/motd.txt")
+    ///         .send_async()
+    ///         .await?;
+    ///
+    ///     Ok(String::from_utf8_lossy(&response.body).into_owned())
+    /// }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn send_async(self) -> Result<CompletedResponse> {
+        match self.spawn().await {
+            Ok(result) => result,
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    }
+
+    fn spawn(self) -> mainthread::Task<Result<CompletedResponse>> {
+        mainthread::spawn_blocking(move || self.send().and_then(Response::complete))
+    }
+}
+
+#[derive(Default)]
+struct Head {
+    version: (c_int, c_int),
+    status: u16,
+    status_text: String,
+    headers: Vec<(String, String)>,
+}
+
+unsafe fn c_str(s: *const c_char) -> String {
+    if s.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(s).to_string_lossy().into_owned()
+    }
+}
+
+unsafe extern "C" fn on_version(
+    user_data: *mut c_void,
+    major: c_int,
+    minor: c_int,
+    status: c_int,
+    status_str: *const c_char,
+) {
+    let head = &mut *(user_data as *mut Head);
+    head.version = (major, minor);
+    head.status = status as u16;
+    head.status_text = c_str(status_str);
+}
+
+unsafe extern "C" fn on_header(user_data: *mut c_void, key: *const c_char, value: *const c_char) {
+    let head = &mut *(user_data as *mut Head);
+    head.headers.push((c_str(key), c_str(value)));
+}
+
+unsafe extern "C" fn on_body(_user_data: *mut c_void, _offset: c_int) {}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// Response whose body hasn't been read yet. The body is streamed with the [`Read`] implementation.
+///
+/// A pooled connection is returned to its pool once the whole body has been read, unless the server doesn't keep
+/// it alive. It's closed if the response is dropped before that.
+pub struct Response {
+    head: Head,
+    body: Body,
+}
+
+impl Response {
+    fn read(mut transport: Transport, method: &str) -> Result<Self> {
+        let mut buf = Vec::new();
+        let mut chunk = [0; 4096];
+        let header_end = loop {
+            if let Some(i) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+                break i + 4;
+            }
+            if buf.len() > MAX_HEADER_SIZE {
+                return Err(Error::InvalidResponse);
+            }
+            match transport.read(&mut chunk)? {
+                0 => return Err(Error::InvalidResponse),
+                len => buf.extend_from_slice(&chunk[..len]),
+            }
+        };
+
+        // The parser writes into the buffer, which must be nul-terminated
+        let mut header = buf[..header_end].to_vec();
+        header.push(0);
+        let mut head = Head::default();
+        let result = unsafe {
+            dmHttpClient::ParseHeader(
+                header.as_mut_ptr() as *mut c_char,
+                &mut head as *mut Head as *mut c_void,
+                false,
+                Some(on_version),
+                Some(on_header),
+                Some(on_body),
+            )
+        };
+        if result != dmHttpClient::ParseResult_PARSE_RESULT_OK {
+            return Err(Error::InvalidResponse);
+        }
+
+        let framing = if method.eq_ignore_ascii_case("HEAD")
+            || matches!(head.status, 100..=199 | 204 | 304)
+        {
+            Framing::Length(0)
+        } else if find_header(&head.headers, "Transfer-Encoding")
+            .is_some_and(|encoding| encoding.to_ascii_lowercase().contains("chunked"))
+        {
+            Framing::Chunked(0)
+        } else if let Some(length) = find_header(&head.headers, "Content-Length") {
+            Framing::Length(length.trim().parse().map_err(|_| Error::InvalidResponse)?)
+        } else {
+            Framing::UntilClose
+        };
+        // HTTP/1.0 connections close after the response unless the server says otherwise, and a body that ends
+        // with the connection leaves nothing to reuse
+        let connection = |option: &str| {
+            find_header(&head.headers, "Connection").is_some_and(|value| {
+                value
+                    .split(',')
+                    .any(|token| token.trim().eq_ignore_ascii_case(option))
+            })
+        };
+        let keep_alive = !matches!(framing, Framing::UntilClose)
+            && if head.version >= (1, 1) {
+                !connection("close")
+            } else {
+                connection("keep-alive")
+            };
+
+        Ok(Self {
+            head,
+            body: Body {
+                transport: Some(transport),
+                prefix: buf.split_off(header_end),
+                prefix_pos: 0,
+                framing,
+                keep_alive,
+            },
+        })
+    }
+
+    /// Returns the status code.
+    pub fn status(&self) -> u16 {
+        self.head.status
+    }
+
+    /// Returns the status text, e.g. `"Not Found"`.
+    pub fn status_text(&self) -> &str {
+        &self.head.status_text
+    }
+
+    /// Returns every header in the order they were received.
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.head.headers
+    }
+
+    /// Returns the first value of the given header, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.head.headers, name)
+    }
+
+    /// Reads the whole body.
+    pub fn bytes(mut self) -> Result<Vec<u8>> {
+        let mut body = Vec::new();
+        self.read_to_end(&mut body)?;
+        Ok(body)
+    }
+
+    /// Reads the whole body as text, replacing invalid UTF-8.
+    pub fn text(self) -> Result<String> {
+        Ok(String::from_utf8_lossy(&self.bytes()?).into_owned())
+    }
+
+    fn complete(self) -> Result<CompletedResponse> {
+        let status = self.head.status;
+        let headers = self.head.headers.clone();
+        Ok(CompletedResponse {
+            status,
+            headers,
+            body: self.bytes()?,
+        })
+    }
+}
+
+impl Read for Response {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.body.read(buf)
+    }
+}
+
+impl fmt::Debug for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response")
+            .field("status", &self.head.status)
+            .field("headers", &self.head.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+enum Framing {
+    Length(u64),
+    /// Bytes left in the current chunk
+    Chunked(u64),
+    UntilClose,
+    Done,
+}
+
+struct Body {
+    transport: Option<Transport>,
+    prefix: Vec<u8>,
+    prefix_pos: usize,
+    framing: Framing,
+    keep_alive: bool,
+}
+
+impl Body {
+    fn read_raw(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.prefix_pos < self.prefix.len() {
+            let len = buf.len().min(self.prefix.len() - self.prefix_pos);
+            buf[..len].copy_from_slice(&self.prefix[self.prefix_pos..self.prefix_pos + len]);
+            self.prefix_pos += len;
+            return Ok(len);
+        }
+
+        match &mut self.transport {
+            Some(transport) => transport.read(buf),
+            None => Ok(0),
+        }
+    }
+
+    fn read_line(&mut self) -> io::Result<String> {
+        let mut line = Vec::new();
+        let mut byte = [0];
+        while !line.ends_with(b"\r\n") {
+            if self.read_raw(&mut byte)? == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            line.push(byte[0]);
+        }
+        line.truncate(line.len() - 2);
+        Ok(String::from_utf8_lossy(&line).into_owned())
+    }
+
+    fn finish(&mut self) {
+        self.framing = Framing::Done;
+        if let Some(Transport::Pooled(connection)) = self.transport.take() {
+            if !self.keep_alive {
+                connection.close();
+            }
+        }
+    }
+}
+
+impl Read for Body {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        match self.framing {
+            Framing::Done => Ok(0),
+            Framing::Length(0) => {
+                self.finish();
+                Ok(0)
+            }
+            Framing::Length(remaining) => {
+                let len = buf.len().min(remaining.min(usize::MAX as u64) as usize);
+                let read = self.read_raw(&mut buf[..len])?;
+                if read == 0 {
+                    return Err(io::ErrorKind::UnexpectedEof.into());
+                }
+                self.framing = Framing::Length(remaining - read as u64);
+                if remaining == read as u64 {
+                    self.finish();
+                }
+                Ok(read)
+            }
+            Framing::UntilClose => {
+                let read = self.read_raw(buf)?;
+                if read == 0 {
+                    self.finish();
+                }
+                Ok(read)
+            }
+            Framing::Chunked(0) => {
+                let line = self.read_line()?;
+                let size = line.split(';').next().unwrap_or_default().trim();
+                let size = u64::from_str_radix(size, 16).map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidData, "invalid chunk size")
+                })?;
+                if size == 0 {
+                    // Skip trailers
+                    while !self.read_line()?.is_empty() {}
+                    self.finish();
+                    return Ok(0);
+                }
+                self.framing = Framing::Chunked(size);
+                self.read(buf)
+            }
+            Framing::Chunked(remaining) => {
+                let len = buf.len().min(remaining.min(usize::MAX as u64) as usize);
+                let read = self.read_raw(&mut buf[..len])?;
+                if read == 0 {
+                    return Err(io::ErrorKind::UnexpectedEof.into());
+                }
+                self.framing = Framing::Chunked(remaining - read as u64);
+                if remaining == read as u64 && !self.read_line()?.is_empty() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "invalid chunk end",
+                    ));
+                }
+                Ok(read)
+            }
+        }
+    }
+}
+
+impl Drop for Body {
+    fn drop(&mut self) {
+        // A partially read response would be mistaken for the next one on a reused connection
+        if let Some(Transport::Pooled(connection)) = self.transport.take() {
+            connection.close();
+        }
+    }
+}
+
+/// Response of a request sent with [`Request::send_with()`], with the whole body.
+#[derive(Debug, Clone)]
+pub struct CompletedResponse {
+    /// Status code.
+    pub status: u16,
+    /// Headers in the order they were received.
+    pub headers: Vec<(String, String)>,
+    /// Body.
+    pub body: Vec<u8>,
+}
+
+impl CompletedResponse {
+    /// Returns the first value of the given header, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// Identifies a request sent with [`Request::send_with()`] or [`Request::send_lua()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RequestId(pub u64);
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static PENDING: RefCell<HashMap<RequestId, LuaCallback>> = RefCell::new(HashMap::new());
+}
+
+fn push_result(l: lua::State, id: RequestId, result: &Result<CompletedResponse>) {
+    lua::push_integer(l, id.0 as isize);
+    lua::new_table(l);
+
+    match result {
+        Ok(response) => {
+            lua::push_integer(l, response.status as isize);
+            lua::set_field(l, -2, "status");
+
+            lua::new_table(l);
+            for (name, value) in &response.headers {
+                lua::push_string(l, value);
+                lua::set_field(l, -2, &name.to_ascii_lowercase());
+            }
+            lua::set_field(l, -2, "headers");
+
+            lua::push_bytes(l, &response.body);
+            lua::set_field(l, -2, "response");
+        }
+        Err(err) => {
+            lua::push_integer(l, 0);
+            lua::set_field(l, -2, "status");
+
+            lua::new_table(l);
+            lua::set_field(l, -2, "headers");
+
+            lua::push_string(l, &err.to_string());
+            lua::set_field(l, -2, "error");
+        }
+    }
+}
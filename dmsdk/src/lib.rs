@@ -4,27 +4,41 @@
 //!
 //! Rust-friendly wrappers for interacting with the [Defold](https://defold.com) extension SDK.
 
+pub mod debug_server;
+pub mod dmarray;
 pub mod dmconfigfile;
+pub mod dmconnectionpool;
+pub mod dmcrypt;
 pub mod dmengine;
 pub mod dmextension;
 pub mod dmgameobject;
-mod dmhash;
+pub mod dmhash;
+pub mod dmhashtable;
 pub mod dmhid;
 pub mod dmlog;
-//pub mod dmresource; TODO update this
+pub mod dmmemory;
+pub mod dmprofile;
+pub mod dmresource;
 pub mod dmscript;
+pub mod dmsocket;
+pub mod dmthread;
 pub mod dmtime;
+pub mod dmuri;
 pub mod dmvmath;
 pub mod dmwebserver;
+#[cfg(feature = "async")]
+pub mod executor;
+pub mod http;
 pub mod lua;
+pub mod mainthread;
 
 pub use dmhash::*;
 
+/// Log domain used by the `dmlog` macros inside this crate.
+const LOG_DOMAIN: &str = "DMSDK";
+
 #[doc(hidden)]
 pub use paste::paste;
 
 #[doc(hidden)]
 pub use ctor::ctor;
-
-#[doc(hidden)]
-pub use lazy_static::lazy_static;
@@ -66,6 +66,7 @@ macro_rules! declare_functions {
              $(
                 #[no_mangle]
                 extern "C" fn [<_wrapped_ $func>](l: lua::StatePtr) -> i32 {
+                    dmprofile::scope!(stringify!($func));
                     unsafe {
                         $func(lua::State::new(l))
                     }
@@ -108,6 +109,13 @@ pub fn push_string(l: State, s: &str) {
     }
 }
 
+/// Pushes a byte slice onto the stack as a Lua string.
+pub fn push_bytes(l: State, bytes: &[u8]) {
+    unsafe {
+        dmsdk_ffi::lua_pushlstring(l.ptr, bytes.as_ptr() as *const libc::c_char, bytes.len());
+    }
+}
+
 /// Checks if there is a Lua string at `i` and converts it into a [`String`].
 ///
 /// This function uses [`CStr::from_ptr()`] and [`String::from_utf8_lossy()`],
@@ -172,6 +180,16 @@ pub fn raw_set_i(l: State, i: i32, n: i32) {
     }
 }
 
+/// Sets the field `name` of the table at `i` to the value on top of the stack.
+///
+/// The value at the top of the stack will be popped.
+pub fn set_field(l: State, i: i32, name: &str) {
+    let name = CString::new(name).unwrap();
+    unsafe {
+        dmsdk_ffi::lua_setfield(l.ptr, i, name.as_ptr());
+    }
+}
+
 /// Pops `n` elements from the stack.
 pub fn pop(l: State, n: i32) {
     unsafe {
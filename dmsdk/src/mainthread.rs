@@ -0,0 +1,326 @@
+//! Hands work to a pool of worker threads and results back to the main thread.
+//!
+//! Closures queued with [`run_on_main()`] run on the main thread, where Lua, game objects and [`dmscript`](crate::dmscript)
+//! can be used. The queue is drained once per frame, right before the `on_update` of the first extension declared with
+//! [`declare_extension!`](crate::declare_extension), for at most [`set_frame_budget()`] each frame.
+//! Anything left over runs on the following frames.
+//!
+//! [`spawn_blocking()`] runs slow work, such as file IO or image decoding, on the worker pool,
+//! and [`Task::then()`] delivers its result to the main thread.
+//!
+//! # Examples
+//! ```
+//! use dmsdk::*;
+//!
+//! fn load_level(path: String) {
+//!     mainthread::spawn_blocking(move || std::fs::read(path)).then(|result| match result {
+//!         Ok(data) => println!("loaded {} bytes", data.len()),
+//!         Err(err) => println!("failed to load level: {err}"),
+//!     });
+//! }
+//! ```
+
+use crate::{dmextension, dmlog, dmthread};
+use std::{
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Condvar, Mutex, MutexGuard, Once, PoisonError,
+    },
+    task::{Context, Poll, Waker},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Number of jobs [`run_on_main()`] queues before it starts blocking.
+pub const DEFAULT_QUEUE_CAPACITY: usize = 1024;
+/// Time spent running queued jobs each frame.
+pub const DEFAULT_FRAME_BUDGET: Duration = Duration::from_millis(2);
+/// Number of threads in the worker pool.
+pub const DEFAULT_WORKERS: usize = 4;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+struct Queue {
+    jobs: Mutex<VecDeque<Job>>,
+    changed: Condvar,
+}
+
+impl Queue {
+    const fn new() -> Self {
+        Self {
+            jobs: Mutex::new(VecDeque::new()),
+            changed: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, VecDeque<Job>> {
+        self.jobs.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn wait<'a>(&self, jobs: MutexGuard<'a, VecDeque<Job>>) -> MutexGuard<'a, VecDeque<Job>> {
+        self.changed
+            .wait(jobs)
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+static MAIN: Queue = Queue::new();
+static WORK: Queue = Queue::new();
+static WORKERS_STARTED: Once = Once::new();
+
+static QUEUE_CAPACITY: AtomicUsize = AtomicUsize::new(DEFAULT_QUEUE_CAPACITY);
+static FRAME_BUDGET_MICROS: AtomicU64 = AtomicU64::new(DEFAULT_FRAME_BUDGET.as_micros() as u64);
+static WORKERS: AtomicUsize = AtomicUsize::new(DEFAULT_WORKERS);
+
+/// Sets how many jobs can wait for the main thread before [`run_on_main()`] blocks.
+pub fn set_queue_capacity(capacity: usize) {
+    QUEUE_CAPACITY.store(capacity.max(1), Ordering::Relaxed);
+    MAIN.changed.notify_all();
+}
+
+/// Sets how long queued jobs may run each frame.
+///
+/// At least one job runs every frame, however long it takes.
+pub fn set_frame_budget(budget: Duration) {
+    FRAME_BUDGET_MICROS.store(budget.as_micros() as u64, Ordering::Relaxed);
+}
+
+/// Sets the number of threads in the worker pool.
+///
+/// Has no effect once the pool has started, which happens on the first call to [`spawn_blocking()`].
+pub fn set_workers(count: usize) {
+    WORKERS.store(count.max(1), Ordering::Relaxed);
+}
+
+/// Queues `f` to run on the main thread.
+///
+/// When called off the main thread with a full queue, this blocks until there's room.
+/// On the main thread the job is always queued, since blocking would never end.
+///
+/// # Examples
+/// ```
+/// use dmsdk::*;
+///
+/// fn report_progress(percent: u32) {
+///     mainthread::run_on_main(move || {
+///         println!("download at {percent}%");
+///     });
+/// }
+/// ```
+pub fn run_on_main(f: impl FnOnce() + Send + 'static) {
+    let mut jobs = MAIN.lock();
+    if !dmextension::is_main_thread() {
+        while jobs.len() >= QUEUE_CAPACITY.load(Ordering::Relaxed) {
+            jobs = MAIN.wait(jobs);
+        }
+    }
+    jobs.push_back(Box::new(f));
+}
+
+/// Queues `f` to run on the main thread, or gives it back if the queue is full.
+pub fn try_run_on_main<F: FnOnce() + Send + 'static>(f: F) -> Result<(), F> {
+    let mut jobs = MAIN.lock();
+    if jobs.len() >= QUEUE_CAPACITY.load(Ordering::Relaxed) {
+        return Err(f);
+    }
+    jobs.push_back(Box::new(f));
+    Ok(())
+}
+
+/// Returns the number of jobs waiting for the main thread.
+pub fn pending() -> usize {
+    MAIN.lock().len()
+}
+
+/// Runs jobs queued with [`run_on_main()`] until the queue is empty or the frame budget is used up,
+/// and returns how many ran.
+///
+/// This is called automatically once per frame by extensions declared with
+/// [`declare_extension!`](crate::declare_extension), so it only needs to be called by hand without one.
+/// Must be called on the main thread.
+pub fn drain() -> usize {
+    let budget = Duration::from_micros(FRAME_BUDGET_MICROS.load(Ordering::Relaxed));
+    let start = Instant::now();
+    let mut count = 0;
+
+    loop {
+        // The lock is released before running the job, so it can queue more
+        let Some(job) = MAIN.lock().pop_front() else {
+            break;
+        };
+        MAIN.changed.notify_all();
+
+        if panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+            dmlog::error!("main thread job panicked");
+        }
+        count += 1;
+
+        if start.elapsed() >= budget {
+            break;
+        }
+    }
+
+    count
+}
+
+fn start_workers() {
+    WORKERS_STARTED.call_once(|| {
+        for i in 0..WORKERS.load(Ordering::Relaxed) {
+            // Workers live as long as the process, so their handles are dropped to detach them
+            dmthread::spawn(&format!("dmsdk_worker_{i}"), || loop {
+                let job = {
+                    let mut jobs = WORK.lock();
+                    loop {
+                        match jobs.pop_front() {
+                            Some(job) => break job,
+                            None => jobs = WORK.wait(jobs),
+                        }
+                    }
+                };
+                job();
+            });
+        }
+    });
+}
+
+enum TaskState<T> {
+    Running,
+    Waiting(Box<dyn FnOnce(T) + Send>),
+    Polled(Waker),
+    Finished(thread::Result<T>),
+    Taken,
+}
+
+struct TaskInner<T> {
+    state: Mutex<TaskState<T>>,
+    finished: Condvar,
+}
+
+impl<T> TaskInner<T> {
+    fn lock(&self) -> MutexGuard<'_, TaskState<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn deliver<T: Send + 'static>(result: thread::Result<T>, callback: Box<dyn FnOnce(T) + Send>) {
+    match result {
+        Ok(value) => run_on_main(move || callback(value)),
+        Err(_) => {
+            dmlog::error!("blocking task panicked");
+        }
+    }
+}
+
+/// Runs `f` on the worker pool.
+///
+/// The returned [`Task`] can hand the result to the main thread with [`Task::then()`], or be waited on with
+/// [`Task::join()`]. Dropping it discards the result once `f` finishes.
+pub fn spawn_blocking<F, T>(f: F) -> Task<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    start_workers();
+
+    let inner = Arc::new(TaskInner {
+        state: Mutex::new(TaskState::Running),
+        finished: Condvar::new(),
+    });
+    let task = Arc::clone(&inner);
+    let job: Job = Box::new(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+
+        let mut state = task.lock();
+        match std::mem::replace(&mut *state, TaskState::Taken) {
+            TaskState::Waiting(callback) => {
+                drop(state);
+                deliver(result, callback);
+            }
+            TaskState::Polled(waker) => {
+                *state = TaskState::Finished(result);
+                drop(state);
+                waker.wake();
+            }
+            _ => {
+                *state = TaskState::Finished(result);
+                task.finished.notify_all();
+            }
+        }
+    });
+
+    WORK.lock().push_back(job);
+    WORK.changed.notify_one();
+
+    Task { inner }
+}
+
+/// Handle to work started with [`spawn_blocking()`].
+pub struct Task<T> {
+    inner: Arc<TaskInner<T>>,
+}
+
+impl<T: Send + 'static> Task<T> {
+    /// Runs `f` with the result on the main thread once the work finishes.
+    ///
+    /// If the work panicked, the panic is logged and `f` is dropped.
+    pub fn then(self, f: impl FnOnce(T) + Send + 'static) {
+        let mut state = self.inner.lock();
+        match std::mem::replace(&mut *state, TaskState::Taken) {
+            TaskState::Finished(result) => {
+                drop(state);
+                deliver(result, Box::new(f));
+            }
+            _ => *state = TaskState::Waiting(Box::new(f)),
+        }
+    }
+
+    /// Blocks until the work finishes and returns its result.
+    ///
+    /// Calling this on the main thread stalls the frame, prefer [`Task::then()`] there.
+    pub fn join(self) -> thread::Result<T> {
+        let mut state = self.inner.lock();
+        loop {
+            match std::mem::replace(&mut *state, TaskState::Taken) {
+                TaskState::Finished(result) => return result,
+                other => *state = other,
+            }
+            state = self
+                .inner
+                .finished
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Returns `true` if the work has finished.
+    pub fn is_finished(&self) -> bool {
+        matches!(*self.inner.lock(), TaskState::Finished(_))
+    }
+}
+
+/// Awaiting a task completes with its result once the work finishes, like [`Task::join()`] without blocking.
+impl<T> Future for Task<T> {
+    type Output = thread::Result<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.inner.lock();
+        match std::mem::replace(&mut *state, TaskState::Taken) {
+            TaskState::Finished(result) => Poll::Ready(result),
+            _ => {
+                *state = TaskState::Polled(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for Task<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Task").finish_non_exhaustive()
+    }
+}
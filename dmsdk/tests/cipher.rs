@@ -0,0 +1,106 @@
+//! Pure Rust, except for the comparison with the engine's XTEA, see `dmsdk/tests/README.md`.
+
+use dmsdk::dmresource::cipher::{self, Cipher};
+
+fn hex(digits: &str) -> Vec<u8> {
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn encrypt(cipher: &Cipher, data: &[u8]) -> Vec<u8> {
+    let mut data = data.to_vec();
+    cipher.encrypt(&mut data);
+    data
+}
+
+#[test]
+fn xtea_known_answers() {
+    // Encrypting zeros leaves the keystream
+    let mut data = [0; 8];
+    cipher::xtea(&[0; 16], &mut data);
+    assert_eq!(data.to_vec(), hex("dee9d4d8f7131ed9"));
+
+    let key = hex("0123456712345678234567893456789a");
+    let mut data = [0; 8];
+    cipher::xtea(&key.try_into().unwrap(), &mut data);
+    assert_eq!(data.to_vec(), hex("1ff9a0261ac64264"));
+}
+
+#[test]
+fn single_block_is_plain_aes() {
+    // FIPS-197, appendix C.1
+    let cipher = Cipher::new(&hex("000102030405060708090a0b0c0d0e0f").try_into().unwrap());
+    assert_eq!(
+        encrypt(&cipher, &hex("00112233445566778899aabbccddeeff")),
+        hex("69c4e0d86a7b0430d8cdb78070b4c55a")
+    );
+}
+
+#[test]
+fn ciphertext_stealing_known_answers() {
+    // RFC 3962, appendix B, which uses the same ciphertext stealing variant with a zero IV
+    let cipher = Cipher::new(b"chicken teriyaki");
+    let plaintext = b"I would like the General Gau's Chicken, please, and wonton soup.";
+    for (len, expected) in [
+        (17, "c6353568f2bf8cb4d8a580362da7ff7f97"),
+        (31, "fc00783e0efdb2c1d445d4c8eff7ed2297687268d6ecccc0c07b25e25ecfe5"),
+        (32, "39312523a78662d5be7fcbcc98ebf5a897687268d6ecccc0c07b25e25ecfe584"),
+        (47, "97687268d6ecccc0c07b25e25ecfe584b3fffd940c16a18c1b5549d2f838029e39312523a78662d5be7fcbcc98ebf5"),
+        (48, "97687268d6ecccc0c07b25e25ecfe5849dad8bbb96c4cdc03bc103e1a194bbd839312523a78662d5be7fcbcc98ebf5a8"),
+        (64, "97687268d6ecccc0c07b25e25ecfe58439312523a78662d5be7fcbcc98ebf5a84807efe836ee89a526730dbc2f7bc8409dad8bbb96c4cdc03bc103e1a194bbd8"),
+    ] {
+        assert_eq!(encrypt(&cipher, &plaintext[..len]), hex(expected), "length {len}");
+    }
+}
+
+#[test]
+fn same_size_resources_have_different_keystreams() {
+    let cipher = Cipher::new(b"0123456789abcdef");
+    let zeros = encrypt(&cipher, &[0; 40]);
+    let ones = encrypt(&cipher, &[1; 40]);
+
+    let keystream = |encrypted: &[u8], original: u8| -> Vec<u8> {
+        encrypted.iter().map(|byte| byte ^ original).collect()
+    };
+    for (a, b) in keystream(&zeros, 0)
+        .chunks(16)
+        .zip(keystream(&ones, 1).chunks(16))
+    {
+        assert_ne!(a, b);
+    }
+}
+
+#[test]
+fn encrypt_roundtrips() {
+    let cipher = Cipher::new(b"0123456789abcdef");
+    for len in [0, 1, 7, 15, 16, 17, 31, 32, 33, 100] {
+        let original: Vec<u8> = (0..len as u8).collect();
+        let mut data = encrypt(&cipher, &original);
+        if len > 0 {
+            assert_ne!(data, original, "length {len}");
+        }
+        cipher.decrypt(&mut data);
+        assert_eq!(data, original, "length {len}");
+    }
+}
+
+#[cfg(dmsdk_engine_tests)]
+#[test]
+fn xtea_matches_engine() {
+    use dmsdk::{
+        dmcrypt::{self, Algorithm},
+        dmresource::cipher::DEFAULT_XTEA_KEY,
+    };
+
+    for len in [1, 7, 8, 9, 16, 100] {
+        let original: Vec<u8> = (0..len as u8).collect();
+        let mut ours = original.clone();
+        cipher::xtea(&DEFAULT_XTEA_KEY, &mut ours);
+
+        let mut engine = original.clone();
+        dmcrypt::encrypt(Algorithm::Xtea, &mut engine, &DEFAULT_XTEA_KEY).unwrap();
+        assert_eq!(ours, engine, "length {len}");
+    }
+}
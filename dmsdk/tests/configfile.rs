@@ -0,0 +1,58 @@
+//! Needs the engine's libraries to link, see `dmsdk/tests/README.md`.
+#![cfg(dmsdk_engine_tests)]
+
+use dmsdk::dmconfigfile::{ConfigFile, ConfigPlugin, PluginCell};
+use std::{
+    ffi::{c_char, CStr},
+    ptr,
+};
+
+/// Returns a new value for every lookup of "changing.key", cycling through three.
+struct Changing(usize);
+
+impl ConfigPlugin for Changing {
+    fn create(_config: ConfigFile) -> Self {
+        Self(0)
+    }
+
+    fn get_string(&mut self, _config: ConfigFile, key: &str, _default: &str) -> Option<String> {
+        (key == "changing.key").then(|| {
+            self.0 += 1;
+            format!("value {}", self.0 % 3)
+        })
+    }
+}
+
+static PLUGIN: PluginCell<Changing> = PluginCell::new();
+
+fn get_string() -> *const c_char {
+    let mut out = ptr::null();
+    let found = unsafe {
+        PLUGIN.get_string(
+            ptr::null_mut(),
+            c"changing.key".as_ptr(),
+            ptr::null(),
+            &mut out,
+        )
+    };
+    assert!(found);
+    out
+}
+
+#[test]
+fn returned_strings_outlive_changes() {
+    PLUGIN.create(ptr::null_mut());
+
+    let first = get_string();
+    let second = get_string();
+    let third = get_string();
+    // All three are still readable after the value changed
+    for (ptr, expected) in [(first, "value 1"), (second, "value 2"), (third, "value 0")] {
+        assert_eq!(unsafe { CStr::from_ptr(ptr) }.to_str().unwrap(), expected);
+    }
+
+    // Values seen before are handed out again instead of being stored twice
+    assert_eq!(get_string(), first);
+
+    PLUGIN.destroy(ptr::null_mut());
+}
@@ -0,0 +1,132 @@
+//! Serves the debug server routes from a stand-in for the engine, so no engine is needed.
+
+use dmsdk::debug_server::{route, Backend};
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    thread,
+};
+
+#[derive(Default)]
+struct FakeEngine {
+    config: HashMap<String, String>,
+    read_only: bool,
+}
+
+impl Backend for FakeEngine {
+    fn get_config(&mut self, key: &str) -> Option<String> {
+        self.config.get(key).cloned()
+    }
+
+    fn set_config(&mut self, key: &str, value: &str) -> Result<(), String> {
+        if self.read_only {
+            return Err("read only".to_owned());
+        }
+        self.config.insert(key.to_owned(), value.to_owned());
+        Ok(())
+    }
+}
+
+/// Serves `connections` connections from `listener` with the debug server routes, then returns.
+fn serve(listener: &TcpListener, backend: &mut dyn Backend, connections: usize) -> io::Result<()> {
+    for stream in listener.incoming().take(connections) {
+        handle(stream?, backend)?;
+    }
+    Ok(())
+}
+
+fn handle(mut stream: TcpStream, backend: &mut dyn Backend) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim_end().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let (status, json) = route(backend, method, path, &body);
+    write!(
+        stream,
+        "HTTP/1.1 {status} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{json}",
+        json.len()
+    )?;
+    stream.flush()
+}
+
+/// Sends each of `requests` on its own connection to a server backed by `backend`, returning the responses.
+fn exchange(backend: &mut FakeEngine, requests: &[&str]) -> Vec<String> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let address = listener.local_addr().unwrap();
+    let requests: Vec<String> = requests.iter().map(|&request| request.to_owned()).collect();
+    let count = requests.len();
+
+    let client = thread::spawn(move || {
+        requests
+            .iter()
+            .map(|request| {
+                let mut stream = TcpStream::connect(address).unwrap();
+                stream.write_all(request.as_bytes()).unwrap();
+                let mut response = String::new();
+                stream.read_to_string(&mut response).unwrap();
+                response
+            })
+            .collect()
+    });
+
+    serve(&listener, backend, count).unwrap();
+    client.join().unwrap()
+}
+
+#[test]
+fn config_roundtrip() {
+    let responses = exchange(
+        &mut FakeEngine::default(),
+        &[
+            "POST /dmsdk/config?key=display.width HTTP/1.1\r\nContent-Length: 4\r\n\r\n1280",
+            "GET /dmsdk/config?key=display.width HTTP/1.1\r\n\r\n",
+            "GET /dmsdk/config?key=display.height HTTP/1.1\r\n\r\n",
+        ],
+    );
+
+    assert!(responses[0].starts_with("HTTP/1.1 200 "));
+    assert!(responses[1].ends_with(r#"{"key":"display.width","value":"1280"}"#));
+    assert!(responses[2].ends_with(r#"{"key":"display.height","value":null}"#));
+}
+
+#[test]
+fn config_write_fails_without_overrides() {
+    let mut engine = FakeEngine {
+        read_only: true,
+        ..Default::default()
+    };
+    let (status, json) = route(&mut engine, "POST", "/dmsdk/config?key=a.b&value=1", b"");
+
+    assert_eq!(status, 501);
+    assert_eq!(json, r#"{"error":"read only"}"#);
+    assert!(engine.config.is_empty());
+}
+
+#[test]
+fn unknown_routes() {
+    let mut engine = FakeEngine::default();
+    assert_eq!(route(&mut engine, "GET", "/dmsdk/nothing", b"").0, 404);
+    assert_eq!(route(&mut engine, "DELETE", "/dmsdk/config", b"").0, 405);
+    assert_eq!(route(&mut engine, "GET", "/dmsdk/config", b"").0, 400);
+}
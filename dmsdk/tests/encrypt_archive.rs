@@ -0,0 +1,80 @@
+//! Pure Rust, runs the `encrypt-archive` tool on a small hand-made archive.
+
+use dmsdk::dmresource::cipher::{self, Cipher, DEFAULT_XTEA_KEY};
+use std::{fs, path::PathBuf, process::Command};
+
+const KEY: &[u8; 16] = b"0123456789abcdef";
+const PLAIN: &[u8] = b"plain resource data";
+const LUA: &[u8] = b"compiled lua, encrypted by the build tools";
+
+/// Writes an archive with a plain entry and one encrypted with the default key, and returns the index path.
+fn archive(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dmsdk-{name}-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let mut lua = LUA.to_vec();
+    cipher::xtea(&DEFAULT_XTEA_KEY, &mut lua);
+    fs::write(dir.join("game.arcd"), [PLAIN, &lua].concat()).unwrap();
+
+    let mut index = vec![0; 48];
+    index[16..20].copy_from_slice(&2u32.to_be_bytes());
+    index[20..24].copy_from_slice(&48u32.to_be_bytes());
+    for (size, compressed, offset, flags) in [
+        (PLAIN.len() as u32, u32::MAX, 0, 0),
+        (100, LUA.len() as u32, PLAIN.len() as u32, 1),
+    ] {
+        for value in [size, compressed, offset, flags] {
+            index.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+    let path = dir.join("game.arci");
+    fs::write(&path, index).unwrap();
+    path
+}
+
+fn encrypt_archive(index: &PathBuf) -> bool {
+    Command::new(env!("CARGO_BIN_EXE_encrypt-archive"))
+        .arg("--key")
+        .arg(std::str::from_utf8(KEY).unwrap())
+        .arg(index)
+        .output()
+        .unwrap()
+        .status
+        .success()
+}
+
+#[test]
+fn entries_decrypt_with_the_cipher() {
+    let index = archive("entries");
+    assert!(encrypt_archive(&index));
+
+    let mut data = fs::read(index.with_extension("arcd")).unwrap();
+    let (plain, lua) = data.split_at_mut(PLAIN.len());
+    let cipher = Cipher::new(KEY);
+    cipher.decrypt(plain);
+    cipher.decrypt(lua);
+    assert_eq!(plain, PLAIN);
+    assert_eq!(lua, LUA);
+
+    let dir = index.parent().unwrap().to_owned();
+    let index = fs::read(&index).unwrap();
+    for entry in [48, 64] {
+        assert_eq!(index[entry + 12..entry + 16], 1u32.to_be_bytes());
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn encrypted_archives_are_refused() {
+    let index = archive("refused");
+    assert!(encrypt_archive(&index));
+    let encrypted = (
+        fs::read(&index).unwrap(),
+        fs::read(index.with_extension("arcd")).unwrap(),
+    );
+
+    assert!(!encrypt_archive(&index));
+    assert_eq!(fs::read(&index).unwrap(), encrypted.0);
+    assert_eq!(fs::read(index.with_extension("arcd")).unwrap(), encrypted.1);
+    fs::remove_dir_all(index.parent().unwrap()).unwrap();
+}
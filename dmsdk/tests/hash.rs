@@ -0,0 +1,122 @@
+//! Pure Rust, except for the comparisons with the engine's hashing, see `dmsdk/tests/README.md`.
+
+use dmsdk::{hash, Hash};
+
+#[test]
+fn hash_macro_matches_from_bytes() {
+    assert_eq!(hash!("").raw(), Hash::from_bytes(b"").raw());
+    assert_eq!(hash!("a").raw(), Hash::from_bytes(b"a").raw());
+    assert_eq!(hash!("jump").raw(), Hash::from_bytes(b"jump").raw());
+    assert_eq!(hash!("12345678").raw(), Hash::from_bytes(b"12345678").raw());
+    assert_eq!(
+        hash!("123456789").raw(),
+        Hash::from_bytes(b"123456789").raw()
+    );
+    assert_eq!(
+        hash!("contact_point_response").raw(),
+        Hash::from_bytes(b"contact_point_response").raw()
+    );
+    assert_eq!(hash!("__unnamed__").raw(), 12415623704795185700);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_formats() {
+    let jump = hash!("jump");
+
+    let json = serde_json::to_string(&jump).unwrap();
+    assert_eq!(json, jump.raw().to_string());
+    assert_eq!(
+        serde_json::from_str::<Hash>(&json).unwrap().raw(),
+        jump.raw()
+    );
+    assert_eq!(
+        serde_json::from_str::<Hash>(r#""jump""#).unwrap().raw(),
+        jump.raw()
+    );
+
+    // Binary formats can't say what type comes next, so they only hold numbers
+    let bytes = bincode::serialize(&jump).unwrap();
+    assert_eq!(bytes, jump.raw().to_le_bytes());
+    assert_eq!(
+        bincode::deserialize::<Hash>(&bytes).unwrap().raw(),
+        jump.raw()
+    );
+}
+
+#[cfg(dmsdk_engine_tests)]
+mod engine {
+    use dmsdk::{hash64, Hash, Hasher64};
+    use std::thread;
+
+    const NAMES: [&str; 6] = [
+        "",
+        "a",
+        "jump",
+        "12345678",
+        "123456789",
+        "contact_point_response",
+    ];
+
+    #[test]
+    fn from_bytes_matches_hash64() {
+        for name in NAMES {
+            assert_eq!(
+                Hash::from_bytes(name.as_bytes()).raw(),
+                hash64(name),
+                "{name:?}"
+            );
+        }
+        let long: String = (0..100).map(|i| char::from(b'a' + i % 26)).collect();
+        assert_eq!(Hash::from_bytes(long.as_bytes()).raw(), hash64(&long));
+    }
+
+    #[test]
+    fn hasher_matches_in_chunks() {
+        let data: Vec<u8> = (0..100).collect();
+        let expected = Hash::from_bytes(&data);
+
+        for chunk_len in [1, 3, 7, 8, 9, 13, 16, 100] {
+            let mut hasher = Hasher64::new();
+            for chunk in data.chunks(chunk_len) {
+                hasher.update(chunk);
+            }
+            assert_eq!(
+                hasher.finish().raw(),
+                expected.raw(),
+                "chunks of {chunk_len}"
+            );
+        }
+
+        // Uneven chunks that cross 8 byte boundaries at different points
+        let mut hasher = Hasher64::new_reversible();
+        let (mut start, mut len) = (0, 1);
+        while start < data.len() {
+            let end = (start + len).min(data.len());
+            hasher.update(&data[start..end]);
+            (start, len) = (end, len + 2);
+        }
+        assert_eq!(hasher.finish().raw(), expected.raw());
+    }
+
+    #[test]
+    fn reverse_from_other_threads() {
+        let hash = Hash::from("reverse_from_other_threads");
+        assert!(Hash::from(12345).reverse().is_none());
+
+        let reversed: Vec<_> = (0..4)
+            .map(|_| thread::spawn(move || (hash.reverse(), hash.to_string())))
+            .map(|worker| worker.join().unwrap())
+            .collect();
+        for (reverse, display) in reversed {
+            // Only debug builds of the engine can reverse hashes
+            match reverse {
+                Some(data) => {
+                    assert_eq!(data, b"reverse_from_other_threads");
+                    assert_eq!(display, "reverse_from_other_threads");
+                }
+                None => assert_eq!(display, format!("{:#018x}", hash.raw())),
+            }
+        }
+    }
+}
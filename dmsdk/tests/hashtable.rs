@@ -0,0 +1,113 @@
+//! Pure Rust, `HashTable` only needs `malloc()` and `free()`.
+
+use dmsdk::dmhashtable::HashTable64;
+
+const INVALID_INDEX: u32 = 0xffffffff;
+
+fn entries(table: &HashTable64<i32>) -> Vec<(u64, i32)> {
+    table.iter().map(|(&key, &value)| (key, value)).collect()
+}
+
+#[test]
+fn insert_get_remove() {
+    let mut table = HashTable64::new();
+    assert_eq!(table.get(1), None);
+    assert_eq!(table.remove(1), None);
+
+    assert_eq!(table.insert(1, 10), None);
+    assert_eq!(table.insert(2, 20), None);
+    assert_eq!(table.insert(1, 11), Some(10));
+    assert_eq!(table.len(), 2);
+    assert_eq!(table.get(1), Some(&11));
+    assert!(table.contains_key(2));
+
+    *table.get_mut(2).unwrap() += 1;
+    assert_eq!(table.remove(2), Some(21));
+    assert_eq!(table.remove(2), None);
+    assert_eq!(table.len(), 1);
+    assert!(!table.contains_key(2));
+
+    table.clear();
+    assert!(table.is_empty());
+    assert_eq!(table.get(1), None);
+    assert!(table.capacity() > 0);
+}
+
+#[test]
+fn removed_entries_are_reused() {
+    let mut table = HashTable64::new();
+    table.set_capacity(2, 4);
+    table.extend([(1, 1), (2, 2), (3, 3), (4, 4)]);
+    assert_eq!(table.as_raw().m_FreeEntries, INVALID_INDEX);
+
+    // Entries are handed out in order, so the second and third ones are 1 and 2
+    assert_eq!(table.remove(2), Some(2));
+    assert_eq!(table.remove(3), Some(3));
+    assert_eq!(table.as_raw().m_FreeEntries, 2);
+
+    // The free list is used before growing, last removed first
+    table.insert(5, 5);
+    assert_eq!(table.as_raw().m_FreeEntries, 1);
+    table.insert(6, 6);
+    assert_eq!(table.as_raw().m_FreeEntries, INVALID_INDEX);
+    assert_eq!(table.capacity(), 4);
+    assert_eq!(table.table_size(), 2);
+
+    // Only a full table grows
+    table.insert(7, 7);
+    assert!(table.capacity() > 4);
+    for key in [1, 4, 5, 6, 7] {
+        assert_eq!(table.get(key), Some(&(key as i32)));
+    }
+}
+
+#[test]
+fn set_capacity_rehashes() {
+    let mut table = HashTable64::new();
+    table.set_capacity(1, 20);
+    table.extend((0..20).map(|key| (key, key as i32 * 2)));
+
+    table.set_capacity(7, 32);
+    assert_eq!(table.table_size(), 7);
+    assert_eq!(table.capacity(), 32);
+    assert_eq!(table.len(), 20);
+    for key in 0..20 {
+        assert_eq!(table.get(key), Some(&(key as i32 * 2)));
+    }
+    // Bucket by bucket after rehashing
+    let keys: Vec<u64> = table.keys().copied().collect();
+    assert_eq!(&keys[..4], [0, 7, 14, 1]);
+
+    let clone = table.clone();
+    assert_eq!(entries(&clone), entries(&table));
+}
+
+#[test]
+#[should_panic(expected = "less than its length")]
+fn set_capacity_keeps_entries() {
+    let mut table: HashTable64<i32> = (0..10).map(|key| (key, 0)).collect();
+    table.set_capacity(4, 9);
+}
+
+#[test]
+fn iteration_order() {
+    let mut table = HashTable64::new();
+    table.set_capacity(4, 8);
+    for key in [5, 1, 2, 9, 4] {
+        table.insert(key, key as i32);
+    }
+
+    // Buckets in order, and each bucket's entries in the order they were added
+    assert_eq!(entries(&table), [(4, 4), (5, 5), (1, 1), (9, 9), (2, 2)]);
+    assert_eq!(table.iter().len(), 5);
+
+    table.remove(1);
+    table.insert(13, 13);
+    for value in table.values_mut() {
+        *value *= 10;
+    }
+    assert_eq!(
+        entries(&table),
+        [(4, 40), (5, 50), (9, 90), (13, 130), (2, 20)]
+    );
+}
@@ -0,0 +1,201 @@
+//! Needs the engine's libraries to link, see `dmsdk/tests/README.md`.
+#![cfg(dmsdk_engine_tests)]
+
+use dmsdk::{dmconnectionpool::Pool, http, mainthread};
+use std::{
+    collections::VecDeque,
+    io::{Read, Write},
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Canned response, and whether the server closes the connection after sending it.
+struct Reply {
+    response: &'static str,
+    close: bool,
+}
+
+fn keep(response: &'static str) -> Reply {
+    Reply {
+        response,
+        close: false,
+    }
+}
+
+fn close(response: &'static str) -> Reply {
+    Reply {
+        response,
+        close: true,
+    }
+}
+
+/// Starts a server that answers requests with `replies`, in order.
+///
+/// Returns its port and a channel receiving the index of the connection each request came on.
+fn server(replies: Vec<Reply>) -> (u16, mpsc::Receiver<usize>) {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let replies = Arc::new(Mutex::new(VecDeque::from(replies)));
+    let (requests, received) = mpsc::channel();
+
+    thread::spawn(move || {
+        for (index, stream) in listener.incoming().enumerate() {
+            let replies = replies.clone();
+            let requests = requests.clone();
+            thread::spawn(move || serve(stream.unwrap(), index, &replies, &requests));
+        }
+    });
+    (port, received)
+}
+
+fn serve(
+    mut stream: TcpStream,
+    index: usize,
+    replies: &Mutex<VecDeque<Reply>>,
+    requests: &mpsc::Sender<usize>,
+) {
+    let mut request = Vec::new();
+    let mut byte = [0];
+    loop {
+        match stream.read(&mut byte) {
+            Ok(1) => request.push(byte[0]),
+            _ => return,
+        }
+        if !request.ends_with(b"\r\n\r\n") {
+            continue;
+        }
+
+        request.clear();
+        let _ = requests.send(index);
+        let Some(reply) = replies.lock().unwrap().pop_front() else {
+            return;
+        };
+        stream.write_all(reply.response.as_bytes()).unwrap();
+        if reply.close {
+            return;
+        }
+    }
+}
+
+fn url(port: u16) -> String {
+    format!("http://127.0.0.1:{port}/test")
+}
+
+#[test]
+fn content_length() {
+    let (port, _) = server(vec![keep(
+        "HTTP/1.1 200 OK\r\nContent-Length: 5\r\nX-Test: yes\r\n\r\nhello",
+    )]);
+
+    let response = http::Request::get(url(port)).send().unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.status_text(), "OK");
+    assert_eq!(response.header("x-test"), Some("yes"));
+    assert_eq!(response.text().unwrap(), "hello");
+}
+
+#[test]
+fn chunked() {
+    let (port, _) = server(vec![keep(
+        "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+         5\r\nhello\r\n7;name=value\r\n, world\r\n0\r\nX-Trailer: yes\r\n\r\n",
+    )]);
+
+    let response = http::Request::get(url(port)).send().unwrap();
+    assert_eq!(response.text().unwrap(), "hello, world");
+}
+
+#[test]
+fn until_close() {
+    let (port, _) = server(vec![close("HTTP/1.1 200 OK\r\n\r\nuntil the end")]);
+
+    let response = http::Request::get(url(port)).send().unwrap();
+    assert_eq!(response.text().unwrap(), "until the end");
+}
+
+#[test]
+fn pooled_connection_is_reused() {
+    let (port, requests) = server(vec![
+        keep("HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nfirst"),
+        keep("HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n6\r\nsecond\r\n0\r\n\r\n"),
+        keep("HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nthird"),
+    ]);
+    let pool = Pool::new().unwrap();
+
+    for expected in ["first", "second", "third"] {
+        let response = http::Request::get(url(port)).pool(&pool).send().unwrap();
+        assert_eq!(response.text().unwrap(), expected);
+    }
+    assert_eq!(requests.try_iter().collect::<Vec<_>>(), [0, 0, 0]);
+}
+
+#[test]
+fn pooled_connection_is_closed_after_until_close_body() {
+    let (port, requests) = server(vec![
+        close("HTTP/1.1 200 OK\r\n\r\nfirst"),
+        keep("HTTP/1.1 200 OK\r\nContent-Length: 6\r\n\r\nsecond"),
+    ]);
+    let pool = Pool::new().unwrap();
+
+    for expected in ["first", "second"] {
+        let response = http::Request::get(url(port)).pool(&pool).send().unwrap();
+        assert_eq!(response.text().unwrap(), expected);
+    }
+    assert_eq!(requests.try_iter().collect::<Vec<_>>(), [0, 1]);
+}
+
+#[test]
+fn pooled_connection_is_closed_after_http_1_0_response() {
+    let (port, requests) = server(vec![
+        keep("HTTP/1.0 200 OK\r\nContent-Length: 5\r\n\r\nfirst"),
+        keep("HTTP/1.0 200 OK\r\nContent-Length: 6\r\nConnection: keep-alive\r\n\r\nsecond"),
+        keep("HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nthird"),
+    ]);
+    let pool = Pool::new().unwrap();
+
+    for expected in ["first", "second", "third"] {
+        let response = http::Request::get(url(port)).pool(&pool).send().unwrap();
+        assert_eq!(response.text().unwrap(), expected);
+    }
+    // HTTP/1.0 connections are only kept alive when the server asks for it
+    assert_eq!(requests.try_iter().collect::<Vec<_>>(), [0, 1, 1]);
+}
+
+#[test]
+fn background_responses_are_delivered_by_drain() {
+    let (port, _) = server(vec![keep(
+        "HTTP/1.1 201 Created\r\nContent-Length: 4\r\n\r\ndone",
+    )]);
+
+    let completed = Arc::new(Mutex::new(None));
+    let result = completed.clone();
+    http::Request::post(url(port))
+        .body("data")
+        .send_with(move |response| *result.lock().unwrap() = Some(response));
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while completed.lock().unwrap().is_none() && Instant::now() < deadline {
+        mainthread::drain();
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    let response = completed.lock().unwrap().take().unwrap().unwrap();
+    assert_eq!(response.status, 201);
+    assert_eq!(response.body, b"done");
+}
+
+#[test]
+fn line_breaks_in_headers_are_rejected() {
+    let (port, requests) = server(Vec::new());
+
+    for (name, value) in [
+        ("X-Test", "value\r\nX-Injected: yes"),
+        ("X-Test\nX-Injected", "value"),
+    ] {
+        let result = http::Request::get(url(port)).header(name, value).send();
+        assert!(matches!(result, Err(http::Error::InvalidHeader)));
+    }
+    assert_eq!(requests.try_iter().count(), 0);
+}
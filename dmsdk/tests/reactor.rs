@@ -0,0 +1,64 @@
+//! Needs the engine's libraries to link, see `dmsdk/tests/README.md`.
+#![cfg(dmsdk_engine_tests)]
+
+use dmsdk::dmsocket::{ConnectionState, Error, Event, Reactor, Token};
+use std::{
+    cell::RefCell,
+    net::{Ipv4Addr, TcpListener},
+    rc::Rc,
+    thread,
+    time::{Duration, Instant},
+};
+
+fn connect(reactor: &mut Reactor, port: u16) -> (Token, Rc<RefCell<Vec<String>>>) {
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let log = events.clone();
+    let token = reactor
+        .connect(Ipv4Addr::LOCALHOST, port, move |_, event| {
+            log.borrow_mut().push(format!("{event:?}"))
+        })
+        .unwrap();
+    (token, events)
+}
+
+fn update_while_connecting(reactor: &mut Reactor, token: Token) -> Option<ConnectionState> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while reactor.state(token) == Some(ConnectionState::Connecting) && Instant::now() < deadline {
+        reactor.update();
+        thread::sleep(Duration::from_millis(1));
+    }
+    reactor.state(token)
+}
+
+#[test]
+fn connect_to_listening_port() {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let mut reactor = Reactor::new();
+    let (token, events) = connect(&mut reactor, port);
+
+    assert_eq!(
+        update_while_connecting(&mut reactor, token),
+        Some(ConnectionState::Connected)
+    );
+    assert_eq!(*events.borrow(), ["Connected"]);
+}
+
+#[test]
+fn connect_to_closed_port_fails() {
+    let port = {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        listener.local_addr().unwrap().port()
+    };
+
+    let mut reactor = Reactor::new();
+    let (token, events) = connect(&mut reactor, port);
+
+    // Failed sockets are removed after their error event
+    assert_eq!(update_while_connecting(&mut reactor, token), None);
+    assert_eq!(
+        *events.borrow(),
+        [format!("{:?}", Event::Error(Error::ConnectionRefused))]
+    );
+}
@@ -0,0 +1,62 @@
+//! Needs the engine's libraries to link, see `dmsdk/tests/README.md`.
+#![cfg(dmsdk_engine_tests)]
+
+use dmsdk::dmsocket::{Error, TcpStream, UdpSocket};
+use std::{
+    net::{self, Ipv4Addr},
+    time::Duration,
+};
+
+fn bind() -> (net::UdpSocket, u16) {
+    let peer = net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    peer.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    let port = peer.local_addr().unwrap().port();
+    (peer, port)
+}
+
+#[test]
+fn udp_exchanges_datagrams_with_its_peer() {
+    let (peer, port) = bind();
+    let socket = UdpSocket::connect(Ipv4Addr::LOCALHOST, port).unwrap();
+    socket
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+
+    assert_eq!(socket.send(b"ping").unwrap(), 4);
+    let mut buf = [0; 16];
+    let (len, from) = peer.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..len], b"ping");
+
+    // Datagrams from anyone else are filtered out
+    let (stranger, _) = bind();
+    stranger.send_to(b"noise", from).unwrap();
+    peer.send_to(b"pong", from).unwrap();
+
+    let len = socket.recv(&mut buf).unwrap();
+    assert_eq!(&buf[..len], b"pong");
+}
+
+#[test]
+fn udp_nonblocking_receive_would_block() {
+    let (_peer, port) = bind();
+    let socket = UdpSocket::connect(Ipv4Addr::LOCALHOST, port).unwrap();
+    socket.set_nonblocking(true).unwrap();
+
+    assert_eq!(socket.recv(&mut [0; 16]), Err(Error::WouldBlock));
+    assert_eq!(socket.take_error(), Ok(None));
+}
+
+#[test]
+fn socket_options() {
+    let (_peer, port) = bind();
+    UdpSocket::connect_broadcast(Ipv4Addr::BROADCAST, port).unwrap();
+    UdpSocket::connect(Ipv4Addr::LOCALHOST, port)
+        .unwrap()
+        .set_broadcast(false)
+        .unwrap();
+
+    let listener = net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let stream = TcpStream::connect(Ipv4Addr::LOCALHOST, port).unwrap();
+    stream.set_quickack(true).unwrap();
+}
@@ -0,0 +1,48 @@
+//! Needs the engine's libraries to link, see `dmsdk/tests/README.md`.
+#![cfg(dmsdk_engine_tests)]
+
+use dmsdk::dmthread::Mutex;
+use std::thread;
+
+/// Tries to lock `mutex` twice from another thread, so a failed attempt that unlocks it shows up.
+fn locked_elsewhere<T: Send>(mutex: &Mutex<T>) -> bool {
+    thread::scope(|scope| {
+        scope
+            .spawn(|| mutex.try_lock().is_none() && mutex.try_lock().is_none())
+            .join()
+            .unwrap()
+    })
+}
+
+#[test]
+fn try_lock_fails_while_locked() {
+    let mutex = Mutex::new(0);
+
+    let guard = mutex.lock();
+    assert!(locked_elsewhere(&mutex));
+    // The engine's mutexes are recursive, so this would succeed without the check
+    assert!(mutex.try_lock().is_none());
+    assert!(locked_elsewhere(&mutex));
+
+    drop(guard);
+    assert!(!locked_elsewhere(&mutex));
+    assert!(mutex.try_lock().is_some());
+}
+
+#[test]
+fn debug_keeps_the_mutex_locked() {
+    let mutex = Mutex::new(1);
+    assert_eq!(format!("{mutex:?}"), "Mutex { data: 1 }");
+
+    let _guard = mutex.lock();
+    assert_eq!(format!("{mutex:?}"), "Mutex { data: <locked> }");
+    assert!(locked_elsewhere(&mutex));
+}
+
+#[test]
+#[should_panic(expected = "already locked by this thread")]
+fn lock_from_holding_thread_panics() {
+    let mutex = Mutex::new(0);
+    let _guard = mutex.lock();
+    let _again = mutex.lock();
+}
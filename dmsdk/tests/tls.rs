@@ -0,0 +1,94 @@
+//! Needs the engine's libraries to link, see `dmsdk/tests/README.md`.
+#![cfg(dmsdk_engine_tests)]
+
+use dmsdk::{dmconnectionpool::Pool, dmsocket::TlsStream};
+use rustls::{
+    pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer},
+    ServerConfig, ServerConnection, StreamOwned,
+};
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{Ipv4Addr, TcpListener},
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+/// Starts a TLS server with a self-signed certificate for `localhost`, answering `connections` connections.
+///
+/// Every line received is sent back with `echo: ` in front of it.
+fn echo_server(connections: usize) -> u16 {
+    let certificate = rcgen::generate_simple_self_signed(["localhost".to_owned()]).unwrap();
+    let key = PrivatePkcs8KeyDer::from(certificate.key_pair.serialize_der());
+    let config =
+        ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_no_client_auth()
+            .with_single_cert(
+                vec![certificate.cert.der().clone()],
+                PrivateKeyDer::Pkcs8(key),
+            )
+            .unwrap();
+    let config = Arc::new(config);
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        for stream in listener.incoming().take(connections) {
+            let connection = ServerConnection::new(config.clone()).unwrap();
+            let mut stream = BufReader::new(StreamOwned::new(connection, stream.unwrap()));
+            let mut line = String::new();
+            while stream.read_line(&mut line).unwrap_or(0) > 0 {
+                write!(stream.get_mut(), "echo: {line}").unwrap();
+                stream.get_mut().flush().unwrap();
+                line.clear();
+            }
+        }
+    });
+    port
+}
+
+fn read_line(stream: &mut impl Read) -> String {
+    let mut line = Vec::new();
+    let mut byte = [0];
+    while stream.read(&mut byte).unwrap() == 1 {
+        line.push(byte[0]);
+        if byte[0] == b'\n' {
+            break;
+        }
+    }
+    String::from_utf8(line).unwrap()
+}
+
+#[test]
+fn tls_stream_accepts_self_signed_certificate() {
+    let port = echo_server(1);
+
+    let mut stream = TlsStream::connect("localhost", port, Some(Duration::from_secs(5))).unwrap();
+    stream.write_all(b"hello\n").unwrap();
+    assert_eq!(read_line(&mut stream), "echo: hello\n");
+    stream.write_all(b"again\n").unwrap();
+    assert_eq!(read_line(&mut stream), "echo: again\n");
+}
+
+#[test]
+fn pooled_tls_connection_is_reused() {
+    let port = echo_server(1);
+    let pool = Pool::new().unwrap();
+
+    let mut connection = pool
+        .dial("localhost", port, true, Duration::from_secs(5))
+        .unwrap();
+    assert!(connection.is_tls());
+    connection.write_all(b"first\n").unwrap();
+    assert_eq!(read_line(&mut connection), "echo: first\n");
+    drop(connection);
+
+    let mut connection = pool
+        .dial("localhost", port, true, Duration::from_secs(5))
+        .unwrap();
+    assert_eq!(connection.reuse_count(), 1);
+    connection.write_all(b"second\n").unwrap();
+    assert_eq!(read_line(&mut connection), "echo: second\n");
+}
@@ -1,3 +1,12 @@
 #include "dmsdk/sdk.h"
 #include "dmsdk/resource/resource.h"
-#include "dmsdk/gameobject/component.h"
\ No newline at end of file
+#include "dmsdk/gameobject/component.h"
+#include "dmsdk/dlib/sslsocket.h"
+#include "dmsdk/dlib/connection_pool.h"
+#include "dmsdk/dlib/http_client.h"
+#include "dmsdk/dlib/crypt.h"
+#include "dmsdk/dlib/uri.h"
+#include "dmsdk/dlib/thread.h"
+#include "dmsdk/dlib/profile.h"
+#include "dmsdk/dlib/intersection.h"
+#include "dmsdk/dlib/memory.h"
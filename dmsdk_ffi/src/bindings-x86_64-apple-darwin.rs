@@ -10432,6 +10432,821 @@ pub mod root {
         pub _base: root::dmHashTable<u64, T>,
         pub _phantom_0: ::std::marker::PhantomData<::std::cell::UnsafeCell<T>>,
     }
+    pub mod dmSSLSocket {
+        #[allow(unused_imports)]
+        use self::super::super::root;
+        pub const Result_RESULT_OK: root::dmSSLSocket::Result = 0;
+        pub const Result_RESULT_UNKNOWN: root::dmSSLSocket::Result = -1;
+        pub const Result_RESULT_SSL_INIT_FAILED: root::dmSSLSocket::Result = -2000;
+        pub const Result_RESULT_HANDSHAKE_FAILED: root::dmSSLSocket::Result = -2001;
+        pub const Result_RESULT_WOULDBLOCK: root::dmSSLSocket::Result = -2002;
+        pub const Result_RESULT_CONNREFUSED: root::dmSSLSocket::Result = -2003;
+        pub type Result = ::std::os::raw::c_int;
+        #[repr(C)]
+        #[derive(Debug, Copy, Clone)]
+        pub struct SSLSocket {
+            _unused: [u8; 0],
+        }
+        pub type Socket = *mut root::dmSSLSocket::SSLSocket;
+        extern "C" {
+            #[link_name = "\u{1}_ZN11dmSSLSocket3NewEiPKcmPPNS_9SSLSocketE"]
+            pub fn New(
+                socket: root::dmSocket::Socket,
+                host: *const ::std::os::raw::c_char,
+                timeout: u64,
+                sslsocket: *mut root::dmSSLSocket::Socket,
+            ) -> root::dmSSLSocket::Result;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN11dmSSLSocket6DeleteEPNS_9SSLSocketE"]
+            pub fn Delete(socket: root::dmSSLSocket::Socket) -> root::dmSSLSocket::Result;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN11dmSSLSocket4SendEPNS_9SSLSocketEPKviPi"]
+            pub fn Send(
+                socket: root::dmSSLSocket::Socket,
+                buffer: *const ::std::os::raw::c_void,
+                length: ::std::os::raw::c_int,
+                sent_bytes: *mut ::std::os::raw::c_int,
+            ) -> root::dmSocket::Result;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN11dmSSLSocket7ReceiveEPNS_9SSLSocketEPviPi"]
+            pub fn Receive(
+                socket: root::dmSSLSocket::Socket,
+                buffer: *mut ::std::os::raw::c_void,
+                length: ::std::os::raw::c_int,
+                received_bytes: *mut ::std::os::raw::c_int,
+            ) -> root::dmSocket::Result;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN11dmSSLSocket17SetReceiveTimeoutEPNS_9SSLSocketEm"]
+            pub fn SetReceiveTimeout(
+                socket: root::dmSSLSocket::Socket,
+                timeout: u64,
+            ) -> root::dmSocket::Result;
+        }
+    }
+    pub mod dmConnectionPool {
+        #[allow(unused_imports)]
+        use self::super::super::root;
+        #[repr(C)]
+        #[derive(Debug, Copy, Clone)]
+        pub struct ConnectionPool {
+            _unused: [u8; 0],
+        }
+        pub type HPool = *mut root::dmConnectionPool::ConnectionPool;
+        pub type HConnection = u32;
+        pub const Result_RESULT_OK: root::dmConnectionPool::Result = 0;
+        pub const Result_RESULT_OUT_OF_RESOURCES: root::dmConnectionPool::Result = -1;
+        pub const Result_RESULT_SOCKET_ERROR: root::dmConnectionPool::Result = -2;
+        pub const Result_RESULT_HANDSHAKE_FAILED: root::dmConnectionPool::Result = -3;
+        pub const Result_RESULT_SHUT_DOWN: root::dmConnectionPool::Result = -4;
+        pub type Result = ::std::os::raw::c_int;
+        #[repr(C)]
+        #[derive(Debug, Copy, Clone)]
+        pub struct Params {
+            pub m_MaxConnections: u32,
+            pub m_MaxKeepAlive: u32,
+        }
+        #[test]
+        fn bindgen_test_layout_Params() {
+            const UNINIT: ::std::mem::MaybeUninit<Params> = ::std::mem::MaybeUninit::uninit();
+            let ptr = UNINIT.as_ptr();
+            assert_eq!(
+                ::std::mem::size_of::<Params>(),
+                8usize,
+                concat!("Size of: ", stringify!(Params))
+            );
+            assert_eq!(
+                ::std::mem::align_of::<Params>(),
+                4usize,
+                concat!("Alignment of ", stringify!(Params))
+            );
+            assert_eq!(
+                unsafe { ::std::ptr::addr_of!((*ptr).m_MaxConnections) as usize - ptr as usize },
+                0usize,
+                concat!(
+                    "Offset of field: ",
+                    stringify!(Params),
+                    "::",
+                    stringify!(m_MaxConnections)
+                )
+            );
+            assert_eq!(
+                unsafe { ::std::ptr::addr_of!((*ptr).m_MaxKeepAlive) as usize - ptr as usize },
+                4usize,
+                concat!(
+                    "Offset of field: ",
+                    stringify!(Params),
+                    "::",
+                    stringify!(m_MaxKeepAlive)
+                )
+            );
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN16dmConnectionPool3NewEPKNS_6ParamsEPPNS_14ConnectionPoolE"]
+            pub fn New(
+                params: *const root::dmConnectionPool::Params,
+                pool: *mut root::dmConnectionPool::HPool,
+            ) -> root::dmConnectionPool::Result;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN16dmConnectionPool6DeleteEPNS_14ConnectionPoolE"]
+            pub fn Delete(pool: root::dmConnectionPool::HPool) -> root::dmConnectionPool::Result;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN16dmConnectionPool4DialEPNS_14ConnectionPoolEPKctbiPjPN8dmSocket6ResultE"]
+            pub fn Dial(
+                pool: root::dmConnectionPool::HPool,
+                host: *const ::std::os::raw::c_char,
+                port: u16,
+                ssl: bool,
+                timeout: ::std::os::raw::c_int,
+                connection: *mut root::dmConnectionPool::HConnection,
+                sock_res: *mut root::dmSocket::Result,
+            ) -> root::dmConnectionPool::Result;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN16dmConnectionPool4DialEPNS_14ConnectionPoolEPKctbiPiPjPN8dmSocket6ResultE"]
+            pub fn Dial1(
+                pool: root::dmConnectionPool::HPool,
+                host: *const ::std::os::raw::c_char,
+                port: u16,
+                ssl: bool,
+                timeout: ::std::os::raw::c_int,
+                cancelflag: *mut ::std::os::raw::c_int,
+                connection: *mut root::dmConnectionPool::HConnection,
+                sock_res: *mut root::dmSocket::Result,
+            ) -> root::dmConnectionPool::Result;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN16dmConnectionPool6ReturnEPNS_14ConnectionPoolEj"]
+            pub fn Return(
+                pool: root::dmConnectionPool::HPool,
+                connection: root::dmConnectionPool::HConnection,
+            );
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN16dmConnectionPool5CloseEPNS_14ConnectionPoolEj"]
+            pub fn Close(
+                pool: root::dmConnectionPool::HPool,
+                connection: root::dmConnectionPool::HConnection,
+            );
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN16dmConnectionPool9GetSocketEPNS_14ConnectionPoolEj"]
+            pub fn GetSocket(
+                pool: root::dmConnectionPool::HPool,
+                connection: root::dmConnectionPool::HConnection,
+            ) -> root::dmSocket::Socket;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN16dmConnectionPool12GetSSLSocketEPNS_14ConnectionPoolEj"]
+            pub fn GetSSLSocket(
+                pool: root::dmConnectionPool::HPool,
+                connection: root::dmConnectionPool::HConnection,
+            ) -> root::dmSSLSocket::Socket;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN16dmConnectionPool13GetReuseCountEPNS_14ConnectionPoolEj"]
+            pub fn GetReuseCount(
+                pool: root::dmConnectionPool::HPool,
+                connection: root::dmConnectionPool::HConnection,
+            ) -> u32;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN16dmConnectionPool8ShutdownEPNS_14ConnectionPoolEN8dmSocket12ShutdownTypeE"]
+            pub fn Shutdown(
+                pool: root::dmConnectionPool::HPool,
+                how: root::dmSocket::ShutdownType,
+            ) -> u32;
+        }
+    }
+    pub mod dmHttpClient {
+        #[allow(unused_imports)]
+        use self::super::super::root;
+        pub const ParseResult_PARSE_RESULT_NEED_MORE_DATA: root::dmHttpClient::ParseResult = 1;
+        pub const ParseResult_PARSE_RESULT_OK: root::dmHttpClient::ParseResult = 0;
+        pub const ParseResult_PARSE_RESULT_SYNTAX_ERROR: root::dmHttpClient::ParseResult = -1;
+        pub type ParseResult = ::std::os::raw::c_int;
+        extern "C" {
+            #[link_name = "\u{1}_ZN12dmHttpClient11ParseHeaderEPcPvbPFvS1_iiiPKcEPFvS1_S3_S3_EPFvS1_iE"]
+            pub fn ParseHeader(
+                header_str: *mut ::std::os::raw::c_char,
+                user_data: *mut ::std::os::raw::c_void,
+                end_of_receive: bool,
+                version_cbk: ::std::option::Option<
+                    unsafe extern "C" fn(
+                        user_data: *mut ::std::os::raw::c_void,
+                        major: ::std::os::raw::c_int,
+                        minor: ::std::os::raw::c_int,
+                        status: ::std::os::raw::c_int,
+                        status_str: *const ::std::os::raw::c_char,
+                    ),
+                >,
+                header_cbk: ::std::option::Option<
+                    unsafe extern "C" fn(
+                        user_data: *mut ::std::os::raw::c_void,
+                        key: *const ::std::os::raw::c_char,
+                        value: *const ::std::os::raw::c_char,
+                    ),
+                >,
+                body_cbk: ::std::option::Option<
+                    unsafe extern "C" fn(
+                        user_data: *mut ::std::os::raw::c_void,
+                        offset: ::std::os::raw::c_int,
+                    ),
+                >,
+            ) -> root::dmHttpClient::ParseResult;
+        }
+    }
+    pub mod dmCrypt {
+        #[allow(unused_imports)]
+        use self::super::super::root;
+        pub const Algorithm_ALGORITHM_XTEA: root::dmCrypt::Algorithm = 0;
+        pub type Algorithm = ::std::os::raw::c_uint;
+        pub const Result_RESULT_OK: root::dmCrypt::Result = 0;
+        pub const Result_RESULT_ERROR: root::dmCrypt::Result = 1;
+        pub type Result = ::std::os::raw::c_uint;
+        extern "C" {
+            #[link_name = "\u{1}_ZN7dmCrypt7EncryptENS_9AlgorithmEPhjPKhj"]
+            pub fn Encrypt(
+                algo: root::dmCrypt::Algorithm,
+                data: *mut u8,
+                datalen: u32,
+                key: *const u8,
+                keylen: u32,
+            ) -> root::dmCrypt::Result;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN7dmCrypt7DecryptENS_9AlgorithmEPhjPKhj"]
+            pub fn Decrypt(
+                algo: root::dmCrypt::Algorithm,
+                data: *mut u8,
+                datalen: u32,
+                key: *const u8,
+                keylen: u32,
+            ) -> root::dmCrypt::Result;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN7dmCrypt8HashSha1EPKhjPh"]
+            pub fn HashSha1(buf: *const u8, buflen: u32, digest: *mut u8);
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN7dmCrypt10HashSha256EPKhjPh"]
+            pub fn HashSha256(buf: *const u8, buflen: u32, digest: *mut u8);
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN7dmCrypt10HashSha512EPKhjPh"]
+            pub fn HashSha512(buf: *const u8, buflen: u32, digest: *mut u8);
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN7dmCrypt7HashMd5EPKhjPh"]
+            pub fn HashMd5(buf: *const u8, buflen: u32, digest: *mut u8);
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN7dmCrypt12Base64EncodeEPKhjPhPj"]
+            pub fn Base64Encode(src: *const u8, src_len: u32, dst: *mut u8, dst_len: *mut u32) -> bool;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN7dmCrypt12Base64DecodeEPKhjPhPj"]
+            pub fn Base64Decode(src: *const u8, src_len: u32, dst: *mut u8, dst_len: *mut u32) -> bool;
+        }
+    }
+    pub mod dmURI {
+        #[allow(unused_imports)]
+        use self::super::super::root;
+        pub const Result_RESULT_OK: root::dmURI::Result = 0;
+        pub const Result_RESULT_TOO_SMALL_BUFFER: root::dmURI::Result = 1;
+        pub type Result = ::std::os::raw::c_uint;
+        pub const MAX_SCHEME_LEN: u32 = 8;
+        pub const MAX_LOCATION_LEN: u32 = 64;
+        pub const MAX_PATH_LEN: u32 = 2048;
+        #[doc = " Maximum length of an URI: scheme :// location / path"]
+        pub const MAX_URI_LEN: u32 = 2124;
+        #[repr(C)]
+        #[derive(Debug, Copy, Clone)]
+        pub struct Parts {
+            #[doc = " Scheme parts, eg http"]
+            pub m_Scheme: [::std::os::raw::c_char; 8usize],
+            #[doc = " Location part, eg foo.com:80"]
+            pub m_Location: [::std::os::raw::c_char; 64usize],
+            #[doc = " Hostname part of location, eg foo.com"]
+            pub m_Hostname: [::std::os::raw::c_char; 64usize],
+            #[doc = " Port part of location, eg 80. -1 if not present"]
+            pub m_Port: ::std::os::raw::c_int,
+            #[doc = " Path part, eg index.html"]
+            pub m_Path: [::std::os::raw::c_char; 2048usize],
+        }
+        #[test]
+        fn bindgen_test_layout_Parts() {
+            const UNINIT: ::std::mem::MaybeUninit<Parts> = ::std::mem::MaybeUninit::uninit();
+            let ptr = UNINIT.as_ptr();
+            assert_eq!(
+                ::std::mem::size_of::<Parts>(),
+                2188usize,
+                concat!("Size of: ", stringify!(Parts))
+            );
+            assert_eq!(
+                ::std::mem::align_of::<Parts>(),
+                4usize,
+                concat!("Alignment of ", stringify!(Parts))
+            );
+            assert_eq!(
+                unsafe { ::std::ptr::addr_of!((*ptr).m_Scheme) as usize - ptr as usize },
+                0usize,
+                concat!("Offset of field: ", stringify!(Parts), "::", stringify!(m_Scheme))
+            );
+            assert_eq!(
+                unsafe { ::std::ptr::addr_of!((*ptr).m_Location) as usize - ptr as usize },
+                8usize,
+                concat!("Offset of field: ", stringify!(Parts), "::", stringify!(m_Location))
+            );
+            assert_eq!(
+                unsafe { ::std::ptr::addr_of!((*ptr).m_Hostname) as usize - ptr as usize },
+                72usize,
+                concat!("Offset of field: ", stringify!(Parts), "::", stringify!(m_Hostname))
+            );
+            assert_eq!(
+                unsafe { ::std::ptr::addr_of!((*ptr).m_Port) as usize - ptr as usize },
+                136usize,
+                concat!("Offset of field: ", stringify!(Parts), "::", stringify!(m_Port))
+            );
+            assert_eq!(
+                unsafe { ::std::ptr::addr_of!((*ptr).m_Path) as usize - ptr as usize },
+                140usize,
+                concat!("Offset of field: ", stringify!(Parts), "::", stringify!(m_Path))
+            );
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN5dmURI5ParseEPKcPNS_5PartsE"]
+            pub fn Parse(
+                uri: *const ::std::os::raw::c_char,
+                parts: *mut root::dmURI::Parts,
+            ) -> root::dmURI::Result;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN5dmURI6EncodeEPKcPcjPj"]
+            pub fn Encode(
+                src: *const ::std::os::raw::c_char,
+                dst: *mut ::std::os::raw::c_char,
+                dst_size: u32,
+                bytes_written: *mut u32,
+            ) -> root::dmURI::Result;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN5dmURI6DecodeEPKcPc"]
+            pub fn Decode(src: *const ::std::os::raw::c_char, dst: *mut ::std::os::raw::c_char);
+        }
+    }
+    pub type pthread_t = ::std::os::raw::c_ulong;
+    pub type pthread_key_t = ::std::os::raw::c_uint;
+    pub mod dmThread {
+        #[allow(unused_imports)]
+        use self::super::super::root;
+        pub type Thread = root::pthread_t;
+        pub type TlsKey = root::pthread_key_t;
+        pub type ThreadStart =
+            ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>;
+        extern "C" {
+            #[link_name = "\u{1}_ZN8dmThread3NewEPFvPvEjS0_PKc"]
+            pub fn New(
+                thread_start: root::dmThread::ThreadStart,
+                stack_size: u32,
+                arg: *mut ::std::os::raw::c_void,
+                name: *const ::std::os::raw::c_char,
+            ) -> root::dmThread::Thread;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN8dmThread4JoinEm"]
+            pub fn Join(thread: root::dmThread::Thread);
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN8dmThread6DetachEm"]
+            pub fn Detach(thread: root::dmThread::Thread);
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN8dmThread8AllocTlsEv"]
+            pub fn AllocTls() -> root::dmThread::TlsKey;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN8dmThread7FreeTlsEj"]
+            pub fn FreeTls(key: root::dmThread::TlsKey);
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN8dmThread11SetTlsValueEjPv"]
+            pub fn SetTlsValue(key: root::dmThread::TlsKey, value: *mut ::std::os::raw::c_void);
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN8dmThread11GetTlsValueEj"]
+            pub fn GetTlsValue(key: root::dmThread::TlsKey) -> *mut ::std::os::raw::c_void;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN8dmThread16GetCurrentThreadEv"]
+            pub fn GetCurrentThread() -> root::dmThread::Thread;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN8dmThread13SetThreadNameEmPKc"]
+            pub fn SetThreadName(
+                thread: root::dmThread::Thread,
+                name: *const ::std::os::raw::c_char,
+            );
+        }
+    }
+    pub type rmtBool = ::std::os::raw::c_uint;
+    pub type rmtU32 = ::std::os::raw::c_uint;
+    pub type rmtU64 = ::std::os::raw::c_ulonglong;
+    pub type rmtS32 = ::std::os::raw::c_int;
+    pub type rmtS64 = ::std::os::raw::c_longlong;
+    pub type rmtF32 = f32;
+    pub type rmtF64 = f64;
+    pub const rmtPropertyFlags_RMT_PropertyFlags_NoFlags: root::rmtPropertyFlags = 0;
+    pub const rmtPropertyFlags_RMT_PropertyFlags_FrameReset: root::rmtPropertyFlags = 1;
+    pub type rmtPropertyFlags = ::std::os::raw::c_uint;
+    pub const rmtPropertyType_RMT_PropertyType_rmtGroup: root::rmtPropertyType = 0;
+    pub const rmtPropertyType_RMT_PropertyType_rmtBool: root::rmtPropertyType = 1;
+    pub const rmtPropertyType_RMT_PropertyType_rmtS32: root::rmtPropertyType = 2;
+    pub const rmtPropertyType_RMT_PropertyType_rmtU32: root::rmtPropertyType = 3;
+    pub const rmtPropertyType_RMT_PropertyType_rmtF32: root::rmtPropertyType = 4;
+    pub const rmtPropertyType_RMT_PropertyType_rmtS64: root::rmtPropertyType = 5;
+    pub const rmtPropertyType_RMT_PropertyType_rmtU64: root::rmtPropertyType = 6;
+    pub const rmtPropertyType_RMT_PropertyType_rmtF64: root::rmtPropertyType = 7;
+    pub type rmtPropertyType = ::std::os::raw::c_uint;
+    #[repr(C)]
+    #[derive(Copy, Clone)]
+    pub union rmtPropertyValue {
+        pub Bool: root::rmtBool,
+        pub S32: root::rmtS32,
+        pub U32: root::rmtU32,
+        pub F32: root::rmtF32,
+        pub S64: root::rmtS64,
+        pub U64: root::rmtU64,
+        pub F64: root::rmtF64,
+    }
+    #[test]
+    fn bindgen_test_layout_rmtPropertyValue() {
+        const UNINIT: ::std::mem::MaybeUninit<rmtPropertyValue> = ::std::mem::MaybeUninit::uninit();
+        let ptr = UNINIT.as_ptr();
+        assert_eq!(
+            ::std::mem::size_of::<rmtPropertyValue>(),
+            8usize,
+            concat!("Size of: ", stringify!(rmtPropertyValue))
+        );
+        assert_eq!(
+            ::std::mem::align_of::<rmtPropertyValue>(),
+            8usize,
+            concat!("Alignment of ", stringify!(rmtPropertyValue))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).Bool) as usize - ptr as usize },
+            0usize,
+            concat!("Offset of field: ", stringify!(rmtPropertyValue), "::", stringify!(Bool))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).S32) as usize - ptr as usize },
+            0usize,
+            concat!("Offset of field: ", stringify!(rmtPropertyValue), "::", stringify!(S32))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).U32) as usize - ptr as usize },
+            0usize,
+            concat!("Offset of field: ", stringify!(rmtPropertyValue), "::", stringify!(U32))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).F32) as usize - ptr as usize },
+            0usize,
+            concat!("Offset of field: ", stringify!(rmtPropertyValue), "::", stringify!(F32))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).S64) as usize - ptr as usize },
+            0usize,
+            concat!("Offset of field: ", stringify!(rmtPropertyValue), "::", stringify!(S64))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).U64) as usize - ptr as usize },
+            0usize,
+            concat!("Offset of field: ", stringify!(rmtPropertyValue), "::", stringify!(U64))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).F64) as usize - ptr as usize },
+            0usize,
+            concat!("Offset of field: ", stringify!(rmtPropertyValue), "::", stringify!(F64))
+        );
+    }
+    #[repr(C)]
+    #[derive(Copy, Clone)]
+    pub struct rmtProperty {
+        pub initialised: root::rmtBool,
+        pub type_: root::rmtPropertyType,
+        pub flags: root::rmtPropertyFlags,
+        pub value: root::rmtPropertyValue,
+        pub lastFrameValue: root::rmtPropertyValue,
+        pub prevValue: root::rmtPropertyValue,
+        pub prevValueFrame: root::rmtU32,
+        pub name: *const ::std::os::raw::c_char,
+        pub description: *const ::std::os::raw::c_char,
+        pub defaultValue: root::rmtPropertyValue,
+        pub parent: *mut root::rmtProperty,
+        pub firstChild: *mut root::rmtProperty,
+        pub lastChild: *mut root::rmtProperty,
+        pub nextSibling: *mut root::rmtProperty,
+        pub nameHash: root::rmtU32,
+        pub uniqueID: root::rmtU32,
+    }
+    #[test]
+    fn bindgen_test_layout_rmtProperty() {
+        const UNINIT: ::std::mem::MaybeUninit<rmtProperty> = ::std::mem::MaybeUninit::uninit();
+        let ptr = UNINIT.as_ptr();
+        assert_eq!(
+            ::std::mem::size_of::<rmtProperty>(),
+            112usize,
+            concat!("Size of: ", stringify!(rmtProperty))
+        );
+        assert_eq!(
+            ::std::mem::align_of::<rmtProperty>(),
+            8usize,
+            concat!("Alignment of ", stringify!(rmtProperty))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).initialised) as usize - ptr as usize },
+            0usize,
+            concat!("Offset of field: ", stringify!(rmtProperty), "::", stringify!(initialised))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).type_) as usize - ptr as usize },
+            4usize,
+            concat!("Offset of field: ", stringify!(rmtProperty), "::", stringify!(type_))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).flags) as usize - ptr as usize },
+            8usize,
+            concat!("Offset of field: ", stringify!(rmtProperty), "::", stringify!(flags))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).value) as usize - ptr as usize },
+            16usize,
+            concat!("Offset of field: ", stringify!(rmtProperty), "::", stringify!(value))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).lastFrameValue) as usize - ptr as usize },
+            24usize,
+            concat!("Offset of field: ", stringify!(rmtProperty), "::", stringify!(lastFrameValue))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).prevValue) as usize - ptr as usize },
+            32usize,
+            concat!("Offset of field: ", stringify!(rmtProperty), "::", stringify!(prevValue))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).prevValueFrame) as usize - ptr as usize },
+            40usize,
+            concat!("Offset of field: ", stringify!(rmtProperty), "::", stringify!(prevValueFrame))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).name) as usize - ptr as usize },
+            48usize,
+            concat!("Offset of field: ", stringify!(rmtProperty), "::", stringify!(name))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).description) as usize - ptr as usize },
+            56usize,
+            concat!("Offset of field: ", stringify!(rmtProperty), "::", stringify!(description))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).defaultValue) as usize - ptr as usize },
+            64usize,
+            concat!("Offset of field: ", stringify!(rmtProperty), "::", stringify!(defaultValue))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).parent) as usize - ptr as usize },
+            72usize,
+            concat!("Offset of field: ", stringify!(rmtProperty), "::", stringify!(parent))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).firstChild) as usize - ptr as usize },
+            80usize,
+            concat!("Offset of field: ", stringify!(rmtProperty), "::", stringify!(firstChild))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).lastChild) as usize - ptr as usize },
+            88usize,
+            concat!("Offset of field: ", stringify!(rmtProperty), "::", stringify!(lastChild))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).nextSibling) as usize - ptr as usize },
+            96usize,
+            concat!("Offset of field: ", stringify!(rmtProperty), "::", stringify!(nextSibling))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).nameHash) as usize - ptr as usize },
+            104usize,
+            concat!("Offset of field: ", stringify!(rmtProperty), "::", stringify!(nameHash))
+        );
+        assert_eq!(
+            unsafe { ::std::ptr::addr_of!((*ptr).uniqueID) as usize - ptr as usize },
+            108usize,
+            concat!("Offset of field: ", stringify!(rmtProperty), "::", stringify!(uniqueID))
+        );
+    }
+    extern "C" {
+        pub fn _rmt_PropertySetValue(property: *mut root::rmtProperty);
+    }
+    extern "C" {
+        pub fn _rmt_PropertyAddValue(
+            property: *mut root::rmtProperty,
+            add_value: root::rmtPropertyValue,
+        );
+    }
+    pub mod dmProfile {
+        #[allow(unused_imports)]
+        use self::super::super::root;
+        pub type HProfile = *mut ::std::os::raw::c_void;
+        extern "C" {
+            #[link_name = "\u{1}_ZN9dmProfile10BeginFrameEv"]
+            pub fn BeginFrame() -> root::dmProfile::HProfile;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN9dmProfile8EndFrameEPv"]
+            pub fn EndFrame(profile: root::dmProfile::HProfile);
+        }
+        #[doc = " Internal, do not use."]
+        #[repr(C)]
+        #[derive(Debug, Copy, Clone)]
+        pub struct ProfileScope {
+            pub valid: ::std::os::raw::c_int,
+        }
+        #[test]
+        fn bindgen_test_layout_ProfileScope() {
+            const UNINIT: ::std::mem::MaybeUninit<ProfileScope> = ::std::mem::MaybeUninit::uninit();
+            let ptr = UNINIT.as_ptr();
+            assert_eq!(
+                ::std::mem::size_of::<ProfileScope>(),
+                4usize,
+                concat!("Size of: ", stringify!(ProfileScope))
+            );
+            assert_eq!(
+                ::std::mem::align_of::<ProfileScope>(),
+                4usize,
+                concat!("Alignment of ", stringify!(ProfileScope))
+            );
+            assert_eq!(
+                unsafe { ::std::ptr::addr_of!((*ptr).valid) as usize - ptr as usize },
+                0usize,
+                concat!("Offset of field: ", stringify!(ProfileScope), "::", stringify!(valid))
+            );
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN9dmProfile12ProfileScope10StartScopeEPKcPm"]
+            pub fn ProfileScope_StartScope(
+                this: *mut root::dmProfile::ProfileScope,
+                name: *const ::std::os::raw::c_char,
+                name_hash: *mut u64,
+            );
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN9dmProfile12ProfileScope8EndScopeEv"]
+            pub fn ProfileScope_EndScope(this: *mut root::dmProfile::ProfileScope);
+        }
+        impl ProfileScope {
+            #[inline]
+            pub unsafe fn StartScope(&mut self, name: *const ::std::os::raw::c_char, name_hash: *mut u64) {
+                ProfileScope_StartScope(self, name, name_hash)
+            }
+            #[inline]
+            pub unsafe fn EndScope(&mut self) {
+                ProfileScope_EndScope(self)
+            }
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN9dmProfile7LogTextEPKcz"]
+            pub fn LogText(text: *const ::std::os::raw::c_char, ...);
+        }
+    }
+    pub mod dmIntersection {
+        #[allow(unused_imports)]
+        use self::super::super::root;
+        pub type Plane = root::dmVMath::Vector4;
+        #[repr(C)]
+        #[repr(align(16))]
+        #[derive(Debug, Copy, Clone)]
+        pub struct Frustum {
+            pub m_Planes: [root::dmIntersection::Plane; 6usize],
+            pub m_NumPlanes: ::std::os::raw::c_int,
+        }
+        #[test]
+        fn bindgen_test_layout_Frustum() {
+            const UNINIT: ::std::mem::MaybeUninit<Frustum> = ::std::mem::MaybeUninit::uninit();
+            let ptr = UNINIT.as_ptr();
+            assert_eq!(
+                ::std::mem::size_of::<Frustum>(),
+                112usize,
+                concat!("Size of: ", stringify!(Frustum))
+            );
+            assert_eq!(
+                ::std::mem::align_of::<Frustum>(),
+                16usize,
+                concat!("Alignment of ", stringify!(Frustum))
+            );
+            assert_eq!(
+                unsafe { ::std::ptr::addr_of!((*ptr).m_Planes) as usize - ptr as usize },
+                0usize,
+                concat!("Offset of field: ", stringify!(Frustum), "::", stringify!(m_Planes))
+            );
+            assert_eq!(
+                unsafe { ::std::ptr::addr_of!((*ptr).m_NumPlanes) as usize - ptr as usize },
+                96usize,
+                concat!("Offset of field: ", stringify!(Frustum), "::", stringify!(m_NumPlanes))
+            );
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN14dmIntersection23CreateFrustumFromMatrixERKN10Vectormath3Aos7Matrix4EbiRNS_7FrustumE"]
+            pub fn CreateFrustumFromMatrix(
+                m: *const root::dmVMath::Matrix4,
+                normalize: bool,
+                num_planes: ::std::os::raw::c_int,
+                frustum: *mut root::dmIntersection::Frustum,
+            );
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN14dmIntersection15DistanceToPlaneEN10Vectormath3Aos7Vector4ENS1_6Point3E"]
+            pub fn DistanceToPlane(
+                plane: root::dmIntersection::Plane,
+                pos: root::dmVMath::Point3,
+            ) -> f32;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN14dmIntersection16TestFrustumPointERKNS_7FrustumERKN10Vectormath3Aos6Point3E"]
+            pub fn TestFrustumPoint(
+                frustum: *const root::dmIntersection::Frustum,
+                pos: *const root::dmVMath::Point3,
+            ) -> bool;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN14dmIntersection17TestFrustumSphereERKNS_7FrustumERKN10Vectormath3Aos6Point3Ef"]
+            pub fn TestFrustumSphere(
+                frustum: *const root::dmIntersection::Frustum,
+                pos: *const root::dmVMath::Point3,
+                radius: f32,
+            ) -> bool;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN14dmIntersection17TestFrustumSphereERKNS_7FrustumERKN10Vectormath3Aos7Vector4Ef"]
+            pub fn TestFrustumSphere1(
+                frustum: *const root::dmIntersection::Frustum,
+                pos: *const root::dmVMath::Vector4,
+                radius: f32,
+            ) -> bool;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN14dmIntersection19TestFrustumSphereSqERKNS_7FrustumERKN10Vectormath3Aos6Point3Ef"]
+            pub fn TestFrustumSphereSq(
+                frustum: *const root::dmIntersection::Frustum,
+                pos: *const root::dmVMath::Point3,
+                radius_sq: f32,
+            ) -> bool;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN14dmIntersection19TestFrustumSphereSqERKNS_7FrustumERKN10Vectormath3Aos7Vector4Ef"]
+            pub fn TestFrustumSphereSq1(
+                frustum: *const root::dmIntersection::Frustum,
+                pos: *const root::dmVMath::Vector4,
+                radius_sq: f32,
+            ) -> bool;
+        }
+        extern "C" {
+            #[link_name = "\u{1}_ZN14dmIntersection14TestFrustumOBBERKNS_7FrustumERKN10Vectormath3Aos7Matrix4ERNS4_7Vector3ES9_"]
+            pub fn TestFrustumOBB(
+                frustum: *const root::dmIntersection::Frustum,
+                world: *const root::dmVMath::Matrix4,
+                aabb_min: *mut root::dmVMath::Vector3,
+                aabb_max: *mut root::dmVMath::Vector3,
+            ) -> bool;
+        }
+    }
+    pub mod dmMemory {
+        #[allow(unused_imports)]
+        use self::super::super::root;
+        pub const Result_RESULT_OK: root::dmMemory::Result = 0;
+        pub const Result_RESULT_INVAL: root::dmMemory::Result = -1;
+        pub const Result_RESULT_NOMEM: root::dmMemory::Result = -2;
+        #[doc = " aligned memory allocation result\n\n Aligned memory allocation result\n\n @enum\n @name Result\n @member dmMemory::RESULT_OK 0\n @member dmMemory::RESULT_INVAL -1\n @member dmMemory::RESULT_NOMEM -2"]
+        pub type Result = ::std::os::raw::c_int;
+        extern "C" {
+            #[doc = " Allocate size bytes of uninitialized storage whose alignment is specified by alignment.\n @name AlignedMalloc\n @param memptr [type: void**] Pointer to a void* where the allocated pointer address should be stored.\n @param alignment [type: uint32_t] The alignment value, which must be an integer power of 2.\n @param size [type: uint32_t] Size of the requested memory allocation.\n @return result [type: Result] Returns RESULT_OK on success, RESULT_INVAL if alignment is not a power of 2 and RESULT_NOMEM if out of memory."]
+            #[link_name = "\u{1}_ZN8dmMemory13AlignedMallocEPPvjj"]
+            pub fn AlignedMalloc(
+                memptr: *mut *mut ::std::os::raw::c_void,
+                alignment: ::std::os::raw::c_uint,
+                size: ::std::os::raw::c_uint,
+            ) -> root::dmMemory::Result;
+        }
+        extern "C" {
+            #[doc = " Frees a block of memory that was allocated with dmMemory::AlignedMalloc\n @name AlignedFree\n @param memptr [type: void*] A pointer to the memory block that was returned by dmMemory::AlignedMalloc"]
+            #[link_name = "\u{1}_ZN8dmMemory11AlignedFreeEPv"]
+            pub fn AlignedFree(memptr: *mut ::std::os::raw::c_void);
+        }
+    }
     pub type __builtin_va_list = [root::__va_list_tag; 1usize];
     #[repr(C)]
     #[derive(Debug, Copy, Clone)]
@@ -0,0 +1,48 @@
+//! Procedural macros re-exported by the `dmsdk` crate. Use them through `dmsdk` instead of depending on this crate.
+
+use proc_macro::{Delimiter, Group, Literal, TokenStream, TokenTree};
+
+fn error(message: &str) -> TokenStream {
+    format!("compile_error!({message:?});").parse().unwrap()
+}
+
+/// Wraps the body of a function in a `dmprofile::scope!`, named after the function unless a name is given.
+#[proc_macro_attribute]
+pub fn function(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut tokens: Vec<TokenTree> = item.into_iter().collect();
+
+    let name = match attr.into_iter().collect::<Vec<_>>().as_slice() {
+        [] => {
+            let name = tokens.windows(2).find_map(|pair| match pair {
+                [TokenTree::Ident(keyword), TokenTree::Ident(name)]
+                    if keyword.to_string() == "fn" =>
+                {
+                    Some(name.to_string())
+                }
+                _ => None,
+            });
+            match name {
+                Some(name) => Literal::string(name.trim_start_matches("r#")),
+                None => return error("`#[dmprofile::function]` can only be used on functions"),
+            }
+        }
+        [TokenTree::Literal(name)] if name.to_string().starts_with('"') => name.clone(),
+        _ => return error("expected a string literal, e.g. `#[dmprofile::function(\"name\")]`"),
+    };
+
+    let body = match tokens.pop() {
+        Some(TokenTree::Group(body)) if body.delimiter() == Delimiter::Brace => body,
+        _ => return error("`#[dmprofile::function]` can only be used on functions with a body"),
+    };
+
+    let mut stream: TokenStream = format!("dmsdk::dmprofile::scope!({name});")
+        .parse()
+        .unwrap();
+    stream.extend(body.stream());
+
+    let mut wrapped = Group::new(Delimiter::Brace, stream);
+    wrapped.set_span(body.span());
+    tokens.push(TokenTree::Group(wrapped));
+
+    tokens.into_iter().collect()
+}
@@ -1,21 +1,27 @@
 use dmsdk::*;
 
-pub fn create(_config: dmconfigfile::ConfigFile) {
-    dmlog::info!("create()");
-}
+pub struct RustConfig;
 
-pub fn destroy(_config: dmconfigfile::ConfigFile) {
-    dmlog::info!("destroy()");
-}
+impl dmconfigfile::ConfigPlugin for RustConfig {
+    fn create(_config: dmconfigfile::ConfigFile) -> Self {
+        dmlog::info!("create()");
+        Self
+    }
+
+    fn destroy(&mut self, _config: dmconfigfile::ConfigFile) {
+        dmlog::info!("destroy()");
+    }
 
-pub fn get_string(
-    _config: dmconfigfile::ConfigFile,
-    key: &str,
-    _default_value: &str,
-) -> Option<String> {
-    if key == "my_section.my_value" {
-        Some("It works!".to_owned())
-    } else {
-        None
+    fn get_string(
+        &mut self,
+        _config: dmconfigfile::ConfigFile,
+        key: &str,
+        _default_value: &str,
+    ) -> Option<String> {
+        if key == "my_section.my_value" {
+            Some("It works!".to_owned())
+        } else {
+            None
+        }
     }
 }
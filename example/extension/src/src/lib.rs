@@ -1,7 +1,8 @@
-use base64::{engine::general_purpose::STANDARD as b64, Engine};
 use dmextension::{AppParams, Event, Extension, Params};
 use dmsdk::*;
 
+const LOG_DOMAIN: &str = "RUSTEXT";
+
 // LUA FUNCTIONS //
 fn lua_function(l: lua::State) -> i32 {
     dmlog::info!("Hello from Rust!");
@@ -35,8 +36,13 @@ fn read_userdata(l: lua::State) -> i32 {
 }
 
 fn b64_encode(l: lua::State) -> i32 {
-    let plaintext = lua::check_string(l, 1);
-    lua::push_string(l, &b64.encode(plaintext));
+    let plaintext = lua::check_bytes(l, 1);
+    match dmcrypt::base64_encode(&plaintext) {
+        Ok(encoded) => lua::push_string(l, &encoded),
+        Err(err) => {
+            lua::error!(l, "{err}");
+        }
+    }
 
     1
 }
@@ -97,7 +103,9 @@ fn lua_init(l: lua::State) {
     let top = lua::get_top(l);
 
     lua::register(l, "rust", LUA_FUNCTIONS);
-    lua::pop(l, 1);
+    // Adds rust.sha256(), rust.base64_decode() and friends to the same table
+    lua::register(l, "rust", dmcrypt::LUA_FUNCTIONS);
+    lua::pop(l, 2);
 
     assert_eq!(top, lua::get_top(l));
 }
@@ -115,7 +123,7 @@ impl Extension for RustExt {
     fn app_init(&mut self, params: AppParams) -> dmextension::Result {
         dmlog::info!("Cool!");
 
-        self.hid_context = Some(dmengine::get_hid_context(params));
+        self.hid_context = Some(params.hid_context());
 
         dmextension::Result::Ok
     }
@@ -153,11 +161,4 @@ declare_extension!(RustExt);
 
 mod config_extension;
 
-declare_configfile_extension!(
-    RUST_CONFIG,
-    Some(config_extension::create),
-    Some(config_extension::destroy),
-    Some(config_extension::get_string),
-    None,
-    None
-);
+declare_configfile_extension!(RUST_CONFIG, config_extension::RustConfig);